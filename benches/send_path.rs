@@ -0,0 +1,84 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use jito_grpc_client::bundle::BundleEncoder;
+use jito_grpc_client::client::JitoClient;
+use jito_grpc_client::grpc::{bundle::Bundle, searcher::SendBundleRequest};
+use jito_grpc_client::testing::MockBlockEngine;
+use prost::Message;
+use solana_keypair::{Keypair, Signer};
+use solana_program::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction::{Message as TxMessage, VersionedMessage};
+use tokio::runtime::{Builder, Runtime};
+
+// A signed memo transaction, close in size to a typical bundle transaction
+fn transaction() -> VersionedTransaction {
+    let payer = Keypair::new();
+    let ix = Instruction {
+        program_id: Pubkey::new_unique(),
+        accounts: vec![AccountMeta::new(payer.pubkey(), true)],
+        data: vec![7; 200],
+    };
+    let message = VersionedMessage::Legacy(TxMessage::new_with_blockhash(
+        &[ix],
+        Some(&payer.pubkey()),
+        &Hash::new_unique(),
+    ));
+    VersionedTransaction::try_new(message, &[payer]).unwrap()
+}
+
+fn runtime() -> Runtime {
+    Builder::new_current_thread().enable_all().build().unwrap()
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+    for count in [1, 5] {
+        let txns: Vec<_> = (0..count).map(|_| transaction()).collect();
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("bundle_create", count),
+            &txns,
+            |b, txns| b.iter(|| Bundle::create(txns).unwrap()),
+        );
+
+        let mut encoder = BundleEncoder::default();
+        group.bench_with_input(
+            BenchmarkId::new("bundle_encoder", count),
+            &txns,
+            |b, txns| b.iter(|| encoder.encode(txns).unwrap()),
+        );
+
+        group.bench_with_input(BenchmarkId::new("send_request", count), &txns, |b, txns| {
+            b.iter(|| {
+                let request = SendBundleRequest {
+                    bundle: Some(Bundle::create(txns).unwrap()),
+                };
+                request.encode_to_vec()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn round_trip(c: &mut Criterion) {
+    let runtime = runtime();
+    let mock = runtime.block_on(MockBlockEngine::start()).unwrap();
+    let client = runtime
+        .block_on(JitoClient::new(mock.endpoint(), None))
+        .unwrap();
+    let client = tokio::sync::Mutex::new(client);
+    let txns: Vec<_> = (0..5).map(|_| transaction()).collect();
+
+    c.bench_function("mock_send_round_trip", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { client.lock().await.send(&txns).await.unwrap() })
+    });
+}
+
+criterion_group!(benches, serialization, round_trip);
+criterion_main!(benches);
@@ -1,15 +1,49 @@
+use std::path::{Path, PathBuf};
+
+const PROTOS: [&str; 7] = [
+    "searcher.proto",
+    "bundle.proto",
+    "packet.proto",
+    "shared.proto",
+    "auth.proto",
+    "block_engine.proto",
+    "relayer.proto",
+];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     unsafe { std::env::set_var("PROTOC", protobuf_src::protoc()) };
+    // JITO_PROTO_DIR compiles against another checkout of the Jito protos instead of the vendored revision
+    println!("cargo:rerun-if-env-changed=JITO_PROTO_DIR");
+    let dir = std::env::var_os("JITO_PROTO_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("proto"));
+    println!("cargo:rerun-if-changed={}", dir.display());
+    println!("cargo:rustc-env=JITO_PROTO_REVISION={}", revision(&dir));
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").ok_or("OUT_DIR not set")?);
+    // tonic's transport, which the generated `connect` constructors and servers are built on, is only enabled by the `native` feature.
+    // Without it, such as for wasm32 with the `web` feature, only the transport-generic clients are generated
+    let native = std::env::var_os("CARGO_FEATURE_NATIVE").is_some();
     tonic_prost_build::configure()
-        .build_server(false)
+        // The server side is used by the mock block engine, which is also compiled for the crate's own tests without the `testing`
+        // feature, and build scripts can't tell a test build apart
+        .build_server(native)
+        .build_transport(native)
+        .bytes(".packet.Packet.data")
+        // Checked against what the client expects by `protos::verify`
+        .file_descriptor_set_path(out_dir.join("jito_descriptors.bin"))
         .compile_protos(
-            &[
-                "proto/searcher.proto",
-                "proto/bundle.proto",
-                "proto/packet.proto",
-                "proto/shared.proto",
-            ],
-            &["proto"],
+            &PROTOS.map(|proto| dir.join(proto)),
+            std::slice::from_ref(&dir),
         )?;
     Ok(())
 }
+
+// Revision recorded by scripts/refresh-protos.sh, or "unknown" for a checkout without one
+fn revision(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join("REVISION"))
+        .ok()
+        .and_then(|revision| revision.lines().next().map(|line| line.trim().to_string()))
+        .filter(|revision| !revision.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,44 @@
+/* C interface of jito-grpc-client, built with the `ffi` feature. See src/ffi.rs. */
+#ifndef JITO_GRPC_CLIENT_H
+#define JITO_GRPC_CLIENT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define JITO_OUTCOME_UNKNOWN 0
+#define JITO_OUTCOME_ACCEPTED 1
+#define JITO_OUTCOME_REJECTED 2
+#define JITO_OUTCOME_FINALIZED 3
+#define JITO_OUTCOME_PROCESSED 4
+#define JITO_OUTCOME_DROPPED 5
+
+/* Smallest `uuid_cap` accepted by jito_client_send: a 36-character uuid and its NUL. */
+#define JITO_UUID_CAP 37
+
+typedef struct JitoFfiClient JitoFfiClient;
+
+/* Message of the last error on this thread, or NULL. */
+const char *jito_last_error(void);
+
+/* Connects to `endpoint`, or the fastest region if NULL. A timeout of 0 uses the default. Returns NULL on failure. */
+JitoFfiClient *jito_client_new(const char *endpoint, uint64_t timeout_ms);
+
+/* Frees a client. NULL is ignored. */
+void jito_client_free(JitoFfiClient *client);
+
+/* Sends `count` bincode-serialized transactions. Writes the NUL-terminated bundle uuid to `uuid_out`, which must hold at least JITO_UUID_CAP bytes. Returns 0 or -1. */
+int jito_client_send(JitoFfiClient *client, const uint8_t *const *txns, const size_t *lens, size_t count,
+                     char *uuid_out, size_t uuid_cap);
+
+/* Waits for the result of bundle `uuid`. Returns a JITO_OUTCOME_* value, or -1 on failure or timeout. */
+int jito_client_wait_result(JitoFfiClient *client, const char *uuid, uint64_t timeout_ms);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,121 @@
+use ::jito_grpc_client::client::JitoClient as Client;
+use ::jito_grpc_client::errors::JitoClientError;
+use ::jito_grpc_client::api::BundleOutcome;
+use ::jito_grpc_client::grpc::bundle::BundleResult;
+use ::jito_grpc_client::nodes::NodeRegion;
+use ::jito_grpc_client::tracker::BundleTracker;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use solana_transaction::versioned::VersionedTransaction;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+fn to_py_err(e: JitoClientError) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+// Name of the bundle result's outcome, such as "accepted" or "dropped"
+fn outcome(result: &BundleResult) -> &'static str {
+    result.outcome().as_ref().map_or("unknown", BundleOutcome::name)
+}
+
+/// Client to send bundles to a Jito block engine.
+///
+/// JitoClient(endpoint=None, timeout_ms=2000) connects to the given endpoint URL, or the fastest region if none is given.
+#[pyclass]
+struct JitoClient {
+    runtime: Arc<Runtime>,
+    client: Client,
+}
+
+#[pymethods]
+impl JitoClient {
+    #[new]
+    #[pyo3(signature = (endpoint=None, timeout_ms=2000))]
+    fn new(py: Python<'_>, endpoint: Option<String>, timeout_ms: u64) -> PyResult<Self> {
+        let runtime = Arc::new(Runtime::new()?);
+        let mut builder = Client::builder().timeout(Duration::from_millis(timeout_ms));
+        if let Some(endpoint) = endpoint {
+            builder = builder.endpoint_owned(endpoint);
+        }
+        let client = py
+            .detach(|| runtime.block_on(builder.build()))
+            .map_err(to_py_err)?;
+        Ok(Self { runtime, client })
+    }
+
+    /// The endpoint URL the client is connected to.
+    #[getter]
+    fn endpoint(&self) -> &'static str {
+        self.client.get_endpoint()
+    }
+
+    /// Sends a bundle of bincode-serialized versioned transactions, returning the bundle uuid.
+    fn send(&mut self, py: Python<'_>, transactions: Vec<Vec<u8>>) -> PyResult<String> {
+        let transactions = transactions
+            .iter()
+            .map(|bytes| bincode::deserialize::<VersionedTransaction>(bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let runtime = self.runtime.clone();
+        let client = &mut self.client;
+        py.detach(|| runtime.block_on(client.send(&transactions)))
+            .map_err(to_py_err)
+    }
+
+    /// Subscribes to the bundle results of this client, returning a `BundleResults` handle.
+    fn subscribe_bundle_results(&mut self, py: Python<'_>) -> PyResult<BundleResults> {
+        let runtime = self.runtime.clone();
+        let client = &mut self.client;
+        let tracker = py
+            .detach(|| runtime.block_on(client.tracker()))
+            .map_err(to_py_err)?;
+        Ok(BundleResults {
+            runtime: self.runtime.clone(),
+            tracker,
+        })
+    }
+}
+
+/// Bundle results received by a `JitoClient`, awaited by bundle uuid.
+#[pyclass]
+struct BundleResults {
+    runtime: Arc<Runtime>,
+    tracker: Arc<BundleTracker>,
+}
+
+#[pymethods]
+impl BundleResults {
+    /// Waits for the result of the bundle with the given uuid, returning its outcome such as "accepted" or "dropped".
+    #[pyo3(signature = (uuid, timeout_ms=30000))]
+    fn wait(&self, py: Python<'_>, uuid: &str, timeout_ms: u64) -> PyResult<&'static str> {
+        let result = py
+            .detach(|| {
+                self.runtime.block_on(
+                    self.tracker
+                        .await_result(uuid, Duration::from_millis(timeout_ms)),
+                )
+            })
+            .map_err(to_py_err)?;
+        Ok(outcome(&result))
+    }
+}
+
+/// Measures the latency to every region, returning the fastest region's name and its latency in milliseconds.
+#[pyfunction]
+fn measure_latency(py: Python<'_>) -> PyResult<(String, u128)> {
+    let runtime = Runtime::new()?;
+    let (region, latency) = py
+        .detach(|| runtime.block_on(NodeRegion::measure_latency()))
+        .map_err(to_py_err)?;
+    Ok((region.to_string(), latency.as_millis()))
+}
+
+#[pymodule]
+fn jito_grpc_client(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<JitoClient>()?;
+    m.add_class::<BundleResults>()?;
+    m.add_function(wrap_pyfunction!(measure_latency, m)?)?;
+    Ok(())
+}
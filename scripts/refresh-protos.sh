@@ -0,0 +1,19 @@
+#!/bin/sh
+# Fetches the Jito protos at a revision of jito-labs/mev-protos into a directory, recording the revision in its REVISION file.
+#
+#   scripts/refresh-protos.sh <revision> [directory]
+#
+# Refresh the vendored protos with the default directory `proto`, or fetch into another directory and build against it with
+# `JITO_PROTO_DIR=<directory> cargo build`. Either way, `protos::verify` checks the result still matches what the client expects.
+set -eu
+
+revision=${1:?usage: refresh-protos.sh <revision> [directory]}
+dir=${2:-proto}
+base="https://raw.githubusercontent.com/jito-labs/mev-protos/$revision"
+
+mkdir -p "$dir"
+for proto in searcher bundle packet shared auth block_engine relayer; do
+    curl -fsSL "$base/$proto.proto" -o "$dir/$proto.proto"
+done
+echo "$revision" > "$dir/REVISION"
+echo "Fetched Jito protos $revision into $dir"
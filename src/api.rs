@@ -0,0 +1,432 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::expiry::DEFAULT_SLOT_DURATION;
+use crate::grpc::bundle::{
+    self, BundleResult, DroppedReason, bundle_result::Result as ResultKind, rejected::Reason,
+};
+use crate::grpc::searcher::{
+    ConnectedLeadersRegionedResponse, ConnectedLeadersResponse, GetRegionsResponse,
+    GetTipAccountsResponse, NextScheduledLeaderResponse,
+};
+use solana_pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub type Slot = u64;
+
+/// The next scheduled leader connected to the block engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextLeader {
+    /// The current slot the block engine is on
+    pub current_slot: Slot,
+    /// The slot of the next leader
+    pub slot: Slot,
+    /// The identity of the next leader
+    pub identity: Pubkey,
+    /// The block engine region of the next leader
+    pub region: String,
+}
+
+impl NextLeader {
+    /// Returns the number of slots until the leader's slot, 0 if it already started.
+    pub fn slots_until(&self) -> u64 {
+        self.slot.saturating_sub(self.current_slot)
+    }
+
+    /// Returns the estimated time until the leader's slot starts, estimated from the average slot time.
+    pub fn time_until(&self) -> Duration {
+        DEFAULT_SLOT_DURATION.saturating_mul(u32::try_from(self.slots_until()).unwrap_or(u32::MAX))
+    }
+}
+
+impl TryFrom<NextScheduledLeaderResponse> for NextLeader {
+    type Error = JitoClientError;
+
+    fn try_from(response: NextScheduledLeaderResponse) -> JitoClientResult<Self> {
+        Ok(Self {
+            current_slot: response.current_slot,
+            slot: response.next_leader_slot,
+            identity: parse_pubkey(&response.next_leader_identity)?,
+            region: response.next_leader_region,
+        })
+    }
+}
+
+/// Leader slots of the Jito validators connected to a block engine for the current epoch, keyed by validator identity.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectedLeaders(pub HashMap<Pubkey, Vec<Slot>>);
+
+impl ConnectedLeaders {
+    /// Returns the connected validator that is leader for `slot`, if any.
+    pub fn leader_at(&self, slot: Slot) -> Option<Pubkey> {
+        self.0
+            .iter()
+            .find(|(_, slots)| slots.contains(&slot))
+            .map(|(identity, _)| *identity)
+    }
+
+    /// Returns the first leader slot at or after `slot`, along with its validator.
+    pub fn next_leader_slot(&self, slot: Slot) -> Option<(Slot, Pubkey)> {
+        self.0
+            .iter()
+            .filter_map(|(identity, slots)| {
+                slots
+                    .iter()
+                    .filter(|s| **s >= slot)
+                    .min()
+                    .map(|s| (*s, *identity))
+            })
+            .min_by_key(|(s, _)| *s)
+    }
+}
+
+impl TryFrom<ConnectedLeadersResponse> for ConnectedLeaders {
+    type Error = JitoClientError;
+
+    fn try_from(response: ConnectedLeadersResponse) -> JitoClientResult<Self> {
+        let mut leaders = HashMap::with_capacity(response.connected_validators.len());
+        for (identity, slots) in response.connected_validators {
+            leaders.insert(parse_pubkey(&identity)?, slots.slots);
+        }
+        Ok(Self(leaders))
+    }
+}
+
+/// Connected leaders for each requested block engine region, keyed by region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectedLeadersRegioned(pub HashMap<String, ConnectedLeaders>);
+
+impl TryFrom<ConnectedLeadersRegionedResponse> for ConnectedLeadersRegioned {
+    type Error = JitoClientError;
+
+    fn try_from(response: ConnectedLeadersRegionedResponse) -> JitoClientResult<Self> {
+        let mut regions = HashMap::with_capacity(response.connected_validators.len());
+        for (region, leaders) in response.connected_validators {
+            regions.insert(region, leaders.try_into()?);
+        }
+        Ok(Self(regions))
+    }
+}
+
+/// The tip accounts searchers shall transfer funds to for the leader to claim.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TipAccounts(pub Vec<Pubkey>);
+
+impl TryFrom<GetTipAccountsResponse> for TipAccounts {
+    type Error = JitoClientError;
+
+    fn try_from(response: GetTipAccountsResponse) -> JitoClientResult<Self> {
+        let accounts = response
+            .accounts
+            .iter()
+            .map(|account| parse_pubkey(account))
+            .collect::<JitoClientResult<_>>()?;
+        Ok(Self(accounts))
+    }
+}
+
+/// The region the client is connected to, along with all regions that are online.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Regions {
+    pub current: String,
+    pub available: Vec<String>,
+}
+
+impl From<GetRegionsResponse> for Regions {
+    fn from(response: GetRegionsResponse) -> Self {
+        Self {
+            current: response.current_region,
+            available: response.available_regions,
+        }
+    }
+}
+
+/// Outcome of a bundle, decoded from a bundle result of the result stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleOutcome {
+    /// Accepted by the block engine and forwarded to a validator. A bundle forwarded to several validators has several accepted results
+    Accepted { slot: Slot, validator: Pubkey },
+    /// Rejected by the block engine
+    Rejected(RejectionReason),
+    /// Reached the processed commitment level
+    Processed {
+        slot: Slot,
+        validator: Pubkey,
+        /// Index of the bundle within the block
+        bundle_index: u64,
+    },
+    /// Reached the finalized commitment level
+    Finalized,
+    /// Forwarded by the block engine but never landed on-chain
+    Dropped(DroppedReason),
+}
+
+/// Why the block engine rejected a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// The bid was not high enough to win its state auction
+    StateAuctionBidRejected {
+        auction_id: String,
+        simulated_bid_lamports: u64,
+        msg: Option<String>,
+    },
+    /// The bid won its state auction, but was too low relative to the other winners to be forwarded
+    WinningBatchBidRejected {
+        auction_id: String,
+        simulated_bid_lamports: u64,
+        msg: Option<String>,
+    },
+    /// A transaction failed simulation
+    SimulationFailure {
+        tx_signature: String,
+        msg: Option<String>,
+    },
+    /// The block engine failed internally
+    InternalError(String),
+    /// The bundle was dropped, for example because no leader is upcoming
+    Dropped(String),
+    /// The block engine sent no reason
+    Unspecified,
+}
+
+impl BundleOutcome {
+    /// Returns whether the bundle landed on-chain.
+    pub fn landed(&self) -> bool {
+        matches!(self, Self::Processed { .. } | Self::Finalized)
+    }
+
+    /// Returns the name of the outcome, such as `accepted` or `dropped`, without its details.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Accepted { .. } => "accepted",
+            Self::Rejected(_) => "rejected",
+            Self::Processed { .. } => "processed",
+            Self::Finalized => "finalized",
+            Self::Dropped(_) => "dropped",
+        }
+    }
+}
+
+impl TryFrom<&BundleResult> for BundleOutcome {
+    type Error = JitoClientError;
+
+    fn try_from(result: &BundleResult) -> JitoClientResult<Self> {
+        let result = result
+            .result
+            .as_ref()
+            .ok_or(JitoClientError::MalformedResponse(
+                "bundle result without outcome",
+            ))?;
+        Ok(match result {
+            ResultKind::Accepted(accepted) => Self::Accepted {
+                slot: accepted.slot,
+                validator: parse_pubkey(&accepted.validator_identity)?,
+            },
+            ResultKind::Rejected(rejected) => Self::Rejected(rejected.into()),
+            ResultKind::Processed(processed) => Self::Processed {
+                slot: processed.slot,
+                validator: parse_pubkey(&processed.validator_identity)?,
+                bundle_index: processed.bundle_index,
+            },
+            ResultKind::Finalized(_) => Self::Finalized,
+            ResultKind::Dropped(dropped) => Self::Dropped(
+                DroppedReason::try_from(dropped.reason)
+                    .map_err(|_| JitoClientError::MalformedResponse("unknown dropped reason"))?,
+            ),
+        })
+    }
+}
+
+impl From<&bundle::Rejected> for RejectionReason {
+    fn from(rejected: &bundle::Rejected) -> Self {
+        match &rejected.reason {
+            Some(Reason::StateAuctionBidRejected(r)) => Self::StateAuctionBidRejected {
+                auction_id: r.auction_id.clone(),
+                simulated_bid_lamports: r.simulated_bid_lamports,
+                msg: r.msg.clone(),
+            },
+            Some(Reason::WinningBatchBidRejected(r)) => Self::WinningBatchBidRejected {
+                auction_id: r.auction_id.clone(),
+                simulated_bid_lamports: r.simulated_bid_lamports,
+                msg: r.msg.clone(),
+            },
+            Some(Reason::SimulationFailure(r)) => Self::SimulationFailure {
+                tx_signature: r.tx_signature.clone(),
+                msg: r.msg.clone(),
+            },
+            Some(Reason::InternalError(r)) => Self::InternalError(r.msg.clone()),
+            Some(Reason::DroppedBundle(r)) => Self::Dropped(r.msg.clone()),
+            None => Self::Unspecified,
+        }
+    }
+}
+
+impl BundleResult {
+    /// Decodes the outcome of the bundle.
+    ///
+    /// # Errors
+    /// This function will return an error if the result has no outcome, an unknown dropped reason, or an invalid validator identity.
+    pub fn outcome(&self) -> JitoClientResult<BundleOutcome> {
+        self.try_into()
+    }
+}
+
+impl fmt::Display for BundleOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accepted { slot, validator } => {
+                write!(f, "accepted in slot {slot} by {validator}")
+            }
+            Self::Rejected(reason) => write!(f, "rejected: {reason}"),
+            Self::Processed {
+                slot, validator, ..
+            } => write!(f, "processed in slot {slot} by {validator}"),
+            Self::Finalized => write!(f, "finalized"),
+            Self::Dropped(reason) => write!(f, "dropped: {}", reason.as_str_name()),
+        }
+    }
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Appends the block engine's message, if it sent one
+        let msg =
+            |msg: &Option<String>| msg.as_ref().map(|m| format!(" ({m})")).unwrap_or_default();
+        match self {
+            Self::StateAuctionBidRejected {
+                auction_id,
+                simulated_bid_lamports,
+                msg: m,
+            } => write!(
+                f,
+                "bid of {simulated_bid_lamports} lamports lost state auction {auction_id}{}",
+                msg(m)
+            ),
+            Self::WinningBatchBidRejected {
+                auction_id,
+                simulated_bid_lamports,
+                msg: m,
+            } => write!(
+                f,
+                "bid of {simulated_bid_lamports} lamports won state auction {auction_id} but lost the batch{}",
+                msg(m)
+            ),
+            Self::SimulationFailure {
+                tx_signature,
+                msg: m,
+            } => write!(f, "simulation failed for {tx_signature}{}", msg(m)),
+            Self::InternalError(m) => write!(f, "internal error: {m}"),
+            Self::Dropped(m) => write!(f, "dropped: {m}"),
+            Self::Unspecified => write!(f, "no reason given"),
+        }
+    }
+}
+
+fn parse_pubkey(value: &str) -> JitoClientResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|_| JitoClientError::InvalidPubkey(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::searcher::SlotList;
+
+    #[test]
+    fn convert_connected_leaders() {
+        let identity = Pubkey::new_unique();
+        let response = ConnectedLeadersResponse {
+            connected_validators: HashMap::from([(
+                identity.to_string(),
+                SlotList {
+                    slots: vec![10, 11, 12, 13],
+                },
+            )]),
+        };
+        let leaders = ConnectedLeaders::try_from(response).unwrap();
+        assert_eq!(leaders.leader_at(11), Some(identity));
+        assert_eq!(leaders.leader_at(14), None);
+        assert_eq!(leaders.next_leader_slot(5), Some((10, identity)));
+    }
+
+    #[test]
+    fn time_until_next_leader() {
+        let mut leader = NextLeader {
+            current_slot: 100,
+            slot: 105,
+            identity: Pubkey::new_unique(),
+            region: "ny".to_string(),
+        };
+        assert_eq!(leader.slots_until(), 5);
+        assert_eq!(leader.time_until(), Duration::from_secs(2));
+
+        leader.current_slot = 106;
+        assert_eq!(leader.time_until(), Duration::ZERO);
+    }
+
+    #[test]
+    fn decode_bundle_outcomes() {
+        let validator = Pubkey::new_unique();
+        let result = |result| BundleResult {
+            bundle_id: "uuid".to_string(),
+            result: Some(result),
+        };
+
+        let accepted = result(ResultKind::Accepted(bundle::Accepted {
+            slot: 7,
+            validator_identity: validator.to_string(),
+        }));
+        assert_eq!(
+            accepted.outcome().unwrap(),
+            BundleOutcome::Accepted { slot: 7, validator }
+        );
+
+        let rejected = result(ResultKind::Rejected(bundle::Rejected {
+            reason: Some(Reason::SimulationFailure(bundle::SimulationFailure {
+                tx_signature: "sig".to_string(),
+                msg: Some("insufficient funds".to_string()),
+            })),
+        }));
+        let outcome = rejected.outcome().unwrap();
+        assert!(!outcome.landed());
+        assert_eq!(outcome.name(), "rejected");
+        assert_eq!(
+            outcome.to_string(),
+            "rejected: simulation failed for sig (insufficient funds)"
+        );
+
+        let dropped = result(ResultKind::Dropped(bundle::Dropped {
+            reason: DroppedReason::PartiallyProcessed as i32,
+        }));
+        assert_eq!(
+            dropped.outcome().unwrap(),
+            BundleOutcome::Dropped(DroppedReason::PartiallyProcessed)
+        );
+        assert!(
+            result(ResultKind::Finalized(bundle::Finalized {}))
+                .outcome()
+                .unwrap()
+                .landed()
+        );
+
+        let empty = BundleResult {
+            bundle_id: "uuid".to_string(),
+            result: None,
+        };
+        assert!(matches!(
+            empty.outcome(),
+            Err(JitoClientError::MalformedResponse(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_pubkey_is_rejected() {
+        let response = GetTipAccountsResponse {
+            accounts: vec!["not a pubkey".to_string()],
+        };
+        assert!(matches!(
+            TipAccounts::try_from(response),
+            Err(JitoClientError::InvalidPubkey(_))
+        ));
+    }
+}
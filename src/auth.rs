@@ -0,0 +1,789 @@
+use crate::clock::{ClockSkew, ClockSkewEstimator};
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::files::atomic_write;
+use crate::grpc::auth::{
+    GenerateAuthChallengeRequest, GenerateAuthTokensRequest, RefreshAccessTokenRequest, Role,
+    Token, auth_service_client::AuthServiceClient,
+};
+use crate::signing::{RequestSigner, SigningRequest};
+use solana_keypair::{Keypair, Signer};
+use solana_pubkey::Pubkey;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tokio::time;
+use tonic::metadata::{AsciiMetadataValue, MetadataMap};
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+// Tokens are refreshed this long before they expire
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+// Wait time before retrying a failed refresh
+const REFRESH_RETRY: Duration = Duration::from_secs(1);
+
+/// An auth token issued by the block engine, along with its expiry time on the block engine's clock.
+#[derive(Debug, Clone)]
+pub struct AuthToken {
+    pub value: String,
+    pub expires_at: SystemTime,
+}
+
+impl AuthToken {
+    fn from_proto(token: Option<Token>) -> JitoClientResult<Self> {
+        let token = token.ok_or(JitoClientError::AuthError("missing token in response"))?;
+        let expires_at = token
+            .expires_at_utc
+            .and_then(|ts| SystemTime::try_from(ts).ok())
+            .ok_or(JitoClientError::AuthError("missing token expiry in response"))?;
+        Ok(Self {
+            value: token.value,
+            expires_at,
+        })
+    }
+
+    /// Returns true if the token has expired, or is about to, by the local clock.
+    ///
+    /// A host clock off by more than the refresh margin misjudges this, see `is_expired_with` and `Authenticator::with_clock_skew`.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_with(None)
+    }
+
+    /// Returns true if the token has expired, or is about to, judging the block engine's current time with `skew`, or by the local clock if `None`.
+    pub fn is_expired_with(&self, skew: Option<ClockSkew>) -> bool {
+        self.time_to_refresh(skew).is_zero()
+    }
+
+    // Returns how long until the token should be refreshed, on the block engine's clock
+    fn time_to_refresh(&self, skew: Option<ClockSkew>) -> Duration {
+        let now = skew.map_or_else(SystemTime::now, |skew| skew.remote_now());
+        self.expires_at
+            .duration_since(now)
+            .unwrap_or_default()
+            .saturating_sub(REFRESH_MARGIN)
+    }
+}
+
+/// Persists auth tokens across restarts, so a restarted client can reuse unexpired tokens instead of authenticating from scratch.
+///
+/// The `Authenticator` calls the store on tokio's blocking thread pool, so implementations may block on IO.
+pub trait TokenStore: Send + Sync {
+    /// Returns the access and refresh tokens last saved for `pubkey`, if any.
+    fn load(&self, pubkey: &Pubkey) -> JitoClientResult<Option<(AuthToken, AuthToken)>>;
+
+    /// Saves the access and refresh tokens of `pubkey`, replacing any saved before.
+    fn save(
+        &self,
+        pubkey: &Pubkey,
+        access: &AuthToken,
+        refresh: &AuthToken,
+    ) -> JitoClientResult<()>;
+}
+
+/// Token store keeping the tokens of every keypair in one file.
+///
+/// The file is line based, one tab-separated record per keypair: `<pubkey> <access token> <expiry unix ms> <refresh token> <expiry unix ms>`.
+/// It is replaced atomically on every save, and created readable by its owner only on unix.
+pub struct FileTokenStore {
+    path: PathBuf,
+    // Serializes saves, which rewrite the whole file
+    lock: Mutex<()>,
+}
+
+impl FileTokenStore {
+    /// Stores tokens in the file at `path`, which is created on the first save.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    // Returns every record in the file, or none if it doesn't exist yet
+    fn records(&self) -> JitoClientResult<Vec<String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(contents.lines().map(str::to_string).collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(JitoClientError::TokenStoreError(e)),
+        }
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self, pubkey: &Pubkey) -> JitoClientResult<Option<(AuthToken, AuthToken)>> {
+        let pubkey = pubkey.to_string();
+        for record in self.records()? {
+            let fields: Vec<&str> = record.split('\t').collect();
+            let [key, access, access_ms, refresh, refresh_ms] = fields.as_slice() else {
+                debug!("Skipping malformed token record");
+                continue;
+            };
+            if *key != pubkey {
+                continue;
+            }
+            let (Ok(access_ms), Ok(refresh_ms)) = (access_ms.parse(), refresh_ms.parse()) else {
+                continue;
+            };
+            return Ok(Some((
+                AuthToken {
+                    value: access.to_string(),
+                    expires_at: UNIX_EPOCH + Duration::from_millis(access_ms),
+                },
+                AuthToken {
+                    value: refresh.to_string(),
+                    expires_at: UNIX_EPOCH + Duration::from_millis(refresh_ms),
+                },
+            )));
+        }
+        Ok(None)
+    }
+
+    fn save(
+        &self,
+        pubkey: &Pubkey,
+        access: &AuthToken,
+        refresh: &AuthToken,
+    ) -> JitoClientResult<()> {
+        let _lock = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let pubkey = pubkey.to_string();
+        let mut records = self.records()?;
+        records.retain(|record| record.split('\t').next() != Some(pubkey.as_str()));
+        records.push(
+            [
+                pubkey,
+                access.value.clone(),
+                unix_millis(access.expires_at).to_string(),
+                refresh.value.clone(),
+                unix_millis(refresh.expires_at).to_string(),
+            ]
+            .join("\t"),
+        );
+
+        atomic_write(&self.path, &records.join("\n"), true)
+            .map_err(JitoClientError::TokenStoreError)
+    }
+}
+
+// Runs a token store call on the blocking thread pool
+async fn blocking<T: Send + 'static>(
+    call: impl FnOnce() -> JitoClientResult<T> + Send + 'static,
+) -> JitoClientResult<T> {
+    tokio::task::spawn_blocking(call)
+        .await
+        .map_err(|e| JitoClientError::TokenStoreError(std::io::Error::other(e)))?
+}
+
+/// Obtains and refreshes auth tokens from a block engine's auth service, by signing a challenge with the client's keypair.
+///
+/// Token expiry is judged on the block engine's clock, estimated from the `date` header of the auth service's responses. Share the estimator
+/// with a client of the same block engine with `with_clock_skew`.
+#[derive(Clone)]
+pub struct Authenticator {
+    client: AuthServiceClient<Channel>,
+    keypair: Arc<Keypair>,
+    role: Role,
+    access: Arc<RwLock<AuthToken>>,
+    refresh: Arc<RwLock<AuthToken>>,
+    store: Option<Arc<dyn TokenStore>>,
+    clock: ClockSkewEstimator,
+}
+
+impl Authenticator {
+    /// Authenticates over `channel` with the given keypair and role, obtaining the initial access and refresh tokens.
+    ///
+    /// # Errors
+    /// This function will return an error if the challenge or token requests fail, for example if the keypair is not approved for the role.
+    pub async fn connect(
+        channel: Channel,
+        keypair: Arc<Keypair>,
+        role: Role,
+    ) -> JitoClientResult<Self> {
+        let mut client = AuthServiceClient::new(channel);
+        let clock = ClockSkewEstimator::new();
+        let (access, refresh) = Self::generate_tokens(&mut client, &keypair, role, &clock).await?;
+        Ok(Self {
+            client,
+            keypair,
+            role,
+            access: Arc::new(RwLock::new(access)),
+            refresh: Arc::new(RwLock::new(refresh)),
+            store: None,
+            clock,
+        })
+    }
+
+    /// Authenticates like `connect`, but reuses the tokens saved in `store` if the refresh token is still valid, skipping the challenge/response round trip.
+    ///
+    /// Saved tokens are checked against the local clock, as no block engine time is known before the first request.
+    ///
+    /// Tokens are saved to `store` whenever they are obtained or refreshed. A store that fails to load or save is logged and otherwise ignored.
+    ///
+    /// # Errors
+    /// This function will return an error if the saved tokens are unusable and the challenge or token requests fail.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{auth::{Authenticator, FileTokenStore}, errors::JitoClientResult, grpc::auth::Role};
+    /// # use solana_keypair::Keypair;
+    /// # use std::sync::Arc;
+    /// # async fn example(channel: tonic::transport::Channel, keypair: Arc<Keypair>) -> JitoClientResult<()> {
+    /// let store = Arc::new(FileTokenStore::new("jito-tokens.tsv"));
+    /// let authenticator = Authenticator::connect_with_store(channel, keypair, Role::Searcher, store).await?;
+    /// let interceptor = authenticator.interceptor();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect_with_store(
+        channel: Channel,
+        keypair: Arc<Keypair>,
+        role: Role,
+        store: Arc<dyn TokenStore>,
+    ) -> JitoClientResult<Self> {
+        let stored = blocking({
+            let (store, pubkey) = (store.clone(), keypair.pubkey());
+            move || store.load(&pubkey)
+        })
+        .await
+        .unwrap_or_else(|e| {
+            debug!("Token store load error: {e}");
+            None
+        });
+        let Some((access, refresh)) = stored.filter(|(_, refresh)| !refresh.is_expired()) else {
+            let authenticator = Self {
+                store: Some(store),
+                ..Self::connect(channel, keypair, role).await?
+            };
+            authenticator.persist().await;
+            return Ok(authenticator);
+        };
+
+        let mut authenticator = Self {
+            client: AuthServiceClient::new(channel),
+            keypair,
+            role,
+            access: Arc::new(RwLock::new(access)),
+            refresh: Arc::new(RwLock::new(refresh)),
+            store: Some(store),
+            clock: ClockSkewEstimator::new(),
+        };
+        if authenticator.access_token().is_expired() {
+            authenticator.refresh().await?;
+        }
+        Ok(authenticator)
+    }
+
+    /// Returns an interceptor that attaches the current access token to every request.
+    pub fn interceptor(&self) -> AuthInterceptor {
+        AuthInterceptor {
+            access: self.access.clone(),
+        }
+    }
+
+    /// Shares `clock` as the estimator of the block engine's clock token expiry is judged on, such as `JitoClient::clock_skew` of a client of
+    /// the same block engine. The auth service's responses keep feeding it.
+    pub fn with_clock_skew(mut self, clock: ClockSkewEstimator) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the estimator of the block engine's clock token expiry is judged on.
+    pub fn clock_skew(&self) -> ClockSkewEstimator {
+        self.clock.clone()
+    }
+
+    /// Returns the current access token.
+    pub fn access_token(&self) -> AuthToken {
+        read(&self.access)
+    }
+
+    /// Returns the pubkey of the keypair authenticating.
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    /// Refreshes the access token. If the refresh token itself has expired, authenticates from scratch instead.
+    pub async fn refresh(&mut self) -> JitoClientResult<()> {
+        let refresh = read(&self.refresh);
+        if refresh.is_expired_with(self.clock.estimate()) {
+            let (access, refresh) =
+                Self::generate_tokens(&mut self.client, &self.keypair, self.role, &self.clock)
+                    .await?;
+            write(&self.access, access);
+            write(&self.refresh, refresh);
+            self.persist().await;
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        let response = self
+            .client
+            .refresh_access_token(RefreshAccessTokenRequest {
+                refresh_token: refresh.value,
+            })
+            .await?;
+        self.clock.record_response(response.metadata(), start);
+        let response = response.into_inner();
+        write(&self.access, AuthToken::from_proto(response.access_token)?);
+        self.persist().await;
+        Ok(())
+    }
+
+    // Saves the current tokens to the store, if there is one, off the async runtime
+    async fn persist(&self) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+        let (pubkey, access, refresh) = (
+            self.keypair.pubkey(),
+            read(&self.access),
+            read(&self.refresh),
+        );
+        if let Err(e) = blocking(move || store.save(&pubkey, &access, &refresh)).await {
+            debug!("Token store save error: {e}");
+        }
+    }
+
+    /// Spawns a background task that refreshes the access token shortly before it expires. Failed refreshes are logged and retried.
+    pub fn spawn_refresh_task(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let skew = self.clock.estimate();
+                time::sleep(read(&self.access).time_to_refresh(skew)).await;
+                if let Err(e) = self.refresh().await {
+                    debug!("Auth refresh error: {e}");
+                    time::sleep(REFRESH_RETRY).await;
+                }
+            }
+        })
+    }
+
+    // Performs the challenge/response flow, returning the access and refresh tokens
+    async fn generate_tokens(
+        client: &mut AuthServiceClient<Channel>,
+        keypair: &Keypair,
+        role: Role,
+        clock: &ClockSkewEstimator,
+    ) -> JitoClientResult<(AuthToken, AuthToken)> {
+        let pubkey = keypair.pubkey();
+        let challenge = client
+            .generate_auth_challenge(GenerateAuthChallengeRequest {
+                role: role as i32,
+                pubkey: pubkey.to_bytes().to_vec(),
+            })
+            .await?
+            .into_inner()
+            .challenge;
+
+        // The server expects the challenge prefixed with the base58 pubkey
+        let challenge = format!("{pubkey}-{challenge}");
+        let signed_challenge = keypair.sign_message(challenge.as_bytes());
+        let start = Instant::now();
+        let response = client
+            .generate_auth_tokens(GenerateAuthTokensRequest {
+                challenge,
+                client_pubkey: pubkey.to_bytes().to_vec(),
+                signed_challenge: signed_challenge.as_ref().to_vec(),
+            })
+            .await?;
+        clock.record_response(response.metadata(), start);
+        let response = response.into_inner();
+
+        Ok((
+            AuthToken::from_proto(response.access_token)?,
+            AuthToken::from_proto(response.refresh_token)?,
+        ))
+    }
+}
+
+/// Authenticates several approved searcher keypairs against one block engine, each with its own tokens, and rotates between them.
+///
+/// Install it on a client with `JitoClientBuilder::authenticator_pool`, which rotates as set by `KeyRotation`. Otherwise, rotate per connection
+/// by giving each connection its own `connection_interceptor`, or per rate-limit event by sharing one `interceptor` and calling
+/// `rotate_on_rate_limit` with the errors of failed requests.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{auth::AuthenticatorPool, errors::JitoClientResult, grpc::auth::Role};
+/// # use solana_keypair::Keypair;
+/// # use std::sync::Arc;
+/// # async fn example(channel: tonic::transport::Channel, keypairs: Vec<Arc<Keypair>>) -> JitoClientResult<()> {
+/// let pool = AuthenticatorPool::connect(channel, keypairs, Role::Searcher).await?;
+/// pool.spawn_refresh_tasks();
+///
+/// let interceptor = pool.interceptor();
+/// // ... on a failed request ...
+/// # let error = jito_grpc_client::errors::JitoClientError::Cancelled;
+/// pool.rotate_on_rate_limit(&error);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AuthenticatorPool {
+    authenticators: Arc<Vec<Authenticator>>,
+    current: Arc<AtomicUsize>,
+    next_connection: Arc<AtomicUsize>,
+}
+
+impl fmt::Debug for AuthenticatorPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthenticatorPool")
+            .field("keypairs", &self.authenticators.len())
+            .field("current", &self.current())
+            .finish()
+    }
+}
+
+/// How a client built with `JitoClientBuilder::authenticator_pool` rotates between the pool's keypairs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyRotation {
+    /// Each client built with the pool authenticates with the next keypair in turn, and keeps it
+    PerConnection,
+    /// Clients share the pool's current keypair, and switch every client of the pool to the next one when the block engine rate limits a send
+    #[default]
+    OnRateLimit,
+}
+
+impl AuthenticatorPool {
+    /// Authenticates every keypair over `channel` with the given role.
+    ///
+    /// # Errors
+    /// This function will return an error if no keypair is given, or any keypair fails to authenticate.
+    pub async fn connect(
+        channel: Channel,
+        keypairs: Vec<Arc<Keypair>>,
+        role: Role,
+    ) -> JitoClientResult<Self> {
+        if keypairs.is_empty() {
+            return Err(JitoClientError::AuthError("no keypairs to authenticate"));
+        }
+        let mut authenticators = Vec::with_capacity(keypairs.len());
+        for keypair in keypairs {
+            authenticators.push(Authenticator::connect(channel.clone(), keypair, role).await?);
+        }
+        Ok(Self::from_authenticators(authenticators))
+    }
+
+    /// Authenticates every keypair like `Authenticator::connect_with_store`, sharing one token store between them.
+    ///
+    /// # Errors
+    /// This function will return an error if no keypair is given, or any keypair without usable saved tokens fails to authenticate.
+    pub async fn connect_with_store(
+        channel: Channel,
+        keypairs: Vec<Arc<Keypair>>,
+        role: Role,
+        store: Arc<dyn TokenStore>,
+    ) -> JitoClientResult<Self> {
+        if keypairs.is_empty() {
+            return Err(JitoClientError::AuthError("no keypairs to authenticate"));
+        }
+        let mut authenticators = Vec::with_capacity(keypairs.len());
+        for keypair in keypairs {
+            authenticators.push(
+                Authenticator::connect_with_store(channel.clone(), keypair, role, store.clone())
+                    .await?,
+            );
+        }
+        Ok(Self::from_authenticators(authenticators))
+    }
+
+    fn from_authenticators(authenticators: Vec<Authenticator>) -> Self {
+        Self {
+            authenticators: Arc::new(authenticators),
+            current: Arc::new(AtomicUsize::new(0)),
+            next_connection: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns an interceptor that attaches the access token of the current keypair, following every rotation.
+    pub fn interceptor(&self) -> RotatingAuthInterceptor {
+        RotatingAuthInterceptor {
+            access: self
+                .authenticators
+                .iter()
+                .map(|a| a.access.clone())
+                .collect(),
+            current: self.current.clone(),
+        }
+    }
+
+    /// Returns an interceptor bound to the next keypair in turn, so each connection authenticates with a different keypair.
+    pub fn connection_interceptor(&self) -> AuthInterceptor {
+        let next = self.next_connection.fetch_add(1, Ordering::Relaxed);
+        self.authenticators[next % self.authenticators.len()].interceptor()
+    }
+
+    /// Returns the pubkey of the keypair currently used by `interceptor`.
+    pub fn current(&self) -> Pubkey {
+        self.authenticators[self.current.load(Ordering::Relaxed) % self.authenticators.len()]
+            .pubkey()
+    }
+
+    /// Switches `interceptor` to the next keypair, returning its pubkey.
+    pub fn rotate(&self) -> Pubkey {
+        let next = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        self.authenticators[next % self.authenticators.len()].pubkey()
+    }
+
+    /// Switches to the next keypair if `error` is a rate limit, returning whether it did.
+    pub fn rotate_on_rate_limit(&self, error: &JitoClientError) -> bool {
+        if !error.is_rate_limited() {
+            return false;
+        }
+        let pubkey = self.rotate();
+        debug!("Rate limited, rotated auth to {pubkey}");
+        true
+    }
+
+    /// Returns the authenticator of every keypair, in the order they were given.
+    pub fn authenticators(&self) -> &[Authenticator] {
+        &self.authenticators
+    }
+
+    /// Spawns a refresh task per keypair, see `Authenticator::spawn_refresh_task`.
+    pub fn spawn_refresh_tasks(&self) -> Vec<JoinHandle<()>> {
+        self.authenticators
+            .iter()
+            .map(|a| a.clone().spawn_refresh_task())
+            .collect()
+    }
+}
+
+/// Interceptor attaching the `authorization` bearer token to outgoing requests.
+///
+/// Also a `RequestSigner`, to authenticate a `JitoClient` with `JitoClientBuilder::request_signer`.
+#[derive(Clone)]
+pub struct AuthInterceptor {
+    access: Arc<RwLock<AuthToken>>,
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        authorize(request.metadata_mut(), &self.access)?;
+        Ok(request)
+    }
+}
+
+impl RequestSigner for AuthInterceptor {
+    fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status> {
+        authorize(request.metadata_mut(), &self.access)
+    }
+}
+
+/// Interceptor attaching the bearer token of an `AuthenticatorPool`'s current keypair to outgoing requests.
+#[derive(Clone)]
+pub struct RotatingAuthInterceptor {
+    access: Vec<Arc<RwLock<AuthToken>>>,
+    current: Arc<AtomicUsize>,
+}
+
+impl Interceptor for RotatingAuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        authorize(request.metadata_mut(), self.current())?;
+        Ok(request)
+    }
+}
+
+impl RequestSigner for RotatingAuthInterceptor {
+    fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status> {
+        authorize(request.metadata_mut(), self.current())
+    }
+}
+
+impl RotatingAuthInterceptor {
+    fn current(&self) -> &RwLock<AuthToken> {
+        &self.access[self.current.load(Ordering::Relaxed) % self.access.len()]
+    }
+}
+
+fn authorize(metadata: &mut MetadataMap, access: &RwLock<AuthToken>) -> Result<(), Status> {
+    let token = read(access).value;
+    let value = AsciiMetadataValue::try_from(format!("Bearer {token}"))
+        .map_err(|_| Status::unauthenticated("Invalid auth token"))?;
+    metadata.insert("authorization", value);
+    Ok(())
+}
+
+fn unix_millis(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn read(token: &RwLock<AuthToken>) -> AuthToken {
+    token.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn write(token: &RwLock<AuthToken>, value: AuthToken) {
+    *token.write().unwrap_or_else(|e| e.into_inner()) = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::JitoClientBuilder;
+    use crate::testing::{MockBlockEngine, MockFault};
+
+    #[test]
+    fn interceptor_attaches_bearer_token() {
+        let token = AuthToken {
+            value: "abc".to_string(),
+            expires_at: SystemTime::now() + Duration::from_secs(600),
+        };
+        assert!(!token.is_expired());
+
+        let mut interceptor = AuthInterceptor {
+            access: Arc::new(RwLock::new(token)),
+        };
+        let request = interceptor.call(Request::new(())).unwrap();
+        assert_eq!(
+            request.metadata().get("authorization").unwrap(),
+            "Bearer abc"
+        );
+    }
+
+    // A pool of keypairs holding the given access tokens, without an auth service behind it
+    fn pool(tokens: [&str; 2]) -> AuthenticatorPool {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let authenticators = tokens
+            .map(|value| {
+                let token = AuthToken {
+                    value: value.to_string(),
+                    expires_at: SystemTime::now() + Duration::from_secs(600),
+                };
+                Authenticator {
+                    client: AuthServiceClient::new(channel.clone()),
+                    keypair: Arc::new(Keypair::new()),
+                    role: Role::Searcher,
+                    access: Arc::new(RwLock::new(token.clone())),
+                    refresh: Arc::new(RwLock::new(token)),
+                    store: None,
+                    clock: ClockSkewEstimator::new(),
+                }
+            })
+            .to_vec();
+        AuthenticatorPool::from_authenticators(authenticators)
+    }
+
+    #[tokio::test]
+    async fn pool_rotates_on_rate_limit() {
+        let pool = pool(["first", "second"]);
+        let mut interceptor = pool.interceptor();
+        let bearer = |interceptor: &mut RotatingAuthInterceptor| {
+            let request = interceptor.call(Request::new(())).unwrap();
+            request.metadata().get("authorization").unwrap().clone()
+        };
+        assert_eq!(bearer(&mut interceptor), "Bearer first");
+
+        assert!(!pool.rotate_on_rate_limit(&JitoClientError::Cancelled));
+        let rate_limited = JitoClientError::SendError(Status::resource_exhausted("rate limited"));
+        assert!(pool.rotate_on_rate_limit(&rate_limited));
+        assert_eq!(pool.current(), pool.authenticators()[1].pubkey());
+        assert_eq!(bearer(&mut interceptor), "Bearer second");
+
+        pool.rotate();
+        assert_eq!(bearer(&mut interceptor), "Bearer first");
+    }
+
+    #[tokio::test]
+    async fn clients_rotate_their_pool() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let bearer = || {
+            mock.last_metadata()
+                .unwrap()
+                .get("authorization")
+                .unwrap()
+                .clone()
+        };
+        let pool = pool(["first", "second"]);
+
+        let mut client = JitoClientBuilder::new()
+            .endpoint(mock.endpoint())
+            .authenticator_pool(pool.clone(), KeyRotation::OnRateLimit)
+            .build()
+            .await
+            .unwrap();
+        client.send(&[]).await.unwrap();
+        assert_eq!(bearer(), "Bearer first");
+        mock.inject(MockFault::RateLimited {
+            retry_after: Duration::from_secs(1),
+        });
+        assert!(client.send(&[]).await.unwrap_err().is_rate_limited());
+        client.send(&[]).await.unwrap();
+        assert_eq!(bearer(), "Bearer second");
+
+        // Each client built per connection keeps its own keypair, whatever the pool's current one
+        let builder = JitoClientBuilder::new()
+            .endpoint(mock.endpoint())
+            .authenticator_pool(pool, KeyRotation::PerConnection);
+        for expected in ["Bearer first", "Bearer second"] {
+            let mut client = builder.clone().build().await.unwrap();
+            client.send(&[]).await.unwrap();
+            assert_eq!(bearer(), expected);
+        }
+    }
+
+    #[test]
+    fn file_store_round_trips_tokens() {
+        let path = std::env::temp_dir().join(format!(
+            "jito-tokens-{}-{}.tsv",
+            std::process::id(),
+            unix_millis(SystemTime::now())
+        ));
+        let store = FileTokenStore::new(&path);
+        let token = |value: &str, secs| AuthToken {
+            value: value.to_string(),
+            expires_at: UNIX_EPOCH + Duration::from_secs(secs),
+        };
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(store.load(&first).unwrap().is_none());
+
+        store
+            .save(&first, &token("a1", 100), &token("r1", 200))
+            .unwrap();
+        store
+            .save(&second, &token("a2", 100), &token("r2", 200))
+            .unwrap();
+        store
+            .save(&first, &token("a3", 300), &token("r3", 400))
+            .unwrap();
+
+        let (access, refresh) = store.load(&first).unwrap().unwrap();
+        assert_eq!(access.value, "a3");
+        assert_eq!(refresh.expires_at, UNIX_EPOCH + Duration::from_secs(400));
+        assert_eq!(store.load(&second).unwrap().unwrap().0.value, "a2");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn token_near_expiry_is_expired() {
+        let token = AuthToken {
+            value: "abc".to_string(),
+            expires_at: SystemTime::now() + Duration::from_secs(10),
+        };
+        assert!(token.is_expired());
+    }
+
+    #[test]
+    fn expiry_is_judged_on_the_block_engine_clock() {
+        let token = AuthToken {
+            value: "abc".to_string(),
+            expires_at: SystemTime::now() + Duration::from_secs(120),
+        };
+        assert!(!token.is_expired());
+
+        // The block engine's clock runs 100s ahead of the local one
+        let clock = ClockSkewEstimator::new();
+        let now = SystemTime::now();
+        clock.record_round_trip(
+            now,
+            Duration::ZERO,
+            now + Duration::from_secs(100),
+            Duration::ZERO,
+        );
+        assert!(token.is_expired_with(clock.estimate()));
+    }
+}
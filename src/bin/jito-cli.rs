@@ -0,0 +1,166 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use clap::{Parser, Subcommand};
+use jito_grpc_client::client::JitoClient;
+use jito_grpc_client::errors::{JitoClientError, JitoClientResult};
+use jito_grpc_client::nodes::PingConfig;
+use jito_grpc_client::region::{Network, NodeRegion};
+use jito_grpc_client::rpc::SolanaRpc;
+use solana_transaction::versioned::VersionedTransaction;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Diagnostic tool for Jito block engines.
+#[derive(Parser)]
+#[command(name = "jito-cli", version)]
+struct Cli {
+    /// Block engine endpoint URL. The fastest region is used if not set
+    #[arg(long, global = true)]
+    endpoint: Option<String>,
+    /// Connection and request timeout in milliseconds
+    #[arg(long, global = true, default_value_t = 2000)]
+    timeout_ms: u64,
+    /// Network whose block engines to use: mainnet, testnet or devnet
+    #[arg(long, global = true, default_value = "mainnet")]
+    network: Network,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Measures the latency to every region
+    PingRegions,
+    /// Times the DNS lookup, TCP connect, TLS handshake and first request to the endpoint, or to every region if no endpoint is set
+    Diagnose,
+    /// Lists the tip accounts
+    TipAccounts,
+    /// Sends a bundle read from a JSON file holding an array of base64-encoded transactions
+    SendBundle {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Looks up a landed bundle with the block engine's getBundleStatuses JSON-RPC method. If it has not landed, waits for its next result
+    /// over a new result subscription, which only sees results of bundles sent by this client's identity from when it is opened
+    BundleStatus {
+        uuid: String,
+        /// Time to wait for the next result of a bundle that has not landed, in milliseconds. Zero only looks up landed bundles
+        #[arg(long, default_value_t = 30000)]
+        wait_ms: u64,
+    },
+    /// Shows the next scheduled leader connected to the block engine
+    NextLeader {
+        /// Regions to consider, by name. Only the connected region is considered if none are given
+        #[arg(long)]
+        region: Vec<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> JitoClientResult<()> {
+    let timeout = Duration::from_millis(cli.timeout_ms);
+    if let Command::PingRegions = cli.command {
+        let config = PingConfig {
+            timeout,
+            network: cli.network,
+            ..PingConfig::default()
+        };
+        for (region, result) in NodeRegion::measure_all(&config).await {
+            let region = region.to_string();
+            match result {
+                Ok(latency) => println!("{region:<16}{} ms", latency.as_millis()),
+                Err(e) => println!("{region:<16}{e}"),
+            }
+        }
+        return Ok(());
+    }
+    if let Command::Diagnose = cli.command {
+        let endpoints: Vec<String> = match cli.endpoint {
+            Some(endpoint) => vec![endpoint],
+            None => NodeRegion::all()
+                .iter()
+                .filter_map(|region| region.endpoint_on(cli.network))
+                .map(str::to_string)
+                .collect(),
+        };
+        for endpoint in endpoints {
+            let builder = JitoClient::builder()
+                .timeout(timeout)
+                .endpoint_owned(endpoint.as_str());
+            match builder.diagnose().await {
+                Ok(diagnosis) => println!("{diagnosis}"),
+                Err(e) => println!("{endpoint}: {e}"),
+            }
+        }
+        return Ok(());
+    }
+
+    let mut builder = JitoClient::builder().timeout(timeout).network(cli.network);
+    if let Some(endpoint) = cli.endpoint {
+        builder = builder.endpoint_owned(endpoint);
+    }
+    let mut client = builder.build().await?;
+
+    match cli.command {
+        Command::PingRegions | Command::Diagnose => unreachable!("handled before connecting"),
+        Command::TipAccounts => {
+            for account in client.get_tip_accounts().await?.0 {
+                println!("{account}");
+            }
+        }
+        Command::SendBundle { file } => {
+            let transactions = read_transactions(&file)?;
+            println!("{}", client.send(&transactions).await?);
+        }
+        Command::BundleStatus { uuid, wait_ms } => {
+            let block_engine = SolanaRpc::block_engine(client.get_endpoint());
+            if let Some(landed) = block_engine.get_bundle_statuses(&[&uuid]).await?.remove(0) {
+                let status = landed.confirmation_status.as_deref().unwrap_or("unknown");
+                println!("Landed in slot {} ({status}): {}", landed.slot, landed.err);
+                return client.shutdown();
+            }
+            if wait_ms == 0 {
+                println!("Not landed");
+                return client.shutdown();
+            }
+            let tracker = client.tracker().await?;
+            let result = tracker
+                .await_result(&uuid, Duration::from_millis(wait_ms))
+                .await?;
+            println!("{:?}", result.result);
+        }
+        Command::NextLeader { region } => {
+            let regions: Vec<&str> = region.iter().map(String::as_str).collect();
+            let leader = client.get_next_leader(&regions).await?;
+            println!("Current slot:  {}", leader.current_slot);
+            println!("Leader slot:   {}", leader.slot);
+            println!("Leader:        {}", leader.identity);
+            println!("Leader region: {}", leader.region);
+        }
+    }
+    client.shutdown()
+}
+
+fn read_transactions(file: &PathBuf) -> JitoClientResult<Vec<VersionedTransaction>> {
+    let invalid = |e: String| JitoClientError::ConfigError(format!("{}: {e}", file.display()));
+    let json = std::fs::read_to_string(file).map_err(|e| invalid(e.to_string()))?;
+    let encoded: Vec<String> = serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+    encoded
+        .iter()
+        .map(|txn| {
+            let bytes = STANDARD.decode(txn).map_err(|e| invalid(e.to_string()))?;
+            Ok(bincode::deserialize(&bytes)?)
+        })
+        .collect()
+}
@@ -0,0 +1,116 @@
+//! Synchronous wrappers for applications without an async runtime.
+
+use crate::builder::JitoClientBuilder;
+use crate::client::{self, RetryLogic};
+use crate::errors::{JitoClientError, JitoClientResult};
+#[cfg(feature = "latency")]
+use crate::region::NodeRegion;
+use solana_transaction::versioned::VersionedTransaction;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+/// A blocking `JitoClient` that owns the runtime driving its connection.
+///
+/// Must not be used from within an async runtime, since the blocking calls would block the runtime's threads.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{blocking::JitoClient, errors::JitoClientResult};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None)?;
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// let uuid = client.send(&transactions)?;
+/// println!("Bundle ID: {}", uuid);
+/// # Ok(())
+/// # }
+/// ```
+pub struct JitoClient {
+    inner: client::JitoClient,
+    runtime: Runtime,
+}
+
+impl JitoClient {
+    #[cfg(feature = "latency")]
+    /// Creates a new client that connects to the fastest region. See `client::JitoClient::new_dynamic_region`.
+    pub fn new_dynamic_region(timeout: Option<Duration>) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new();
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        Self::from_builder(builder)
+    }
+
+    /// Creates a new client that connects to the specified endpoint. See `client::JitoClient::new`.
+    pub fn new(endpoint: &'static str, timeout: Option<Duration>) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new().endpoint(endpoint);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        Self::from_builder(builder)
+    }
+
+    /// Creates a new client from a configured builder.
+    pub fn from_builder(builder: JitoClientBuilder) -> JitoClientResult<Self> {
+        // One worker thread keeps the connection serviced between calls
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(JitoClientError::RuntimeError)?;
+        let inner = runtime.block_on(builder.build())?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Sends a bundle of transactions, blocking until the block engine responds. See `client::JitoClient::send`.
+    pub fn send(&mut self, transactions: &[VersionedTransaction]) -> JitoClientResult<String> {
+        self.runtime.block_on(self.inner.send(transactions))
+    }
+
+    /// Sends a bundle of transactions with automatic retries. See `client::JitoClient::send_with_retry`.
+    pub fn send_with_retry(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+    ) -> JitoClientResult<String> {
+        self.runtime
+            .block_on(self.inner.send_with_retry(transactions, retry_logic))
+    }
+
+    /// Exercises the connection with a lightweight request. See `client::JitoClient::warm_up`.
+    pub fn warm_up(&mut self) -> JitoClientResult<Duration> {
+        self.runtime.block_on(self.inner.warm_up())
+    }
+
+    /// Returns the endpoint URL that this client is currently connected to.
+    pub fn get_endpoint(&self) -> &'static str {
+        self.inner.get_endpoint()
+    }
+
+    /// Shuts the client down cleanly. See `client::JitoClient::shutdown`.
+    pub fn shutdown(self) -> JitoClientResult<()> {
+        self.inner.shutdown()
+    }
+}
+
+#[cfg(feature = "latency")]
+/// Blocking version of `NodeRegion::measure_latency`.
+pub fn measure_latency() -> JitoClientResult<(NodeRegion, Duration)> {
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(JitoClientError::RuntimeError)?;
+    runtime.block_on(NodeRegion::measure_latency())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_endpoint_returns_error() {
+        let client = JitoClient::new("https://127.0.0.1:1", Some(Duration::from_millis(200)));
+        assert!(matches!(client, Err(JitoClientError::GRPCError(_))));
+    }
+}
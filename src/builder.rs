@@ -0,0 +1,853 @@
+use crate::auth::{AuthenticatorPool, KeyRotation};
+use crate::client::ResponseMetadata;
+use crate::client::{InFlightLimit, JitoClient, RetryLogic};
+use crate::connector::{self, EstablishedConnector, IpPreference, Proxy};
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::searcher::GetTipAccountsRequest;
+#[cfg(feature = "latency")]
+use crate::grpc::searcher::searcher_service_client::SearcherServiceClient;
+use crate::health::Diagnosis;
+#[cfg(feature = "latency")]
+use crate::nodes::{LatencySnapshot, LatencyStore, PingConfig};
+use crate::protos;
+use crate::region::{Network, NodeRegion, RegionOverrides};
+use crate::resolver::Resolver;
+#[cfg(feature = "latency")]
+use crate::selection::{Fastest, RegionSelectionPolicy};
+use crate::signing::{RequestSigner, SearcherClient, SignerInterceptor};
+use crate::stats::LatencyStats;
+pub(crate) use crate::transport::normalized;
+pub use crate::transport::{ChannelLimits, TlsRoots, USER_AGENT, normalize_endpoint};
+use crate::transport::{ConnectOptions, intern};
+#[cfg(feature = "latency")]
+use crate::warm::{WarmChannel, WarmChannels};
+#[cfg(feature = "latency")]
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use tonic::codegen::http::Uri;
+use tonic::transport::{Certificate, Channel, Endpoint};
+
+// Keep-alive interval of warm channels, unless set with `keep_alive`
+#[cfg(feature = "latency")]
+const WARM_KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+// Latencies of the reachable regions measured to pick the region, and the unused connections opened by a gRPC probe
+type Measurement = (Vec<(NodeRegion, Duration)>, Vec<(&'static str, Channel)>);
+
+#[cfg(feature = "latency")]
+/// How the latency to each region is measured when connecting to the fastest region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LatencyProbe {
+    /// DNS resolution and a TCP connect. Cheap, but blind to TLS, HTTP/2 and server processing time
+    #[default]
+    TcpConnect,
+    /// Connects to every region with the client's connection settings and times a `GetTipAccounts` request over each connection.
+    /// Reflects the latency of real requests, and the connection to the fastest region is kept for the client
+    GrpcRoundTrip,
+}
+
+/// Configures and connects a `JitoClient`.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{builder::{JitoClientBuilder, TlsRoots}, errors::JitoClientResult};
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let client = JitoClientBuilder::new()
+///     .endpoint("https://ny.mainnet.block-engine.jito.wtf:443")
+///     .connect_timeout(Duration::from_secs(2))
+///     .request_timeout(Duration::from_secs(1))
+///     .tls_roots(TlsRoots::WebPki)
+///     .build()
+///     .await?;
+///
+/// // A private block engine behind a proxy, with a certificate signed by a private CA
+/// let client = JitoClientBuilder::new()
+///     .endpoint("https://10.0.0.5:443")
+///     .tls_roots(TlsRoots::Custom)
+///     .ca_certificate(std::fs::read("ca.pem").unwrap())
+///     .tls_domain_name("block-engine.internal")
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct JitoClientBuilder {
+    endpoint: Option<&'static str>,
+    options: ConnectOptions,
+    max_decoding_message_size: Option<usize>,
+    max_encoding_message_size: Option<usize>,
+    stats: Option<LatencyStats>,
+    #[cfg(feature = "latency")]
+    probe: LatencyProbe,
+    #[cfg(feature = "latency")]
+    cache: Option<LatencyCache>,
+    #[cfg(feature = "latency")]
+    selection: Option<Arc<dyn RegionSelectionPolicy>>,
+    #[cfg(feature = "latency")]
+    warm_regions: usize,
+    #[cfg(feature = "latency")]
+    reevaluate_region: bool,
+    overrides: RegionOverrides,
+    network: Network,
+    max_in_flight: Option<(usize, Option<Duration>)>,
+    signer: SignerInterceptor,
+    auth_pool: Option<(AuthenticatorPool, KeyRotation)>,
+    connect_retry: Option<RetryLogic>,
+}
+
+#[cfg(feature = "latency")]
+// Store of region latencies, and how long its measurements are used before measuring again
+#[derive(Clone)]
+struct LatencyCache {
+    store: Arc<dyn LatencyStore>,
+    max_age: Duration,
+}
+
+#[cfg(feature = "latency")]
+impl fmt::Debug for LatencyCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyCache")
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JitoClientBuilder {
+    /// Creates a builder that connects to the fastest region with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connects to the given endpoint URL instead of the fastest region.
+    ///
+    /// The URL is validated and completed by `normalize_endpoint` when the client is built, so a bare host such as `ny.mainnet.block-engine.jito.wtf` is accepted.
+    pub fn endpoint(mut self, endpoint: &'static str) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Connects to the given endpoint URL like `endpoint`, from a URL known only at runtime, such as one read from the command line.
+    ///
+    /// Clients keep their endpoint for their whole lifetime, so each distinct URL is copied once and kept for the rest of the process,
+    /// however many clients are built for it.
+    pub fn endpoint_owned(self, endpoint: impl Into<String>) -> Self {
+        self.endpoint(intern(endpoint.into()))
+    }
+
+    /// Sets both the connect timeout and the request timeout.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.connect_timeout(timeout).request_timeout(timeout)
+    }
+
+    /// Sets the time allowed to establish the connection, including the TLS handshake. Defaults to 2 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.options.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the deadline of each request, such as sending a bundle. Defaults to 2 seconds.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.options.request_timeout = timeout;
+        self
+    }
+
+    /// Sets where the TLS root certificates come from. Defaults to the operating system's certificate store.
+    pub fn tls_roots(mut self, tls_roots: TlsRoots) -> Self {
+        self.options.tls_roots = tls_roots;
+        self
+    }
+
+    /// Trusts an additional CA certificate, given in PEM format, on top of the selected TLS roots.
+    ///
+    /// Needed for private Jito-compatible block engines or TLS-terminating proxies with certificates signed by a private CA.
+    pub fn ca_certificate(mut self, pem: impl AsRef<[u8]>) -> Self {
+        self.options
+            .ca_certificates
+            .push(Certificate::from_pem(pem));
+        self
+    }
+
+    /// Overrides the domain name used for SNI and to verify the server certificate, which otherwise is the endpoint's host.
+    pub fn tls_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.options.domain_name = Some(domain_name.into());
+        self
+    }
+
+    /// Tunnels the connection through an HTTP CONNECT or SOCKS5 proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.options.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets how host names are resolved, for the connection and for measuring region latency. Ignored for the connection when a proxy is set.
+    pub fn resolver(mut self, resolver: Resolver) -> Self {
+        self.options.resolver = Some(resolver);
+        self
+    }
+
+    /// Sets which IP address family to prefer when a host resolves to both, for the connection and for measuring region latency. Ignored for the connection when a proxy is set.
+    pub fn ip_preference(mut self, preference: IpPreference) -> Self {
+        self.options.ip_preference = preference;
+        self
+    }
+
+    /// Appends `agent`, such as the bot's name and version, to the `user-agent` header identifying the crate and its version on every request.
+    ///
+    /// Lets Jito support correlate the traffic of a firm, and the firm tell its bots apart in proxy logs. The header is `jito-grpc-client/<version> <agent>`,
+    /// followed by tonic's own version. Connecting fails with `InvalidMetadata` if `agent` is not a valid header value.
+    pub fn user_agent(mut self, agent: impl Into<String>) -> Self {
+        self.options.user_agent = Some(agent.into());
+        self
+    }
+
+    /// Keeps an idle connection alive by sending an HTTP/2 PING and enabling TCP keep-alive probes every `interval`.
+    ///
+    /// Prevents NATs and load balancers from silently dropping the connection between rare sends. A PING that isn't answered within the request timeout closes the connection,
+    /// so the next request reconnects instead of hanging on a dead connection. Disabled by default.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.options.keep_alive = Some(interval);
+        self
+    }
+
+    /// Bounds the resources the channel consumes: requests in flight, request rate and buffered requests. See `ChannelLimits`.
+    ///
+    /// Unlike `max_in_flight`, which fails sends waiting too long, the channel limits only make requests wait. They apply to every request of the channel,
+    /// and to the channels of warm regions and gRPC latency probes. Building fails with `ConfigError` if a limit is 0.
+    pub fn channel_limits(mut self, limits: ChannelLimits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    /// Sets the maximum size of a response message, such as a large streamed update. Defaults to tonic's 4 MB limit.
+    pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+        self.max_decoding_message_size = Some(limit);
+        self
+    }
+
+    /// Sets the maximum size of a request message, such as a large bundle. Unlimited by default.
+    pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+        self.max_encoding_message_size = Some(limit);
+        self
+    }
+
+    /// Shares send latency statistics with the client, such as those of a previous client returned by `JitoClient::region_stats`.
+    ///
+    /// When connecting to the fastest region, regions with recorded sends are ranked by their average send latency. As sends are gRPC round trips,
+    /// the other regions are then measured with `LatencyProbe::GrpcRoundTrip` whatever the configured probe, so every region is ranked by a gRPC round trip.
+    pub fn latency_stats(mut self, stats: LatencyStats) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    #[cfg(feature = "latency")]
+    /// Sets how the latency to each region is measured when connecting to the fastest region. Defaults to `LatencyProbe::TcpConnect`.
+    pub fn latency_probe(mut self, probe: LatencyProbe) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    #[cfg(feature = "latency")]
+    /// Sets how the region is picked from the measured latencies, such as spreading clients over regions with `WeightedRandom`. Defaults to `Fastest`.
+    pub fn region_selection(mut self, policy: Arc<dyn RegionSelectionPolicy>) -> Self {
+        self.selection = Some(policy);
+        self
+    }
+
+    #[cfg(feature = "latency")]
+    /// Keeps connected, authenticated channels to the `count` fastest regions besides the client's own, so `JitoClient::fail_over` and
+    /// `JitoClient::warm_fanout` send without setting up a connection.
+    ///
+    /// Every channel, the client's own included, is kept alive with HTTP/2 and TCP keep-alives every 10 seconds, or the interval set with `keep_alive`,
+    /// and checked with a signed `GetTipAccounts` at the same interval. Their state is reported in `JitoClient::region_stats`.
+    /// Regions that can't be connected are left out. Disabled by default.
+    pub fn warm_regions(mut self, count: usize) -> Self {
+        self.warm_regions = count;
+        self
+    }
+
+    #[cfg(feature = "latency")]
+    /// Re-evaluates the client's region at every keep-alive check of the warm regions set with `warm_regions`. When another warm region answers
+    /// its checks more than 20% faster than the client's region, or the client's region stops answering, the client's next send switches to the
+    /// faster region, as with `JitoClient::fail_over`.
+    ///
+    /// Regions are ranked by the round trip of the same signed `GetTipAccounts` check. Building fails with `ConfigError` without warm regions.
+    /// Disabled by default.
+    pub fn reevaluate_region(mut self, enabled: bool) -> Self {
+        self.reevaluate_region = enabled;
+        self
+    }
+
+    /// Replaces the endpoints of specific regions, which are then measured and connected to at their override. See `RegionOverrides`.
+    pub fn region_overrides(mut self, overrides: RegionOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Caps the sends the client has in flight at once at `limit`, so bursts from strategy code can't open hundreds of simultaneous HTTP/2 streams and trip server limits.
+    ///
+    /// Sends beyond the limit wait for a slot. With a `queue_timeout`, a send waiting longer fails with a `ResourceExhausted` status instead.
+    /// The limit is per client, and applies to every send, including every attempt of a retrying send and the bundles of `JitoClient::send_batch`.
+    pub fn max_in_flight(mut self, limit: usize, queue_timeout: Option<Duration>) -> Self {
+        self.max_in_flight = Some((limit, queue_timeout));
+        self
+    }
+
+    /// Signs every request of the client with `signer`, such as for an authenticating proxy in front of the block engine. See `RequestSigner`.
+    ///
+    /// Signers run in the order they were added, each seeing the metadata set by the previous ones.
+    pub fn request_signer(mut self, signer: Arc<dyn RequestSigner>) -> Self {
+        self.signer = self.signer.then(signer);
+        self
+    }
+
+    /// Authenticates every request of the client with the keypairs of `pool`, rotating between them as set by `rotation`. See `AuthenticatorPool`.
+    ///
+    /// The pool's token is attached after the request signers, whenever they were added. With `KeyRotation::OnRateLimit`, a rate-limited send
+    /// attempt switches the pool to its next keypair, so the retry goes out under another one. Refreshing the tokens is left to the pool.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{auth::{AuthenticatorPool, KeyRotation}, builder::JitoClientBuilder, errors::JitoClientResult, grpc::auth::Role};
+    /// # use solana_keypair::Keypair;
+    /// # use std::sync::Arc;
+    /// # async fn example(channel: tonic::transport::Channel, keypairs: Vec<Arc<Keypair>>) -> JitoClientResult<()> {
+    /// let pool = AuthenticatorPool::connect(channel, keypairs, Role::Searcher).await?;
+    /// pool.spawn_refresh_tasks();
+    ///
+    /// let client = JitoClientBuilder::new()
+    ///     .authenticator_pool(pool, KeyRotation::OnRateLimit)
+    ///     .build()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn authenticator_pool(mut self, pool: AuthenticatorPool, rotation: KeyRotation) -> Self {
+        self.auth_pool = Some((pool, rotation));
+        self
+    }
+
+    /// Retries a failed connection with `retry_logic`, so a transient DNS, TCP or TLS failure at process start doesn't fail `build`.
+    ///
+    /// Only retryable errors, see `JitoClientError::is_retryable`, are retried, waiting a random time between attempts. Without an endpoint,
+    /// every attempt measures the regions again. Once no retry is left, the error of the last attempt is returned. Connections aren't retried by default.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{builder::JitoClientBuilder, client::{Backoff, RetryLogic}, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let retry_logic = RetryLogic::builder()
+    ///     .max_retries(5)
+    ///     .bounds_ms(100, 2_000)
+    ///     .backoff(Backoff::Exponential)
+    ///     .build()?;
+    /// let client = JitoClientBuilder::new().connect_with_retry(retry_logic).build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connect_with_retry(mut self, retry_logic: RetryLogic) -> Self {
+        self.connect_retry = Some(retry_logic);
+        self
+    }
+
+    /// Connects to the block engines of `network` instead of mainnet. Only the network's regions, and overridden regions, are measured.
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    // Endpoint of the region on the network, with the region overrides applied
+    pub(crate) fn region_endpoint(&self, region: NodeRegion) -> JitoClientResult<&'static str> {
+        self.overrides
+            .endpoint_on(region, self.network)
+            .ok_or(JitoClientError::NoBlockEngine(region, self.network))
+    }
+
+    #[cfg(feature = "latency")]
+    /// Connects to the fastest region according to the latencies saved in `store`, if they were measured less than `max_age` ago.
+    ///
+    /// Otherwise, or if the store cannot be read, every region is measured and the measurements are saved to the store for the next client.
+    pub fn latency_cache(mut self, store: Arc<dyn LatencyStore>, max_age: Duration) -> Self {
+        self.cache = Some(LatencyCache { store, max_age });
+        self
+    }
+
+    /// Connects the client.
+    ///
+    /// If no endpoint was set, latency to all regions is measured first and the region is picked by the selection policy, the fastest one by default,
+    /// preferring recorded send latency over the measured latency.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The endpoint is malformed (`InvalidEndpoint`)
+    /// - No endpoint is set and the `latency` feature is disabled
+    /// - Region latency measurement fails
+    /// - Connection to the endpoint fails, after the retries set with `connect_with_retry`
+    /// - The in-flight limit or a channel limit is 0, or the region is re-evaluated without warm regions
+    /// - The compiled protos don't match what the client expects (`ProtoMismatch`), see `protos::verify`
+    pub async fn build(mut self) -> JitoClientResult<JitoClient> {
+        protos::verify()?;
+        self.options.limits.validate()?;
+        if let Some((0, _)) = self.max_in_flight {
+            return Err(JitoClientError::ConfigError(
+                "max in-flight sends must be at least 1".to_string(),
+            ));
+        }
+        #[cfg(feature = "latency")]
+        if self.reevaluate_region && self.warm_regions == 0 {
+            return Err(JitoClientError::ConfigError(
+                "re-evaluating the region requires warm regions".to_string(),
+            ));
+        }
+        #[cfg(feature = "latency")]
+        if self.warm_regions > 0 {
+            self.options.keep_alive.get_or_insert(WARM_KEEP_ALIVE);
+        }
+        #[cfg_attr(not(feature = "latency"), allow(unused_variables))]
+        let (endpoint, channel, measurement) = self.connect_with_retries().await?;
+        // Picked at build, so every client built from clones of the builder takes its own keypair
+        let rotating = match self.auth_pool.take() {
+            Some((pool, KeyRotation::PerConnection)) => {
+                self.signer = self.signer.then(Arc::new(pool.connection_interceptor()));
+                None
+            }
+            Some((pool, KeyRotation::OnRateLimit)) => {
+                self.signer = self.signer.then(Arc::new(pool.interceptor()));
+                Some(pool)
+            }
+            None => None,
+        };
+        let searcher = self.searcher(channel);
+        let stats = self.stats.take().unwrap_or_default();
+        let mut client = JitoClient::from_searcher(searcher.clone(), endpoint, stats.clone())
+            .with_signer(self.signer.clone());
+        client.auth_pool = rotating;
+        #[cfg(feature = "latency")]
+        let client = match self.warm_regions {
+            0 => client,
+            _ => {
+                let own = WarmChannel {
+                    endpoint,
+                    client: searcher,
+                };
+                client.with_warm_channels(self.connect_warm(own, measurement, stats).await)
+            }
+        };
+        Ok(match self.max_in_flight {
+            Some((limit, queue_timeout)) => {
+                client.with_in_flight_limit(InFlightLimit::new(limit, queue_timeout))
+            }
+            None => client,
+        })
+    }
+
+    // Connects to the endpoint, or to the region picked by the selection policy, retrying retryable failures with the connect retry policy
+    async fn connect_with_retries(
+        &self,
+    ) -> JitoClientResult<(&'static str, Channel, Option<Measurement>)> {
+        let mut attempt: u8 = 0;
+        loop {
+            let error = match self.connect_once().await {
+                Ok(connection) => return Ok(connection),
+                Err(e) => e,
+            };
+            attempt = attempt.saturating_add(1);
+            let wait = self
+                .connect_retry
+                .filter(|_| error.is_retryable())
+                .and_then(|retry_logic| retry_logic.next_wait(attempt));
+            let Some(wait) = wait else {
+                return Err(error);
+            };
+            debug!("Connect attempt {attempt} failed, retrying in {wait:?}: {error}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // A single connection attempt
+    async fn connect_once(&self) -> JitoClientResult<(&'static str, Channel, Option<Measurement>)> {
+        match self.endpoint {
+            Some(endpoint) => {
+                let endpoint = normalized(endpoint)?;
+                let channel = self
+                    .options
+                    .connect(Endpoint::from_static(endpoint))
+                    .await?;
+                Ok((endpoint, channel, None))
+            }
+            None => {
+                let (endpoint, channel, measurement) = self.connect_fastest().await?;
+                Ok((endpoint, channel, Some(measurement)))
+            }
+        }
+    }
+
+    /// Connects to the endpoint phase by phase with the builder's TLS, timeout, DNS and signer settings, timing the DNS lookup, TCP connect,
+    /// TLS handshake and first request separately. See `Diagnosis`.
+    ///
+    /// The connection is only used for the diagnosis. It is made directly, as the phases of a proxied connection can't be told apart.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - No endpoint is set, or a proxy is (`ConfigError`)
+    /// - The endpoint is malformed (`InvalidEndpoint`)
+    /// - A phase fails, such as `DNSResolution`, `TCPConnect`, or a `GRPCConnectError` for a failed TLS handshake
+    pub async fn diagnose(&self) -> JitoClientResult<Diagnosis> {
+        let endpoint = self.endpoint.ok_or_else(|| {
+            JitoClientError::ConfigError("diagnosing a connection requires an endpoint".to_string())
+        })?;
+        if self.options.proxy.is_some() {
+            return Err(JitoClientError::ConfigError(
+                "proxied connections can't be diagnosed".to_string(),
+            ));
+        }
+        let endpoint = normalized(endpoint)?;
+        let uri = Uri::from_static(endpoint);
+        // A normalized endpoint always has a host
+        let (host, port) =
+            connector::host_port(&uri).map_err(|_| JitoClientError::InvalidEndpoint {
+                endpoint: endpoint.to_string(),
+                reason: "missing host",
+            })?;
+
+        let start = Instant::now();
+        let resolver = self.options.resolver.clone().unwrap_or_default();
+        let addresses = resolver.lookup(host).await?;
+        let dns = start.elapsed();
+
+        let start = Instant::now();
+        let connect = connector::connect_any(&addresses, port, self.options.ip_preference);
+        let stream = match tokio::time::timeout(self.options.connect_timeout, connect).await {
+            Ok(stream) => stream,
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "TCP connect timed out",
+            )),
+        };
+        let tcp_error = |source| JitoClientError::TCPConnect {
+            region: None,
+            host: host.to_string(),
+            source,
+        };
+        let stream = stream.map_err(tcp_error)?;
+        let tcp_connect = start.elapsed();
+        let connected_to = stream.peer_addr().map_err(tcp_error)?;
+
+        let start = Instant::now();
+        let channel = self
+            .options
+            .configure(Endpoint::from_static(endpoint))?
+            .connect_with_connector(EstablishedConnector::new(stream))
+            .await?;
+        let tls_handshake = (uri.scheme_str() == Some("https")).then(|| start.elapsed());
+
+        let start = Instant::now();
+        let response = self
+            .searcher(channel)
+            .get_tip_accounts(self.signer.request(GetTipAccountsRequest {}))
+            .await?;
+        let first_rpc = start.elapsed();
+        Ok(Diagnosis {
+            endpoint,
+            addresses,
+            connected_to,
+            dns,
+            tcp_connect,
+            tls_handshake,
+            first_rpc,
+            server_timing: ResponseMetadata::new(response.metadata().clone()).server_timing(),
+        })
+    }
+
+    // Searcher client over the channel, signing every request and applying the message size limits
+    fn searcher(&self, channel: Channel) -> SearcherClient {
+        let mut searcher = self.signer.client(channel);
+        if let Some(limit) = self.max_decoding_message_size {
+            searcher = searcher.max_decoding_message_size(limit);
+        }
+        if let Some(limit) = self.max_encoding_message_size {
+            searcher = searcher.max_encoding_message_size(limit);
+        }
+        searcher
+    }
+
+    #[cfg(feature = "latency")]
+    // Measures the regions, or reads the cached latencies, and connects to the region picked by the selection policy.
+    // The latencies and unused probe connections are returned for the warm channels
+    async fn connect_fastest(&self) -> JitoClientResult<(&'static str, Channel, Measurement)> {
+        let (mut results, mut channels) = self.latencies().await;
+        self.prefer_recorded_latency(&mut results);
+        let latencies = reachable(&results);
+        let endpoint = self.region_endpoint(self.select_region(results)?)?;
+        let channel = match channels.iter().position(|(e, _)| *e == endpoint) {
+            Some(index) => channels.swap_remove(index).1,
+            None => {
+                self.options
+                    .connect(Endpoint::from_static(endpoint))
+                    .await?
+            }
+        };
+        Ok((endpoint, channel, (latencies, channels)))
+    }
+
+    #[cfg(feature = "latency")]
+    // Connects to the fastest regions besides the client's own, reusing the probe connections, and keeps them warm along with the client's channel
+    async fn connect_warm(
+        &self,
+        own: WarmChannel,
+        measurement: Option<Measurement>,
+        stats: LatencyStats,
+    ) -> WarmChannels {
+        let (mut latencies, mut channels) = match measurement {
+            Some(measurement) => measurement,
+            None => {
+                let (mut results, channels) = self.latencies().await;
+                self.prefer_recorded_latency(&mut results);
+                (reachable(&results), channels)
+            }
+        };
+        latencies.sort_by_key(|(_, latency)| *latency);
+        let endpoints: Vec<&'static str> = latencies
+            .into_iter()
+            .filter_map(|(region, _)| self.region_endpoint(region).ok())
+            .filter(|endpoint| *endpoint != own.endpoint)
+            .take(self.warm_regions)
+            .collect();
+        let connects = endpoints.into_iter().map(|endpoint| {
+            let probed = channels
+                .iter()
+                .position(|(e, _)| *e == endpoint)
+                .map(|index| channels.swap_remove(index).1);
+            async move {
+                let channel = match probed {
+                    Some(channel) => Ok(channel),
+                    None => self.options.connect(Endpoint::from_static(endpoint)).await,
+                };
+                (endpoint, channel)
+            }
+        });
+        let mut warm = vec![own];
+        for (endpoint, channel) in futures::future::join_all(connects).await {
+            match channel {
+                Ok(channel) => warm.push(WarmChannel {
+                    endpoint,
+                    client: self.searcher(channel),
+                }),
+                Err(e) => debug!("Warm connection to {endpoint} failed: {e}"),
+            }
+        }
+        let interval = self.options.keep_alive.unwrap_or(WARM_KEEP_ALIVE);
+        WarmChannels::spawn(
+            warm,
+            self.signer.clone(),
+            stats,
+            interval,
+            self.reevaluate_region,
+        )
+    }
+
+    #[cfg(feature = "latency")]
+    // Reads the cached latencies if they are fresh, or measures every region and saves the measurements.
+    // Latencies measured with another probe than the configured one are neither read from nor saved to the cache
+    async fn latencies(
+        &self,
+    ) -> (
+        Vec<(NodeRegion, JitoClientResult<Duration>)>,
+        Vec<(&'static str, Channel)>,
+    ) {
+        let probe = self.effective_probe();
+        if probe != self.probe {
+            return self.measure(probe).await;
+        }
+        match self.cached_latencies().await {
+            Some(results) => (results, Vec::new()),
+            None => {
+                let (results, channels) = self.measure(probe).await;
+                self.save_latencies(&results).await;
+                (results, channels)
+            }
+        }
+    }
+
+    #[cfg(feature = "latency")]
+    // The probe the regions are measured with. Recorded send latencies are gRPC round trips, so when some are preferred the other regions are
+    // measured with gRPC round trips too
+    fn effective_probe(&self) -> LatencyProbe {
+        let recorded = self.stats.as_ref().is_some_and(|stats| {
+            stats.all().iter().any(|(endpoint, stats)| {
+                stats.samples > 0
+                    && NodeRegion::all()
+                        .iter()
+                        .any(|region| self.region_endpoint(*region).ok() == Some(*endpoint))
+            })
+        });
+        match recorded {
+            true => LatencyProbe::GrpcRoundTrip,
+            false => self.probe,
+        }
+    }
+
+    // Without latency measurement, there is no fastest region to fall back on
+    #[cfg(not(feature = "latency"))]
+    async fn connect_fastest(&self) -> JitoClientResult<(&'static str, Channel, Measurement)> {
+        Err(JitoClientError::ConfigError(
+            "no endpoint set, and connecting to the fastest region requires the `latency` feature"
+                .to_string(),
+        ))
+    }
+
+    #[cfg(feature = "latency")]
+    // Returns the latencies saved in the cache, if they are fresh. The store is read off the async runtime
+    async fn cached_latencies(&self) -> Option<Vec<(NodeRegion, JitoClientResult<Duration>)>> {
+        let cache = self.cache.as_ref()?;
+        let store = cache.store.clone();
+        let snapshot = tokio::task::spawn_blocking(move || store.load())
+            .await
+            .map_err(|e| JitoClientError::LatencyStoreError(io::Error::other(e)))
+            .and_then(|loaded| loaded)
+            .unwrap_or_else(|e| {
+                debug!("Latency store load error: {e}");
+                None
+            })?;
+        if !snapshot.is_fresh(cache.max_age) || snapshot.latencies.is_empty() {
+            return None;
+        }
+        let results = snapshot
+            .latencies
+            .into_iter()
+            .filter(|(region, _)| self.region_endpoint(*region).is_ok())
+            .map(|(region, latency)| (region, Ok(latency)))
+            .collect();
+        Some(results)
+    }
+
+    #[cfg(feature = "latency")]
+    // Saves the measurements to the cache off the async runtime. Failing to save only costs the next client a measurement, so it is logged
+    async fn save_latencies(&self, results: &[(NodeRegion, JitoClientResult<Duration>)]) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        let (store, snapshot) = (cache.store.clone(), LatencySnapshot::new(results));
+        let saved = tokio::task::spawn_blocking(move || store.save(&snapshot))
+            .await
+            .map_err(|e| JitoClientError::LatencyStoreError(io::Error::other(e)))
+            .and_then(|saved| saved);
+        if let Err(e) = saved {
+            debug!("Latency store save error: {e}");
+        }
+    }
+
+    #[cfg(feature = "latency")]
+    // Measures every region with `probe`, returning the connections opened by a gRPC probe for reuse
+    async fn measure(
+        &self,
+        probe: LatencyProbe,
+    ) -> (
+        Vec<(NodeRegion, JitoClientResult<Duration>)>,
+        Vec<(&'static str, Channel)>,
+    ) {
+        match probe {
+            LatencyProbe::TcpConnect => {
+                let ping = PingConfig {
+                    resolver: self.options.resolver.clone().unwrap_or_default(),
+                    ip_preference: self.options.ip_preference,
+                    overrides: self.overrides.clone(),
+                    network: self.network,
+                    ..PingConfig::default()
+                };
+                (NodeRegion::measure_all(&ping).await, Vec::new())
+            }
+            LatencyProbe::GrpcRoundTrip => {
+                let probes = NodeRegion::all().iter().filter_map(|region| {
+                    let endpoint = self.region_endpoint(*region).ok()?;
+                    Some(self.options.probe(*region, endpoint))
+                });
+                let mut channels = Vec::new();
+                let results = futures::future::join_all(probes)
+                    .await
+                    .into_iter()
+                    .map(|(region, result)| {
+                        let latency = result.map(|(latency, channel)| {
+                            if let Ok(endpoint) = self.region_endpoint(region) {
+                                channels.push((endpoint, channel));
+                            }
+                            latency
+                        });
+                        (region, latency)
+                    })
+                    .collect();
+                (results, channels)
+            }
+        }
+    }
+
+    #[cfg(feature = "latency")]
+    // Picks a region among the reachable ones with the selection policy, reporting every failure if none is picked
+    fn select_region(
+        &self,
+        results: Vec<(NodeRegion, JitoClientResult<Duration>)>,
+    ) -> JitoClientResult<NodeRegion> {
+        let mut latencies = Vec::new();
+        let mut failures = Vec::new();
+        for (region, result) in results {
+            match result {
+                Ok(latency) => latencies.push((region, latency)),
+                Err(e) => failures.push((region, e)),
+            }
+        }
+        let selected = match &self.selection {
+            Some(policy) => policy.select(&latencies),
+            None => Fastest.select(&latencies),
+        };
+        selected.ok_or(JitoClientError::AllRegionLatencyMissing(failures))
+    }
+
+    #[cfg(feature = "latency")]
+    // Replaces the gRPC round trip measured to each region with recorded sends by its average send latency
+    fn prefer_recorded_latency(&self, results: &mut [(NodeRegion, JitoClientResult<Duration>)]) {
+        let Some(stats) = &self.stats else {
+            return;
+        };
+        for (region, latency) in results {
+            if let Ok(endpoint) = self.region_endpoint(*region)
+                && let Some(region_stats) = stats.get(endpoint)
+                && region_stats.samples > 0
+            {
+                *latency = Ok(region_stats.ema);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "latency")]
+// Latencies of the regions that could be measured
+fn reachable(results: &[(NodeRegion, JitoClientResult<Duration>)]) -> Vec<(NodeRegion, Duration)> {
+    results
+        .iter()
+        .filter_map(|(region, latency)| Some((*region, *latency.as_ref().ok()?)))
+        .collect()
+}
+
+#[cfg(feature = "latency")]
+impl ConnectOptions {
+    // Connects to the region's endpoint and times a GetTipAccounts round trip over the connection, which is returned for reuse
+    async fn probe(
+        &self,
+        region: NodeRegion,
+        endpoint: &'static str,
+    ) -> (NodeRegion, JitoClientResult<(Duration, Channel)>) {
+        let probe = async {
+            let channel = self.connect(Endpoint::from_shared(endpoint)?).await?;
+            let start = Instant::now();
+            SearcherServiceClient::new(channel.clone())
+                .get_tip_accounts(GetTipAccountsRequest {})
+                .await?;
+            Ok((start.elapsed(), channel))
+        };
+        (region, probe.await)
+    }
+}
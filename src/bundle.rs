@@ -1,44 +1,575 @@
+use crate::clock::ClockSkew;
 use crate::errors::{JitoClientError, JitoClientResult};
 use crate::grpc::{
     bundle::Bundle,
-    packet::{Meta, Packet},
+    packet::{Meta, Packet, PacketFlags},
+    shared::Header,
 };
+use bytes::BytesMut;
+use prost::Message;
+use prost::encoding::encoded_len_varint;
 use solana_transaction::versioned::VersionedTransaction;
+use std::fmt;
+use std::time::SystemTime;
 
-const TXNS_LIMIT: usize = 5;
+/// Maximum number of transactions in a bundle accepted by Jito's block engines.
+pub const DEFAULT_TXNS_LIMIT: usize = 5;
+/// Upper bound of a configured transaction limit, keeping a misconfigured limit from building bundles no block engine would accept.
+pub const MAX_TXNS_LIMIT: usize = 64;
+/// Maximum size of a serialized transaction, the payload of a network packet.
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Options for building a bundle.
+#[derive(Debug, Clone, Default)]
+pub struct BundleOptions {
+    /// Stamps the bundle header with the time the bundle was built, so the latency between building a bundle and the block engine receiving it can be analyzed
+    pub timestamp: bool,
+    /// Offset of the block engine's clock the header timestamp is converted with, so a skewed host clock doesn't distort the latency measured from it.
+    /// See `JitoClient::measure_clock_skew`
+    pub clock_skew: Option<ClockSkew>,
+    /// Metadata attached to every packet of the bundle
+    pub packet_meta: PacketMetaOptions,
+    /// Maximum number of transactions in a bundle, for block engines with a different limit. Defaults to `DEFAULT_TXNS_LIMIT`, and is capped at `MAX_TXNS_LIMIT`
+    pub max_txns: Option<usize>,
+    /// Maximum sum of the transactions' serialized sizes, for block engines limiting the bundle's total size. Unlimited by default,
+    /// since Jito's block engines only limit each transaction to a packet
+    pub max_size: Option<usize>,
+}
+
+/// Why a bundle exceeds the block engine's size limits, listing the transactions to trim for it to fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleSizeError {
+    /// Serialized size of every transaction, in bundle order
+    pub sizes: Vec<usize>,
+    /// Sum of the serialized sizes
+    pub total_size: usize,
+    /// Limit of the sum of the serialized sizes, if the options set one
+    pub max_size: Option<usize>,
+    /// Indices of the transactions larger than a packet, `PACKET_DATA_SIZE` bytes
+    pub oversized: Vec<usize>,
+    /// Indices of the transactions to remove or shrink for the bundle to fit: the oversized ones, then the largest until the total fits
+    pub trim: Vec<usize>,
+}
+
+/// Metadata of the packets holding a bundle's transactions.
+///
+/// Packet metadata describes how a validator received a transaction, so the block engine ignores the address, port and stake of bundles sent by searchers,
+/// and the defaults of an unspecified address, port 0, no flags and no stake are what it expects. Only set these when a private deployment interprets them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketMetaOptions {
+    pub addr: String,
+    pub port: u32,
+    pub flags: Option<PacketFlags>,
+    pub sender_stake: u64,
+}
+
+impl Default for PacketMetaOptions {
+    fn default() -> Self {
+        Self {
+            addr: "0.0.0.0".to_string(),
+            port: 0,
+            flags: None,
+            sender_stake: 0,
+        }
+    }
+}
+
+impl BundleOptions {
+    /// Returns the maximum number of transactions in a bundle.
+    pub fn txns_limit(&self) -> usize {
+        self.max_txns
+            .map_or(DEFAULT_TXNS_LIMIT, |limit| limit.clamp(1, MAX_TXNS_LIMIT))
+    }
+
+    /// Returns the maximum sum of the transactions' serialized sizes, if limited.
+    pub fn size_limit(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    // Checks the serialized sizes of a bundle's transactions against the packet size and the bundle size limit
+    fn check_sizes(&self, sizes: Vec<usize>) -> JitoClientResult<()> {
+        let total_size = sizes.iter().sum();
+        let oversized: Vec<usize> = (0..sizes.len())
+            .filter(|&index| sizes[index] > PACKET_DATA_SIZE)
+            .collect();
+        let max_size = self.size_limit();
+        let fits = |size: usize| max_size.is_none_or(|max_size| size <= max_size);
+        if oversized.is_empty() && fits(total_size) {
+            return Ok(());
+        }
+
+        let mut trim = oversized.clone();
+        let mut remaining: usize = total_size - oversized.iter().map(|&i| sizes[i]).sum::<usize>();
+        let mut largest: Vec<usize> = (0..sizes.len())
+            .filter(|index| !oversized.contains(index))
+            .collect();
+        largest.sort_by_key(|&index| std::cmp::Reverse(sizes[index]));
+        for index in largest {
+            if fits(remaining) {
+                break;
+            }
+            remaining -= sizes[index];
+            trim.push(index);
+        }
+        trim.sort_unstable();
+        Err(JitoClientError::BundleTooLarge(Box::new(BundleSizeError {
+            sizes,
+            total_size,
+            max_size,
+            oversized,
+            trim,
+        })))
+    }
+
+    // Header of a bundle built now
+    fn header(&self) -> Option<Header> {
+        self.timestamp.then(|| {
+            let now = SystemTime::now();
+            let now = self.clock_skew.map_or(now, |skew| skew.to_remote(now));
+            Header {
+                ts: Some(now.into()),
+            }
+        })
+    }
+}
+
+impl PacketMetaOptions {
+    // Metadata of a packet holding `size` bytes
+    fn meta(&self, size: u64) -> Meta {
+        Meta {
+            size,
+            addr: self.addr.clone(),
+            port: self.port,
+            flags: self.flags,
+            sender_stake: self.sender_stake,
+        }
+    }
+}
+
+impl fmt::Display for BundleSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.total_size)?;
+        if let Some(max_size) = self.max_size {
+            write!(f, ", limit {max_size}")?;
+        }
+        if !self.oversized.is_empty() {
+            write!(
+                f,
+                ", transactions {:?} exceed {PACKET_DATA_SIZE} bytes",
+                self.oversized
+            )?;
+        }
+        write!(f, ", trim transactions {:?}", self.trim)
+    }
+}
 
 impl Bundle {
-    /// Creates a Bundle from a vec of transactions, to be sent via GRPC connection. Returns error if too many transactions.
+    /// Creates a Bundle from a vec of transactions, to be sent via GRPC connection. Returns error if too many transactions, or a transaction doesn't fit into a packet.
     /// For each transaction, serialize the data and store it in a Packet, which then constitudes apart of a Bundle. Returns error if serialize fails.
     pub fn create(txns: &[VersionedTransaction]) -> JitoClientResult<Self> {
-        if txns.len() > TXNS_LIMIT {
+        Self::create_with(txns, &BundleOptions::default())
+    }
+
+    /// Same as `create`, with the given options.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - There are more transactions than the options' limit (`TooManyTxns`)
+    /// - A transaction cannot be serialized
+    /// - A transaction doesn't fit into a packet, or the transactions together exceed the options' size limit (`BundleTooLarge`, listing the transactions to trim)
+    pub fn create_with(
+        txns: &[VersionedTransaction],
+        options: &BundleOptions,
+    ) -> JitoClientResult<Self> {
+        if txns.len() > options.txns_limit() {
             return Err(JitoClientError::TooManyTxns);
         }
 
+        let packets = Self::serialize_with(txns, &options.packet_meta)?;
+        options.check_sizes(packets.iter().map(|packet| packet.data.len()).collect())?;
         Ok(Self {
-            header: None,
-            packets: Self::serialize(txns)?,
+            header: options.header(),
+            packets,
         })
     }
 
+    /// Returns the size of the bundle built from the transactions, as encoded on the wire with default options, without building it.
+    ///
+    /// Signatures take up their space whether or not a transaction is signed yet, so the size of unsigned transactions is final.
+    /// Useful to decide how many transactions to pack into a bundle before signing them.
+    ///
+    /// # Errors
+    /// This function will return an error if a transaction cannot be serialized.
+    ///
+    /// # Examples
+    /// ```
+    /// # use jito_grpc_client::{errors::JitoClientResult, grpc::bundle::Bundle};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # fn example() -> JitoClientResult<()> {
+    /// let mut transactions = Vec::new();
+    /// for candidate in Vec::<VersionedTransaction>::new() {
+    ///     if !Bundle::fits_in_bundle(&candidate) {
+    ///         continue;
+    ///     }
+    ///     transactions.push(candidate);
+    /// }
+    /// println!("Bundle of {} bytes", Bundle::estimated_wire_size(&transactions)?);
+    /// # Ok(())
+    /// # }
+    /// # example().unwrap();
+    /// ```
+    pub fn estimated_wire_size(txns: &[VersionedTransaction]) -> JitoClientResult<usize> {
+        let meta = PacketMetaOptions::default();
+        let mut size = 0;
+        for txn in txns {
+            let data = bincode::serialized_size(txn)? as usize;
+            let meta = meta.meta(data as u64).encoded_len();
+            // Each field is prefixed by a one byte tag and its length
+            let packet = 1
+                + encoded_len_varint(data as u64)
+                + data
+                + 1
+                + encoded_len_varint(meta as u64)
+                + meta;
+            size += 1 + encoded_len_varint(packet as u64) + packet;
+        }
+        Ok(size)
+    }
+
+    /// Returns whether the transaction fits into a single packet of a bundle, at most `PACKET_DATA_SIZE` bytes serialized.
+    pub fn fits_in_bundle(txn: &VersionedTransaction) -> bool {
+        bincode::serialized_size(txn).is_ok_and(|size| size as usize <= PACKET_DATA_SIZE)
+    }
+
+    /// Returns the time the bundle was built, if its header was stamped with `BundleOptions::timestamp`, on the block engine's clock if stamped with a clock skew.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        let ts = self.header.as_ref()?.ts?;
+        SystemTime::try_from(ts).ok()
+    }
+
     // For each transaction, serialize the data and store it in a Packet, which then constitudes apart of a Bundle. Returns error if serialize fails
-    fn serialize(txns: &[VersionedTransaction]) -> JitoClientResult<Vec<Packet>> {
+    #[cfg(feature = "native")]
+    pub(crate) fn serialize(txns: &[VersionedTransaction]) -> JitoClientResult<Vec<Packet>> {
+        Self::serialize_with(txns, &PacketMetaOptions::default())
+    }
+
+    // Same as `serialize`, with the given packet metadata
+    fn serialize_with(
+        txns: &[VersionedTransaction],
+        meta: &PacketMetaOptions,
+    ) -> JitoClientResult<Vec<Packet>> {
         let mut packets = Vec::with_capacity(txns.len());
         for txn in txns {
             let data = bincode::serialize(&txn)?;
             let size = data.len() as u64;
             let packet = Packet {
-                data,
-                meta: Some(Meta {
-                    size,
-                    addr: "0.0.0.0".to_string(),
-                    port: 0u32,
-                    flags: None,
-                    sender_stake: 0u64,
-                }),
+                data: data.into(),
+                meta: Some(meta.meta(size)),
             };
             packets.push(packet);
         }
         Ok(packets)
     }
 }
+
+/// Reusable bundle builder that serializes transactions into a pre-allocated buffer, for hot loops that build hundreds of bundles per second.
+///
+/// `Bundle::create` allocates a new buffer for every transaction, while the encoder writes all transactions into one scratch buffer that packets share.
+/// Once the bundles built from a region of the buffer are dropped, for example after they were sent, that region is reused without allocating.
+/// `JitoClient` keeps an encoder for its sends.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::{bundle::BundleEncoder, errors::JitoClientResult};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # fn example() -> JitoClientResult<()> {
+/// let mut encoder = BundleEncoder::default();
+/// for transactions in Vec::<Vec<VersionedTransaction>>::new() {
+///     let bundle = encoder.encode(&transactions)?;
+///     // send the bundle
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BundleEncoder {
+    buffer: BytesMut,
+    scratch: Vec<u8>,
+    capacity: usize,
+    options: BundleOptions,
+}
+
+impl Default for BundleEncoder {
+    fn default() -> Self {
+        Self::new(BundleOptions::default())
+    }
+}
+
+impl BundleEncoder {
+    /// Creates an encoder building bundles with `options`, with room for a bundle of maximum size transactions.
+    pub fn new(options: BundleOptions) -> Self {
+        Self::with_capacity(options.txns_limit() * PACKET_DATA_SIZE, options)
+    }
+
+    /// Creates an encoder whose buffer holds `capacity` bytes of serialized transactions before it has to grow.
+    pub fn with_capacity(capacity: usize, options: BundleOptions) -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(capacity),
+            scratch: Vec::with_capacity(PACKET_DATA_SIZE),
+            capacity,
+            options,
+        }
+    }
+
+    /// Returns the options bundles are built with.
+    pub fn options(&self) -> &BundleOptions {
+        &self.options
+    }
+
+    /// Builds a bundle from the transactions, like `Bundle::create_with`. Returns error if too many transactions, serialize fails, or the bundle exceeds the size limits.
+    pub fn encode(&mut self, txns: &[VersionedTransaction]) -> JitoClientResult<Bundle> {
+        if txns.len() > self.options.txns_limit() {
+            return Err(JitoClientError::TooManyTxns);
+        }
+
+        let mut packets = Vec::with_capacity(txns.len());
+        for txn in txns {
+            // bincode writes field by field, which is much faster into a Vec than into a slice or the shared buffer
+            self.scratch.clear();
+            bincode::serialize_into(&mut self.scratch, txn)?;
+            // Reclaims the whole buffer if no packet shares it anymore, otherwise starts a new one
+            if self.buffer.capacity() < self.scratch.len() {
+                self.buffer.reserve(self.capacity.max(self.scratch.len()));
+            }
+            self.buffer.extend_from_slice(&self.scratch);
+            let data = self.buffer.split().freeze();
+            let size = data.len() as u64;
+            packets.push(Packet {
+                data,
+                meta: Some(self.options.packet_meta.meta(size)),
+            });
+        }
+        self.options
+            .check_sizes(packets.iter().map(|packet| packet.data.len()).collect())?;
+        Ok(Bundle {
+            header: self.options.header(),
+            packets,
+        })
+    }
+}
+
+/// A bundle built ahead of time from signed transactions, ready to be sent with `JitoClient::send_prepared`.
+///
+/// Serializing the transactions into a bundle is most of a send's local work. Preparing the bundle while waiting for the right moment,
+/// such as the signing window before a leader's slot, leaves only the request itself on the critical path. A prepared bundle can be sent any number of times,
+/// for example to several clients. A header timestamp set with `BundleOptions::timestamp` records when the bundle was prepared.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{bundle::PreparedBundle, client::JitoClient, errors::JitoClientResult};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None).await?;
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// let prepared = PreparedBundle::new(transactions)?;
+/// // ...wait for the opportunity
+/// let uuid = client.send_prepared(&prepared).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreparedBundle {
+    transactions: Vec<VersionedTransaction>,
+    bundle: Bundle,
+}
+
+impl PreparedBundle {
+    /// Builds the request from signed transactions, with the default bundle options.
+    ///
+    /// # Errors
+    /// This function will return an error if the bundle cannot be built, see `Bundle::create_with`.
+    pub fn new(transactions: Vec<VersionedTransaction>) -> JitoClientResult<Self> {
+        Self::with_options(transactions, &BundleOptions::default())
+    }
+
+    /// Same as `new`, with the given options.
+    ///
+    /// # Errors
+    /// This function will return an error if the bundle cannot be built, see `Bundle::create_with`.
+    pub fn with_options(
+        transactions: Vec<VersionedTransaction>,
+        options: &BundleOptions,
+    ) -> JitoClientResult<Self> {
+        let bundle = Bundle::create_with(&transactions, options)?;
+        Ok(Self {
+            transactions,
+            bundle,
+        })
+    }
+
+    /// Returns the transactions the bundle was built from.
+    pub fn transactions(&self) -> &[VersionedTransaction] {
+        &self.transactions
+    }
+
+    /// Returns the bundle sent, wrapped in a `SendBundleRequest`.
+    pub fn bundle(&self) -> &Bundle {
+        &self.bundle
+    }
+
+    /// Returns the size of the `SendBundleRequest` as encoded on the wire.
+    pub fn encoded_len(&self) -> usize {
+        prost::encoding::message::encoded_len(1, &self.bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_is_opt_in() {
+        let bundle = Bundle::create(&[]).unwrap();
+        assert!(bundle.header.is_none());
+        assert!(bundle.timestamp().is_none());
+
+        let before = SystemTime::now();
+        let options = BundleOptions {
+            timestamp: true,
+            ..BundleOptions::default()
+        };
+        let bundle = Bundle::create_with(&[], &options).unwrap();
+        assert!(bundle.timestamp().unwrap() >= before);
+    }
+
+    #[test]
+    fn txns_limit_is_bounded() {
+        let options = |max_txns| BundleOptions {
+            max_txns,
+            ..BundleOptions::default()
+        };
+        assert_eq!(options(None).txns_limit(), DEFAULT_TXNS_LIMIT);
+        assert_eq!(options(Some(0)).txns_limit(), 1);
+        assert_eq!(options(Some(1000)).txns_limit(), MAX_TXNS_LIMIT);
+
+        let transactions = vec![VersionedTransaction::default(); 2];
+        assert!(matches!(
+            Bundle::create_with(&transactions, &options(Some(1))),
+            Err(JitoClientError::TooManyTxns)
+        ));
+        assert!(
+            BundleEncoder::new(options(Some(2)))
+                .encode(&transactions)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn packet_meta_is_applied() {
+        let default = PacketMetaOptions::default().meta(10);
+        assert_eq!(
+            (default.addr.as_str(), default.port, default.size),
+            ("0.0.0.0", 0, 10)
+        );
+
+        let options = PacketMetaOptions {
+            flags: Some(PacketFlags {
+                from_staked_node: true,
+                ..PacketFlags::default()
+            }),
+            ..PacketMetaOptions::default()
+        };
+        assert!(options.meta(10).flags.unwrap().from_staked_node);
+    }
+
+    #[test]
+    fn wire_size_matches_encoding() {
+        use solana_keypair::{Keypair, Signer};
+        use solana_transaction::{Message, VersionedMessage};
+
+        let payer = Keypair::new();
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let txn = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let txns = vec![txn.clone(), txn.clone()];
+
+        let bundle = Bundle::create(&txns).unwrap();
+        assert_eq!(
+            Bundle::estimated_wire_size(&txns).unwrap(),
+            bundle.encoded_len()
+        );
+        assert!(Bundle::fits_in_bundle(&txn));
+
+        let oversized = VersionedTransaction {
+            signatures: vec![Default::default(); 40],
+            ..txn
+        };
+        assert!(!Bundle::fits_in_bundle(&oversized));
+    }
+
+    #[test]
+    fn oversized_bundles_list_transactions_to_trim() {
+        use solana_keypair::{Keypair, Signer};
+        use solana_transaction::{Message, VersionedMessage};
+
+        let payer = Keypair::new();
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let txn = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let larger = VersionedTransaction {
+            signatures: vec![Default::default(); 4],
+            ..txn.clone()
+        };
+        let oversized = VersionedTransaction {
+            signatures: vec![Default::default(); 40],
+            ..txn.clone()
+        };
+        let size = bincode::serialized_size(&txn).unwrap() as usize;
+
+        let txns = [txn.clone(), oversized, larger, txn];
+        let Err(JitoClientError::BundleTooLarge(error)) = Bundle::create(&txns) else {
+            panic!("oversized transaction accepted");
+        };
+        assert_eq!(error.oversized, vec![1]);
+        assert_eq!(error.trim, vec![1]);
+
+        let options = BundleOptions {
+            max_size: Some(2 * size),
+            ..BundleOptions::default()
+        };
+        let txns = [txns[0].clone(), txns[2].clone(), txns[3].clone()];
+        let Err(JitoClientError::BundleTooLarge(error)) = BundleEncoder::new(options).encode(&txns)
+        else {
+            panic!("bundle over the size limit accepted");
+        };
+        assert!(error.oversized.is_empty());
+        assert_eq!(error.trim, vec![1]);
+        assert_eq!(error.max_size, Some(2 * size));
+        assert_eq!(error.total_size, error.sizes.iter().sum::<usize>());
+    }
+
+    #[test]
+    fn encoder_matches_create() {
+        use solana_keypair::{Keypair, Signer};
+        use solana_transaction::{Message, VersionedMessage};
+
+        let payer = Keypair::new();
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let txn = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let txns = vec![txn.clone(), txn];
+
+        let mut encoder = BundleEncoder::with_capacity(0, BundleOptions::default());
+        for _ in 0..3 {
+            assert_eq!(
+                encoder.encode(&txns).unwrap(),
+                Bundle::create(&txns).unwrap()
+            );
+        }
+
+        let prepared = PreparedBundle::new(txns.clone()).unwrap();
+        assert_eq!(prepared.bundle(), &Bundle::create(&txns).unwrap());
+        let request = crate::grpc::searcher::SendBundleRequest {
+            bundle: Some(prepared.bundle().clone()),
+        };
+        assert_eq!(prepared.encoded_len(), request.encoded_len());
+    }
+}
@@ -1,25 +1,96 @@
+use crate::api::{ConnectedLeaders, ConnectedLeadersRegioned, NextLeader, Regions, TipAccounts};
+use crate::auth::{Authenticator, AuthenticatorPool};
+use crate::builder::JitoClientBuilder;
+use crate::bundle::{BundleEncoder, BundleOptions};
+use crate::clock::{ClockSkew, ClockSkewEstimator};
+use crate::dedup::{BundleDedup, SignatureGuard};
 use crate::errors::{JitoClientError, JitoClientResult};
+use crate::expiry::BlockhashTracker;
+#[cfg(feature = "latency")]
+use crate::fanout::FanoutClient;
 use crate::grpc::{
-    bundle::Bundle,
-    searcher::{searcher_service_client::SearcherServiceClient, SendBundleRequest},
+    bundle::BundleResult,
+    searcher::{
+        ConnectedLeadersRegionedRequest, ConnectedLeadersRequest, GetRegionsRequest,
+        GetTipAccountsRequest, NextScheduledLeaderRequest, SubscribeBundleResultsRequest,
+    },
 };
-use crate::nodes::NodeRegion;
-use futures_timer::Delay;
-use solana_transaction::versioned::VersionedTransaction;
-use std::time::Duration;
-use tonic::transport::{channel::ClientTlsConfig, Channel, Endpoint};
+#[cfg(feature = "latency")]
+use crate::health::ChannelState;
+use crate::health::{self, Health};
+use crate::journal::BundleJournal;
+#[cfg(feature = "latency")]
+use crate::nodes::LatencyStore;
+#[cfg(feature = "latency")]
+use crate::region::Network;
+use crate::region::NodeRegion;
+#[cfg(feature = "rpc")]
+use crate::rpc::SolanaRpc;
+use crate::signing::{SearcherClient, SignerInterceptor};
+use crate::sink::SubmissionSink;
+use crate::stats::{AdaptiveTimeout, BundleStats, LatencyStats};
+use crate::subscription::{BufferPolicy, ReconnectPolicy, ResultSubscription, SubscriptionEvent};
+use crate::tips::TipAccountProvider;
+use crate::tracker::BundleTracker;
+#[cfg(feature = "latency")]
+use crate::warm::{WarmChannel, WarmChannels};
+use futures::StreamExt;
+use futures::future::{self};
+use std::fmt::{self};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::Streaming;
+
+pub use crate::retry::{Backoff, RetryLogic, RetryLogicBuilder};
+pub use crate::submit::{
+    AttemptReport, BundleSubmission, RateLimit, RequestOptions, ResponseMetadata, SubmitPath,
+};
+pub(crate) use crate::submit::{InFlightLimit, SendHooks};
 
 pub struct JitoClient {
-    client: SearcherServiceClient<Channel>,
-    endpoint: &'static str,
+    pub(crate) client: SearcherClient,
+    pub(crate) signer: SignerInterceptor,
+    // Pool whose keypair the signer attaches, rotated when a send is rate limited
+    pub(crate) auth_pool: Option<AuthenticatorPool>,
+    pub(crate) endpoint: &'static str,
+    pub(crate) blockhashes: BlockhashTracker,
+    pub(crate) tracker: Option<Arc<BundleTracker>>,
+    // Whether the tracker was subscribed by this client, rather than shared with `with_tracker`
+    pub(crate) owns_tracker: bool,
+    // Journal, signature guard, deduplication, submission sink and statistics every send goes through
+    pub(crate) hooks: SendHooks,
+    pub(crate) clock: ClockSkewEstimator,
+    pub(crate) encoder: BundleEncoder,
+    pub(crate) path: SubmitPath,
+    pub(crate) timeout: Option<AdaptiveTimeout>,
+    pub(crate) in_flight: Option<InFlightLimit>,
+    #[cfg(feature = "latency")]
+    pub(crate) warm: Option<WarmChannels>,
+    #[cfg(feature = "rpc")]
+    pub(crate) transactions: std::sync::OnceLock<SolanaRpc>,
+}
+impl fmt::Debug for JitoClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JitoClient")
+            .field("endpoint", &self.endpoint)
+            .field("journal", &self.hooks.journal.is_some())
+            .field("tracker", &self.tracker.is_some())
+            .field("sink", &self.hooks.sink.is_some())
+            .field("dedup", &self.hooks.dedup.is_some())
+            .field("guard", &self.hooks.guard.is_some())
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
 }
+
 impl JitoClient {
+    #[cfg(feature = "latency")]
     /// Creates a new gRPC client that dyanmically determines the fastest endpoint to connect to.
     ///
     /// This method measures latency to all available endpoints and selects the one with the lowest response time for optimal performance.
     ///
     /// # Arguments
-    /// * `timeout` - Connection and request timeout in seconds. Defaults to 2 seconds if None is passed.
+    /// * `timeout` - Connection and request timeout. Defaults to 2 seconds if None is passed.
     ///
     /// # Returns
     /// Returns the configured client connected to the fastest endpoint, or an error if region measurement or connection fails.
@@ -30,161 +101,645 @@ impl JitoClient {
     /// - Connection to the selected endpoint fails
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
     /// //Use default 2-second timeout
     /// let client = JitoClient::new_dynamic_region(None).await?;
     ///
-    /// // Use custom 5-second timeout
-    /// let client = JitoClient::new_dynamic_region(Some(5)).await?;
+    /// // Use custom 500-millisecond timeout
+    /// let client = JitoClient::new_dynamic_region(Some(Duration::from_millis(500))).await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn new_dynamic_region(timeout: Option<u64>) -> JitoClientResult<Self> {
-        let fastest_endpoint = NodeRegion::measure_latency().await?.0.endpoint();
-        let timeout_dur = Duration::from_secs(timeout.unwrap_or(2));
-        let channel = Endpoint::from_static(fastest_endpoint)
-            .tls_config(ClientTlsConfig::new().with_native_roots())?
-            .tcp_nodelay(true)
-            .timeout(timeout_dur)
-            .connect_timeout(timeout_dur)
-            .connect()
-            .await?;
+    pub async fn new_dynamic_region(timeout: Option<Duration>) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new();
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
+
+    #[cfg(feature = "latency")]
+    /// Same as `new_dynamic_region`, selecting among the block engines of `network` instead of mainnet.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Region latency measurement fails, or Jito runs no block engine on the network
+    /// - Connection to the selected endpoint fails
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, region::Network};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region_on(Network::Testnet, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new_dynamic_region_on(
+        network: Network,
+        timeout: Option<Duration>,
+    ) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new().network(network);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
+
+    #[cfg(feature = "latency")]
+    /// Same as `new_dynamic_region`, retrying a failed connection with `retry_logic`, so a transient DNS, TCP or TLS failure at process start
+    /// doesn't fail the construction. See `JitoClientBuilder::connect_with_retry`.
+    ///
+    /// # Errors
+    /// This function will return the error of the last attempt if region latency measurement or the connection still fails once no retry is left,
+    /// or the first error that is not retryable.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let retry_logic = RetryLogic::new_with_wait_bounds(5, 200, 1_000)?;
+    /// let client = JitoClient::new_dynamic_region_with_retry(None, retry_logic).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new_dynamic_region_with_retry(
+        timeout: Option<Duration>,
+        retry_logic: RetryLogic,
+    ) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new().connect_with_retry(retry_logic);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
 
-        Ok(Self {
-            client: SearcherServiceClient::new(channel),
-            endpoint: fastest_endpoint,
-        })
+    #[cfg(feature = "latency")]
+    /// Same as `new_dynamic_region`, with the timeout given in whole seconds.
+    pub async fn new_dynamic_region_secs(timeout: Option<u64>) -> JitoClientResult<Self> {
+        Self::new_dynamic_region(timeout.map(Duration::from_secs)).await
+    }
+
+    #[cfg(feature = "latency")]
+    /// Same as `new_dynamic_region`, but selects the region from the latencies saved in `store` if they were measured less than `max_age` ago,
+    /// skipping the measurement on a warm start. Fresh measurements are saved to `store`.
+    ///
+    /// # Arguments
+    /// * `timeout` - Connection and request timeout. Defaults to 2 seconds if None is passed.
+    /// * `store` - Where region latencies are kept between runs
+    /// * `max_age` - How long saved latencies are used before measuring again
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The latencies are not fresh and region latency measurement fails
+    /// - Connection to the selected endpoint fails
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, nodes::FileLatencyStore};
+    /// # use std::{sync::Arc, time::Duration};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let store = Arc::new(FileLatencyStore::new("jito-latency.tsv"));
+    /// let client = JitoClient::new_dynamic_region_cached(None, store, Duration::from_secs(3600)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new_dynamic_region_cached(
+        timeout: Option<Duration>,
+        store: Arc<dyn LatencyStore>,
+        max_age: Duration,
+    ) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new().latency_cache(store, max_age);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
     }
 
     /// Creates a new gRPC client that connects to a specified input endpoint.
     ///
     /// # Arguments
     /// * `endpoint` - The gRPC endpoint URL
-    /// * `timeout` - Connection and request timeout in seconds. Defaults to 2 seconds if None is passed.
+    /// * `timeout` - Connection and request timeout. Defaults to 2 seconds if None is passed.
     ///
     /// # Returns
     /// Returns the configured client connected to the endpoint, or an error if connection fails.
     ///
     /// # Errors
-    /// This function will return an error if connection to the selected endpoint fails
+    /// This function will return an error if:
+    /// - The endpoint is malformed (`InvalidEndpoint`). A bare host is completed to `https://host:443`, see `builder::normalize_endpoint`
+    /// - Connection to the endpoint fails
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
     /// // Connect with default timeout
     /// let client = JitoClient::new("https://ny.mainnet.block-engine.jito.wtf:443", None).await?;
     ///
-    /// // Connect with custom 10-second timeout
-    /// let client = JitoClient::new("https://ny.mainnet.block-engine.jito.wtf:443", Some(10)).await?;
+    /// // Connect with custom 300-millisecond timeout
+    /// let client = JitoClient::new("https://ny.mainnet.block-engine.jito.wtf:443", Some(Duration::from_millis(300))).await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn new(endpoint: &'static str, timeout: Option<u64>) -> JitoClientResult<Self> {
-        let timeout_dur = Duration::from_secs(timeout.unwrap_or(2));
-        let channel = Endpoint::from_shared(endpoint)?
-            .tls_config(ClientTlsConfig::new().with_native_roots())?
-            .tcp_nodelay(true)
-            .timeout(timeout_dur)
-            .connect_timeout(timeout_dur)
-            .connect()
-            .await?;
+    pub async fn new(endpoint: &'static str, timeout: Option<Duration>) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new().endpoint(endpoint);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
 
-        let client = SearcherServiceClient::new(channel);
+    /// Same as `new`, retrying a failed connection with `retry_logic`. See `JitoClientBuilder::connect_with_retry`.
+    ///
+    /// # Errors
+    /// This function will return an error if the endpoint is malformed, or the error of the last attempt if the connection still fails once no retry is left.
+    pub async fn new_with_retry(
+        endpoint: &'static str,
+        timeout: Option<Duration>,
+        retry_logic: RetryLogic,
+    ) -> JitoClientResult<Self> {
+        let mut builder = JitoClientBuilder::new()
+            .endpoint(endpoint)
+            .connect_with_retry(retry_logic);
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build().await
+    }
 
-        Ok(Self { client, endpoint })
+    /// Same as `new`, with the timeout given in whole seconds.
+    pub async fn new_secs(endpoint: &'static str, timeout: Option<u64>) -> JitoClientResult<Self> {
+        Self::new(endpoint, timeout.map(Duration::from_secs)).await
+    }
+
+    /// Returns a builder for further connection options, such as the TLS root certificates.
+    pub fn builder() -> JitoClientBuilder {
+        JitoClientBuilder::new()
+    }
+
+    pub(crate) fn from_searcher(
+        client: SearcherClient,
+        endpoint: &'static str,
+        stats: LatencyStats,
+    ) -> Self {
+        Self {
+            client,
+            signer: SignerInterceptor::default(),
+            auth_pool: None,
+            endpoint,
+            blockhashes: BlockhashTracker::default(),
+            tracker: None,
+            owns_tracker: false,
+            hooks: SendHooks {
+                stats,
+                ..SendHooks::default()
+            },
+            clock: ClockSkewEstimator::default(),
+            encoder: BundleEncoder::default(),
+            path: SubmitPath::default(),
+            timeout: None,
+            in_flight: None,
+            #[cfg(feature = "latency")]
+            warm: None,
+            #[cfg(feature = "rpc")]
+            transactions: std::sync::OnceLock::new(),
+        }
     }
 
-    /// Sends a bundle of transactions to the node via gRPC.
+    // Records the signers the searcher client was built with, to encode payloads for those reading them
+    pub(crate) fn with_signer(mut self, signer: SignerInterceptor) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    #[cfg(feature = "latency")]
+    pub(crate) fn with_warm_channels(mut self, warm: WarmChannels) -> Self {
+        self.warm = Some(warm);
+        self
+    }
+
+    pub(crate) fn with_in_flight_limit(mut self, limit: InFlightLimit) -> Self {
+        self.in_flight = Some(limit);
+        self
+    }
+
+    /// Records every bundle sent by this client in `journal`, before it is sent, along with the send outcome, and the result of every bundle
+    /// streamed to the client's tracker, as subscribed by `tracker` or `send_and_track`.
     ///
-    /// # Arguments
-    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// If a submission cannot be written to the journal, the bundle is not sent.
+    pub fn with_journal(mut self, journal: Arc<BundleJournal>) -> Self {
+        self.hooks.journal = Some(journal);
+        self
+    }
+
+    /// Suppresses re-submission of a bundle identical to one sent by this client within the dedup's window. See `BundleDedup`.
     ///
-    /// # Returns
-    /// Returns a String containing the unique bundle ID.
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, dedup::BundleDedup, errors::JitoClientResult};
+    /// # use std::{sync::Arc, time::Duration};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None)
+    ///     .await?
+    ///     .with_dedup(Arc::new(BundleDedup::new(Duration::from_secs(2))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_dedup(mut self, dedup: Arc<BundleDedup>) -> Self {
+        self.hooks.dedup = Some(dedup);
+        self
+    }
+
+    /// Rejects bundles reusing the signature of a transaction sent by this client within the guard's TTL with `DuplicateInFlight`. See `SignatureGuard`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, dedup::SignatureGuard, errors::JitoClientResult};
+    /// # use std::{sync::Arc, time::Duration};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// // Share the guard between every client used by the strategy tasks
+    /// let guard = Arc::new(SignatureGuard::new(Duration::from_secs(30)));
+    /// let client = JitoClient::new_dynamic_region(None)
+    ///     .await?
+    ///     .with_signature_guard(guard.clone());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_signature_guard(mut self, guard: Arc<SignatureGuard>) -> Self {
+        self.hooks.guard = Some(guard);
+        self
+    }
+
+    /// Builds every bundle sent by this client with `options`, such as stamping the bundle header with the build time or raising the transaction limit.
+    pub fn with_bundle_options(mut self, options: BundleOptions) -> Self {
+        self.encoder = BundleEncoder::new(options);
+        self
+    }
+
+    /// Sends bundles over `path`, such as with an HTTP fallback for when the block engine's gRPC frontend is unavailable.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, SubmitPath}, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    /// # #[cfg(feature = "rpc")] {
+    /// let fallback = SubmitPath::http_fallback(client.get_endpoint());
+    /// let client = client.with_submit_path(fallback);
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_submit_path(mut self, path: SubmitPath) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Hands a copy of every bundle sent by this client, and of every result streamed to its tracker, to `sink`. See `SubmissionSink`.
+    ///
+    /// Set the sink before the first call to `tracker` or `send_and_track`, which subscribes the tracker.
+    pub fn with_submission_sink(mut self, sink: Arc<dyn SubmissionSink>) -> Self {
+        self.hooks.sink = Some(sink);
+        self
+    }
+
+    /// Awaits the results of `send_and_track` with `tracker`, instead of subscribing this client to bundle results.
+    ///
+    /// Lets several clients connected to the same block engine, such as one per strategy task, share one result subscription. Results are
+    /// only streamed by the block engine a bundle is sent to, so a tracker created by the `tracker` of a client connected to another block
+    /// engine is rejected. A tracker created with `BundleTracker::new` has no known endpoint and is accepted as is.
+    /// The tracker is not closed when this client shuts down, it is closed with the client that created it.
     ///
     /// # Errors
-    /// This function will return an error if:
-    /// - Too many transactions provided
-    /// - Transaction serialization fails
-    /// - gRPC connection fails
-    /// - Node server returns an error
+    /// This function will return a `TrackerMismatch` error if the tracker follows results from another endpoint than this client's.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
     /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let tracker = client.tracker().await?;
+    ///
+    /// // A second client for another task, following results over the first client's subscription
+    /// let other = JitoClient::new(client.get_endpoint(), None).await?.with_tracker(tracker)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tracker(mut self, tracker: Arc<BundleTracker>) -> JitoClientResult<Self> {
+        if let Some(endpoint) = tracker.endpoint()
+            && endpoint != self.endpoint
+        {
+            return Err(JitoClientError::TrackerMismatch {
+                tracker: endpoint,
+                client: self.endpoint,
+            });
+        }
+        if let Some(stats) = tracker.stats() {
+            self.hooks.outcomes = stats.clone();
+        }
+        self.tracker = Some(tracker);
+        self.owns_tracker = false;
+        Ok(self)
+    }
+
+    /// Derives the deadline of every send from the measured round trips of the endpoint, instead of the connection's static timeout. See `AdaptiveTimeout`.
     ///
-    /// let transactions = vec![];
+    /// The connection's timeout still applies if it is shorter.
     ///
-    /// match client.send(transactions).await {
-    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
-    ///     Err(e) => eprintln!("Failed to send: {}", e),
-    /// }
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, stats::AdaptiveTimeout};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None)
+    ///     .await?
+    ///     .with_adaptive_timeout(AdaptiveTimeout { multiplier: 3.0, ..Default::default() });
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn send(
-        &mut self,
-        transactions: &[VersionedTransaction],
-    ) -> JitoClientResult<String> {
-        let bundle = Bundle::create(transactions)?;
-        let request = SendBundleRequest {
-            bundle: Some(bundle),
+    pub fn with_adaptive_timeout(mut self, timeout: AdaptiveTimeout) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the options bundles sent by this client are built with.
+    pub fn bundle_options(&self) -> &BundleOptions {
+        self.encoder.options()
+    }
+
+    /// Returns the next scheduled leader connected to the block engine.
+    ///
+    /// # Arguments
+    /// * `regions` - Block engine regions to consider. Defaults to the currently connected region if empty.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails or the response contains an invalid pubkey.
+    pub async fn get_next_leader(&mut self, regions: &[&str]) -> JitoClientResult<NextLeader> {
+        let request = NextScheduledLeaderRequest {
+            regions: regions.iter().map(|r| r.to_string()).collect(),
         };
-        let response = self.client.send_bundle(request).await?;
-        Ok(response.into_inner().uuid)
+        let response = self
+            .client
+            .get_next_scheduled_leader(self.signer.request(request))
+            .await?;
+        response.into_inner().try_into()
+    }
+
+    /// Returns the leader slots of Jito validators connected to the currently connected region, for the current epoch.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails or the response contains an invalid pubkey.
+    pub async fn get_connected_leaders(&mut self) -> JitoClientResult<ConnectedLeaders> {
+        let response = self
+            .client
+            .get_connected_leaders(self.signer.request(ConnectedLeadersRequest {}))
+            .await?;
+        response.into_inner().try_into()
     }
 
-    /// Sends a bundle of transactions with automatic retries.
+    /// Returns the leader slots of Jito validators connected to each of the given regions, for the current epoch.
     ///
     /// # Arguments
-    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
-    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    /// * `regions` - Block engine regions to query. Defaults to the currently connected region if empty.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails or the response contains an invalid pubkey.
+    pub async fn get_connected_leaders_regioned(
+        &mut self,
+        regions: &[&str],
+    ) -> JitoClientResult<ConnectedLeadersRegioned> {
+        let request = ConnectedLeadersRegionedRequest {
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+        };
+        let response = self
+            .client
+            .get_connected_leaders_regioned(self.signer.request(request))
+            .await?;
+        response.into_inner().try_into()
+    }
+
+    /// Returns the tip accounts searchers shall transfer funds to for the leader to claim.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails or the response contains an invalid pubkey.
+    pub async fn get_tip_accounts(&mut self) -> JitoClientResult<TipAccounts> {
+        let response = self
+            .client
+            .get_tip_accounts(self.signer.request(GetTipAccountsRequest {}))
+            .await?;
+        let accounts: TipAccounts = response.into_inner().try_into()?;
+        self.hooks.outcomes.set_tip_accounts(&accounts);
+        Ok(accounts)
+    }
+
+    /// Returns a cache of the tip accounts that refetches them once they are older than `ttl`, sharing this client's connection.
+    ///
+    /// See `TipAccountProvider`. `tips::DEFAULT_TIP_ACCOUNTS_TTL` is a reasonable TTL, as the tip accounts rarely change.
+    pub fn tip_account_provider(&self, ttl: Duration) -> TipAccountProvider {
+        TipAccountProvider::new(self.client.clone(), ttl).with_stats(self.hooks.outcomes.clone())
+    }
+
+    /// Returns the region the client is connected to, along with all available regions.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails.
+    pub async fn get_regions(&mut self) -> JitoClientResult<Regions> {
+        let response = self
+            .client
+            .get_regions(self.signer.request(GetRegionsRequest {}))
+            .await?;
+        Ok(response.into_inner().into())
+    }
+
+    /// Exercises the connection with a lightweight request, so the first real send doesn't pay cold-start costs.
+    ///
+    /// The TLS handshake and HTTP/2 setup happen when the client connects; this additionally sends a `GetTipAccounts` request through the full request path,
+    /// warming up the HTTP/2 stream, the connection's flow control windows, and the server side of the connection. Call it before a time-critical opportunity.
     ///
     /// # Returns
-    /// Returns a String containing the unique bundle ID.
+    /// Returns the round trip time of the warm-up request.
     ///
     /// # Errors
-    /// This function will return an error if:
-    /// - Too many transactions provided
-    /// - Transaction serialization fails
-    /// - gRPC connection fails
-    /// - Node server returns an error
-    /// - Maximum retry attempts exceeded
+    /// This function will return an error if the warm-up request fails.
     ///
-    /// # Retry Behavior
-    /// - Uses random jitter between min_wait and max_wait milliseconds
-    /// - Logs debug information for each failed attempt
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let rtt = client.warm_up().await?;
+    /// println!("Warmed up in {} ms", rtt.as_millis());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn warm_up(&mut self) -> JitoClientResult<Duration> {
+        let start = Instant::now();
+        self.client
+            .get_tip_accounts(self.signer.request(GetTipAccountsRequest {}))
+            .await?;
+        Ok(start.elapsed())
+    }
+
+    /// Checks the connection to the block engine with a cheap `GetTipAccounts` request, for exposing through an application's health endpoint.
+    ///
+    /// Never fails, a failed check is reported in the returned `Health`. The check gives up after `HEALTH_CHECK_TIMEOUT`.
+    ///
+    /// # Returns
+    /// Returns the channel state, the round trip of the check, and when a bundle was last sent successfully.
     ///
     /// # Examples
-    /// ```rust
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
     /// let mut client = JitoClient::new_dynamic_region(None).await?;
-    /// // 3 retries with default timings
-    /// let retry_config = RetryLogic::new(3);     
     ///
-    /// let transactions = vec![];
+    /// let health = client.health().await;
+    /// if !health.is_healthy() {
+    ///     eprintln!("Unhealthy: {health}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn health(&mut self) -> Health {
+        let (state, rtt, error) = health::check(&mut self.client, &self.signer).await;
+        Health {
+            endpoint: self.endpoint,
+            state,
+            rtt,
+            error,
+            last_success: self
+                .hooks
+                .stats
+                .get(self.endpoint)
+                .and_then(|stats| stats.last_success),
+            auth_expires_at: None,
+        }
+    }
+
+    /// Same as `health`, also reporting whether the access token of `authenticator`, used by the client's requests, is still valid.
+    pub async fn health_with_auth(&mut self, authenticator: &Authenticator) -> Health {
+        self.health().await.with_auth(authenticator)
+    }
+
+    /// Subscribes to the results of bundles sent by this searcher.
     ///
-    /// match client.send_with_retry(transactions, retry_config).await {
-    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
-    ///     Err(e) => eprintln!("Failed to send: {}", e),
+    /// The subscription is opened over this client's channel, on the block engine its bundles are sent to. Pass the returned stream to a `BundleTracker` to await the results of individual bundles.
+    ///
+    /// # Errors
+    /// This function will return an error if the subscription request fails.
+    pub async fn subscribe_bundle_results(&mut self) -> JitoClientResult<Streaming<BundleResult>> {
+        let start = Instant::now();
+        let response = self
+            .client
+            .subscribe_bundle_results(SubscribeBundleResultsRequest {})
+            .await?;
+        self.clock.record_response(response.metadata(), start);
+        Ok(response.into_inner())
+    }
+
+    /// Subscribes to the results of bundles sent by this searcher, resubscribing according to `policy` whenever the server drops the stream.
+    ///
+    /// A `SubscriptionEvent::Gap` is yielded after each resubscription, as results produced while the stream was down may have been missed.
+    ///
+    /// # Errors
+    /// This function will return an error if the initial subscription request fails.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use jito_grpc_client::subscription::{ReconnectPolicy, SubscriptionEvent};
+    /// # use futures::StreamExt;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let mut results = client
+    ///     .subscribe_bundle_results_with_reconnect(ReconnectPolicy::default())
+    ///     .await?;
+    ///
+    /// while let Some(event) = results.next().await {
+    ///     match event {
+    ///         SubscriptionEvent::Result(result) => println!("{}: {:?}", result.bundle_id, result.result),
+    ///         SubscriptionEvent::Gap { downtime, .. } => println!("Results of the last {downtime:?} may be missing"),
+    ///         SubscriptionEvent::Overflow => println!("Fell behind, results were discarded"),
+    ///     }
     /// }
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn send_with_retry(
+    pub async fn subscribe_bundle_results_with_reconnect(
         &mut self,
-        transactions: &[VersionedTransaction],
-        retry_logic: RetryLogic,
-    ) -> JitoClientResult<String> {
-        let bundle = Bundle::create(transactions)?;
-        let request = SendBundleRequest {
-            bundle: Some(bundle),
-        };
-        let mut retries = 0u8;
-        loop {
-            match self.client.send_bundle(request.clone()).await {
-                Ok(response) => {
-                    return Ok(response.into_inner().uuid);
+        policy: ReconnectPolicy,
+    ) -> JitoClientResult<ResultSubscription> {
+        self.subscribe_bundle_results_buffered(policy, BufferPolicy::default())
+            .await
+    }
+
+    /// Subscribes like `subscribe_bundle_results_with_reconnect`, buffering results for the consumer according to `buffer`.
+    ///
+    /// The default buffer holds 1024 events and drops the oldest when full.
+    ///
+    /// # Errors
+    /// This function will return an error if the initial subscription request fails.
+    pub async fn subscribe_bundle_results_buffered(
+        &mut self,
+        policy: ReconnectPolicy,
+        buffer: BufferPolicy,
+    ) -> JitoClientResult<ResultSubscription> {
+        ResultSubscription::start(self.client.clone(), policy, buffer, self.clock.clone()).await
+    }
+
+    /// Returns the client's shared bundle tracker, subscribing to bundle results on first use.
+    ///
+    /// The tracker reuses this client's channel, so results are streamed from the same block engine bundles are sent to, and it is closed
+    /// when the client is shut down or dropped. Share it with other clients of the same block engine with `with_tracker`.
+    /// The subscription is resubscribed with the default `ReconnectPolicy` if the server drops it.
+    ///
+    /// # Errors
+    /// This function will return an error if the subscription request fails.
+    pub async fn tracker(&mut self) -> JitoClientResult<Arc<BundleTracker>> {
+        if let Some(tracker) = &self.tracker {
+            return Ok(tracker.clone());
+        }
+        let subscription = self
+            .subscribe_bundle_results_with_reconnect(ReconnectPolicy::default())
+            .await?;
+        let results = subscription.filter_map(|event| {
+            future::ready(match event {
+                SubscriptionEvent::Result(result) => {
+                    Some(Ok::<_, std::convert::Infallible>(result))
                 }
-                Err(e) => {
-                    log::debug!("Send error: {e}");
-                    Delay::new(retry_logic.jitter()).await;
-                    retries += 1;
-                    if retries >= retry_logic.max_retries {
-                        return Err(JitoClientError::MaxRetriesError);
-                    }
+                SubscriptionEvent::Gap { downtime, .. } => {
+                    debug!("Bundle results of the last {downtime:?} may have been missed");
+                    None
                 }
-            }
+                SubscriptionEvent::Overflow => None,
+            })
+        });
+        let tracker = BundleTracker::start(
+            results,
+            self.hooks.sink.clone(),
+            Some(self.hooks.outcomes.clone()),
+            self.hooks.journal.clone(),
+        );
+        let tracker = Arc::new(tracker.on_endpoint(self.endpoint));
+        self.tracker = Some(tracker.clone());
+        self.owns_tracker = true;
+        Ok(tracker)
+    }
+
+    /// Shuts the client down cleanly: stops its background tasks, syncs the journal to disk, and closes the connection.
+    ///
+    /// Sends take `&mut self`, so no send of this client can still be in flight once it is shut down. Dropping the client also stops its background tasks, and the journal syncs once its last handle is dropped.
+    ///
+    /// # Errors
+    /// This function will return an error if the journal fails to sync.
+    pub fn shutdown(mut self) -> JitoClientResult<()> {
+        self.stop_background_tasks();
+        match self.hooks.journal.take() {
+            Some(journal) => journal.flush(),
+            None => Ok(()),
         }
     }
 
@@ -193,50 +748,241 @@ impl JitoClient {
         self.endpoint
     }
 
-    /// Returns all available node regions that can be used for connections.
-    pub fn all_regions() -> &'static [NodeRegion] {
-        NodeRegion::all()
+    /// Returns the send latency statistics recorded by this client, per endpoint.
+    ///
+    /// Every bundle send is timed, so unlike the TCP ping used to pick a region, the statistics reflect actual request latency.
+    /// Pass them to `JitoClientBuilder::latency_stats` to switch to the region with the lowest send latency.
+    /// With `JitoClientBuilder::warm_regions`, they also report the state of the channel to each warm region at its last keep-alive check.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    /// // ... send bundles ...
+    ///
+    /// let stats = client.region_stats();
+    /// for (endpoint, region) in stats.all() {
+    ///     println!("{endpoint}: {} ms average", region.ema.as_millis());
+    /// }
+    ///
+    /// // Reconnect, ranking regions by their recorded send latency
+    /// let client = JitoClient::builder().latency_stats(stats).build().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn region_stats(&self) -> LatencyStats {
+        self.hooks.stats.clone()
     }
-}
 
-pub struct RetryLogic {
-    pub max_retries: u8,
-    pub min_wait: u64,
-    pub max_wait: u64,
-}
+    /// Returns the outcome statistics of the bundles sent by this client: landed, rejected and dropped counts, land rate per endpoint,
+    /// average tip paid and time-to-land percentiles, over a sliding window. See `BundleStats`.
+    ///
+    /// Outcomes are recorded from the results streamed to the client's tracker, so only bundles followed by it are counted as resolved.
+    pub fn outcome_stats(&self) -> BundleStats {
+        self.hooks.outcomes.clone()
+    }
 
-impl RetryLogic {
-    pub fn new(max_retries: u8) -> Self {
-        Self {
-            max_retries,
-            min_wait: 5,
-            max_wait: 25,
+    /// Records the outcomes of the bundles sent by this client in `stats`, such as to aggregate them over several clients, or over another window.
+    ///
+    /// Set the statistics before the first call to `tracker` or `send_and_track`, which subscribes the tracker.
+    pub fn with_bundle_stats(mut self, stats: BundleStats) -> Self {
+        self.hooks.outcomes = stats;
+        self
+    }
+
+    /// Measures the offset between the local clock and the block engine's, from the HTTP `date` header of `samples` cheap requests timed by their round trip.
+    ///
+    /// Samples are kept in the client's `clock_skew` estimator, and the one with the lowest uncertainty is returned. As the header has a resolution of
+    /// a second, so does the estimate. Set the result as `BundleOptions::clock_skew` to stamp bundle headers in block engine time.
+    ///
+    /// # Errors
+    /// This function will return an error if a request fails, or a `NoServerTime` error if no response carried a `date` header.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{bundle::BundleOptions, client::JitoClient, errors::JitoClientResult};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let skew = client.measure_clock_skew(5).await?;
+    /// if skew.local_ahead() || skew.local_behind() {
+    ///     println!("Host clock is off by {} ms", -skew.offset_millis());
+    /// }
+    /// let client = client.with_bundle_options(BundleOptions {
+    ///     timestamp: true,
+    ///     clock_skew: Some(skew),
+    ///     ..BundleOptions::default()
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn measure_clock_skew(&mut self, samples: usize) -> JitoClientResult<ClockSkew> {
+        for _ in 0..samples.max(1) {
+            let start = Instant::now();
+            let response = self
+                .client
+                .get_tip_accounts(self.signer.request(GetTipAccountsRequest {}))
+                .await?;
+            self.clock.record_response(response.metadata(), start);
         }
+        self.clock.estimate().ok_or(JitoClientError::NoServerTime)
     }
 
-    pub fn new_with_wait_bounds(
-        max_retries: u8,
-        min_wait: u64,
-        max_wait: u64,
-    ) -> JitoClientResult<Self> {
-        if min_wait >= max_wait {
-            return Err(JitoClientError::WaitParameterError);
+    /// Returns the clock skew estimator of this client, holding the samples of `measure_clock_skew` and of the `date` header of its bundle
+    /// result subscriptions.
+    ///
+    /// Feed it the header timestamps of messages streamed by the same block engine with `ClockSkewEstimator::record_header`, and share it with
+    /// an `Authenticator` of the same block engine with `Authenticator::with_clock_skew`.
+    pub fn clock_skew(&self) -> ClockSkewEstimator {
+        self.clock.clone()
+    }
+
+    #[cfg(feature = "latency")]
+    /// Switches the client to the warm channel of another region, without setting up a connection. See `JitoClientBuilder::warm_regions`.
+    ///
+    /// Channels found ready by their last keep-alive check are preferred, then the fastest region measured when connecting. Channels found
+    /// unreachable are skipped. The client's tracker follows the results of the block engine it was subscribed on, so it is released, and the
+    /// next `tracker` or `send_and_track` subscribes on the new block engine. Likewise, an HTTP fallback to the old block engine, as set with
+    /// `SubmitPath::http_fallback`, and the transaction endpoint of `send_transaction` switch to the new block engine.
+    ///
+    /// # Returns
+    /// Returns the endpoint the client now sends to.
+    ///
+    /// # Errors
+    /// This function will return a `NoWarmRegion` error if the client keeps no warm channel to another region, or all were found unreachable.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::{ErrorKind, JitoClientResult}};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::builder().warm_regions(2).build().await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// if let Err(e) = client.send(&transactions).await {
+    ///     if e.kind() == ErrorKind::Network {
+    ///         let endpoint = client.fail_over()?;
+    ///         println!("Failed over to {endpoint}");
+    ///         client.send(&transactions).await?;
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fail_over(&mut self) -> JitoClientResult<&'static str> {
+        let warm = self.warm.as_ref().ok_or(JitoClientError::NoWarmRegion)?;
+        let state = |endpoint| {
+            self.hooks
+                .stats
+                .get(endpoint)
+                .and_then(|stats| stats.channel)
+        };
+        let others = || {
+            warm.channels()
+                .iter()
+                .filter(|channel| channel.endpoint != self.endpoint)
+        };
+        let channel = others()
+            .find(|channel| state(channel.endpoint) == Some(ChannelState::Ready))
+            .or_else(|| {
+                others().find(|channel| state(channel.endpoint) != Some(ChannelState::Unreachable))
+            })
+            .ok_or(JitoClientError::NoWarmRegion)?
+            .clone();
+        debug!(
+            "Failing over from {} to {}",
+            self.endpoint, channel.endpoint
+        );
+        warm.prefer(channel.endpoint);
+        self.switch_to(channel);
+        Ok(self.endpoint)
+    }
+
+    #[cfg(feature = "latency")]
+    // Switches to the warm region preferred by the keeper, if the region is re-evaluated and the keeper found a faster one than the client's
+    pub(crate) fn follow_preferred_region(&mut self) {
+        let Some(channel) = self
+            .warm
+            .as_ref()
+            .and_then(WarmChannels::preferred)
+            .filter(|channel| channel.endpoint != self.endpoint)
+            .cloned()
+        else {
+            return;
+        };
+        debug!(
+            "Switching from {} to the faster {}",
+            self.endpoint, channel.endpoint
+        );
+        self.switch_to(channel);
+    }
+
+    #[cfg(feature = "latency")]
+    // Sends to the block engine of a warm channel from now on, releasing what followed the old block engine
+    fn switch_to(&mut self, channel: WarmChannel) {
+        self.stop_background_tasks();
+        #[cfg(feature = "rpc")]
+        {
+            self.path = self.path.follow(self.endpoint, channel.endpoint);
+            self.transactions = std::sync::OnceLock::new();
         }
-        Ok(Self {
-            max_retries,
-            min_wait,
-            max_wait,
-        })
+        self.client = channel.client;
+        self.endpoint = channel.endpoint;
     }
 
-    pub fn jitter(&self) -> std::time::Duration {
-        std::time::Duration::from_millis(rand::random_range(self.min_wait..=self.max_wait))
+    #[cfg(feature = "latency")]
+    /// Returns a `FanoutClient` sending to this client's block engine and every warm region, over the connections already open.
+    ///
+    /// The fan-out clients share this client's latency statistics and request signers. Without warm regions, it only sends to this client's block engine.
+    pub fn warm_fanout(&self) -> FanoutClient {
+        let own =
+            JitoClient::from_searcher(self.client.clone(), self.endpoint, self.hooks.stats.clone());
+        let warm = self
+            .warm
+            .iter()
+            .flat_map(|warm| warm.channels())
+            .filter(|channel| channel.endpoint != self.endpoint)
+            .map(|channel| {
+                JitoClient::from_searcher(
+                    channel.client.clone(),
+                    channel.endpoint,
+                    self.hooks.stats.clone(),
+                )
+            });
+        let clients = std::iter::once(own)
+            .chain(warm)
+            .map(|client| client.with_signer(self.signer.clone()))
+            .collect();
+        FanoutClient::new(clients)
+    }
+
+    /// Returns all available node regions that can be used for connections.
+    pub fn all_regions() -> &'static [NodeRegion] {
+        NodeRegion::all()
+    }
+}
+
+impl JitoClient {
+    fn stop_background_tasks(&mut self) {
+        if let Some(tracker) = self.tracker.take()
+            && self.owns_tracker
+        {
+            tracker.close();
+        }
+    }
+}
+
+impl Drop for JitoClient {
+    fn drop(&mut self) {
+        self.stop_background_tasks();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::expiry::SlotDeadline;
+    use crate::testing::{MOCK_REGION, MOCK_TIP_ACCOUNT, MockBlockEngine};
     use serial_test::serial;
     use solana_keypair::{Keypair, Signer};
     use solana_program::{
@@ -245,25 +991,237 @@ mod tests {
         pubkey::Pubkey,
     };
     use solana_system_interface::instruction::transfer;
-    use solana_transaction::{Message, VersionedMessage};
+    use solana_transaction::{Message, VersionedMessage, versioned::VersionedTransaction};
     use std::str::FromStr;
+    use tokio::sync::watch;
+    use tonic::transport::Channel;
 
     const SERVER_URL1: &str = "https://ny.mainnet.block-engine.jito.wtf:443";
     const SERVER_URL2: &str = "https://ny.testnet.block-engine.jito.wtf:443";
 
+    #[tokio::test]
+    async fn late_bundle_is_rejected_locally() {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let mut client = JitoClient::from_searcher(
+            SignerInterceptor::default().client(channel),
+            "http://127.0.0.1:1",
+            LatencyStats::default(),
+        );
+        let (_sender, slots) = watch::channel(101);
+
+        let result = client
+            .send_with_slot_deadline(&[], RetryLogic::new(1), SlotDeadline::at(100), slots)
+            .await;
+        assert!(matches!(
+            result,
+            Err(JitoClientError::SlotDeadlinePassed {
+                current: 101,
+                last: 100
+            })
+        ));
+        assert_eq!(client.region_stats().get("http://127.0.0.1:1"), None);
+    }
+
+    // Answers a single JSON-RPC request with `result`, returning the request received
+    #[cfg(feature = "rpc")]
+    async fn json_rpc_server(result: String) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            // The body is small enough to arrive with the headers
+            while !request.ends_with(b"}") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let body = format!(r#"{{"jsonrpc":"2.0","result":"{result}","id":1}}"#);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, server)
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn unavailable_grpc_falls_back_to_http() {
+        let (url, server) = json_rpc_server("http-uuid".to_string()).await;
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let mut client = JitoClient::from_searcher(
+            SignerInterceptor::default().client(channel),
+            "http://127.0.0.1:1",
+            LatencyStats::default(),
+        )
+        .with_submit_path(SubmitPath::GrpcWithHttpFallback(SolanaRpc::new(url)));
+
+        let uuid = client.send(&[memo_transaction()]).await.unwrap();
+        assert_eq!(uuid, "http-uuid");
+        let request = server.await.unwrap();
+        assert!(request.contains("sendBundle"));
+        assert!(request.contains(r#""encoding":"base64""#));
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn timed_out_grpc_does_not_fall_back_to_http() {
+        use crate::testing::MockFault;
+
+        let (url, server) = json_rpc_server("http-uuid".to_string()).await;
+        let mock = MockBlockEngine::start().await.unwrap();
+        mock.inject(MockFault::Reject(
+            tonic::Code::DeadlineExceeded,
+            "deadline exceeded".to_string(),
+        ));
+        let mut client = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_submit_path(SubmitPath::GrpcWithHttpFallback(SolanaRpc::new(url)));
+
+        let error = client.send(&[memo_transaction()]).await.unwrap_err();
+        assert!(
+            matches!(error.inner(), JitoClientError::SendError(status) if status.code() == tonic::Code::DeadlineExceeded)
+        );
+        assert!(!server.is_finished());
+        server.abort();
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn single_transaction_is_sent_to_transaction_endpoint() {
+        let transaction = memo_transaction();
+        let signature = transaction.signatures[0];
+        let (url, server) = json_rpc_server(signature.to_string()).await;
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let client = JitoClient::from_searcher(
+            SignerInterceptor::default().client(channel),
+            Box::leak(url.into_boxed_str()),
+            LatencyStats::default(),
+        );
+
+        assert_eq!(
+            client.send_transaction(&transaction).await.unwrap(),
+            signature
+        );
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/v1/transactions"));
+        assert!(request.contains("sendTransaction"));
+    }
+
+    // The shared tests below run against the in-process mock block engine. Their live variants, against Jito's public block engines,
+    // need network access and are ignored by default, run them with `cargo test -- --ignored`
+
+    async fn connects_with_default_timeout(endpoint: &'static str) {
+        let client = JitoClient::new(endpoint, None)
+            .await
+            .expect("Failed to create client");
+        assert_eq!(client.get_endpoint(), endpoint);
+    }
+
+    async fn sends_bundle(endpoint: &'static str) -> String {
+        let mut client = JitoClient::new(endpoint, None)
+            .await
+            .expect("Failed to create client");
+        client
+            .send(&[memo_transaction()])
+            .await
+            .expect("Send error")
+    }
+
+    async fn sends_with_retries(endpoint: &'static str) -> JitoClientResult<String> {
+        let mut client = JitoClient::new(endpoint, None)
+            .await
+            .expect("Failed to create client");
+        client
+            .send_with_retry(&[memo_transaction()], RetryLogic::new(3))
+            .await
+    }
+
+    async fn sends_batch(endpoint: &'static str) -> Vec<JitoClientResult<String>> {
+        let client = JitoClient::new(endpoint, None)
+            .await
+            .expect("Failed to create client");
+        let bundles = vec![vec![memo_transaction()], vec![memo_transaction()]];
+        let results = client.send_batch(bundles, 2).await;
+        assert_eq!(results.len(), 2);
+        results
+    }
+
+    async fn answers_searcher_queries(endpoint: &'static str) -> (TipAccounts, NextLeader) {
+        let mut client = JitoClient::new(endpoint, None)
+            .await
+            .expect("Failed to create client");
+        client.warm_up().await.expect("Warm-up error");
+        let accounts = client
+            .get_tip_accounts()
+            .await
+            .expect("Get tip accounts error");
+        let leader = client
+            .get_next_leader(&[])
+            .await
+            .expect("Get next leader error");
+        (accounts, leader)
+    }
+
+    #[tokio::test]
+    async fn mock_custom_endpoint_default_timeout() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        connects_with_default_timeout(mock.endpoint()).await;
+    }
+
+    #[tokio::test]
+    async fn mock_send_endpoint() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let uuid = sends_bundle(mock.endpoint()).await;
+        assert!(!uuid.is_empty());
+        let bundles = mock.bundles();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].packets.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn mock_send_with_retries() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        assert!(sends_with_retries(mock.endpoint()).await.is_ok());
+        assert_eq!(mock.bundles().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn mock_send_batch_endpoint() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let results = sends_batch(mock.endpoint()).await;
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(mock.bundles().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn mock_searcher_queries() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let (accounts, leader) = answers_searcher_queries(mock.endpoint()).await;
+        assert_eq!(accounts.0, [Pubkey::from_str(MOCK_TIP_ACCOUNT).unwrap()]);
+        assert_eq!(leader.slot, 1004);
+        assert_eq!(leader.region, MOCK_REGION);
+    }
+
     #[tokio::test]
     #[serial]
+    #[ignore = "connects to Jito's public block engines"]
     async fn custom_endpoint_default_timeout() {
-        match JitoClient::new(SERVER_URL2, None).await {
-            Ok(client) => println!("Get Endpoint: {}", client.get_endpoint()),
-            Err(e) => panic!("Error in creating client: {e}"),
-        }
+        connects_with_default_timeout(SERVER_URL2).await;
     }
 
+    #[cfg(feature = "latency")]
     #[tokio::test]
     #[serial]
+    #[ignore = "connects to Jito's public block engines"]
     async fn dynamic_region_custom_timeout() {
-        match JitoClient::new_dynamic_region(Some(5)).await {
+        match JitoClient::new_dynamic_region_secs(Some(5)).await {
             Ok(client) => println!("Get Endpoint: {}", client.get_endpoint()),
             Err(e) => panic!("Error in creating client: {e}"),
         }
@@ -271,47 +1229,49 @@ mod tests {
 
     #[tokio::test]
     #[serial]
+    #[ignore = "connects to Jito's public block engines"]
     async fn send_endpoint() {
         let start = std::time::Instant::now();
-        let mut client = JitoClient::new(SERVER_URL1, None)
-            .await
-            .expect("Failed to create client");
-
-        let signer_keypair = Keypair::new();
-        let bh = Hash::new_unique();
-        let tip_account = Pubkey::from_str("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5").unwrap();
-
-        let ix = Instruction {
-            program_id: Pubkey::from_str("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo").unwrap(),
-            accounts: vec![AccountMeta::new(signer_keypair.pubkey(), true)],
-            data: b"test".to_vec(),
-        };
-        let txns = vec![
-            ix,
-            transfer(&signer_keypair.pubkey(), &tip_account, 100_000),
-        ];
-        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
-            &txns,
-            Some(&signer_keypair.pubkey()),
-            &bh,
-        ));
-        let transaction = VersionedTransaction::try_new(message, &[signer_keypair]).unwrap();
+        println!("bundle id: {}", sends_bundle(SERVER_URL1).await);
+        println!("Elapsed: {} ms", start.elapsed().as_millis());
+    }
 
-        match client.send(&[transaction]).await {
+    #[tokio::test]
+    #[serial]
+    #[ignore = "connects to Jito's public block engines"]
+    async fn send_with_retries() {
+        let start = std::time::Instant::now();
+        match sends_with_retries(SERVER_URL2).await {
             Ok(out) => println!("bundle id: {out}"),
-            Err(e) => panic!("Send error: {e}"),
+            Err(e) => println!("Send error: {e}"),
         }
         println!("Elapsed: {} ms", start.elapsed().as_millis());
     }
 
     #[tokio::test]
     #[serial]
-    async fn send_with_retries() {
+    #[ignore = "connects to Jito's public block engines"]
+    async fn send_batch_endpoint() {
         let start = std::time::Instant::now();
-        let mut client = JitoClient::new(SERVER_URL2, None)
-            .await
-            .expect("Failed to create client");
+        for result in sends_batch(SERVER_URL1).await {
+            match result {
+                Ok(out) => println!("bundle id: {out}"),
+                Err(e) => println!("Send error: {e}"),
+            }
+        }
+        println!("Elapsed: {} ms", start.elapsed().as_millis());
+    }
 
+    #[tokio::test]
+    #[serial]
+    #[ignore = "connects to Jito's public block engines"]
+    async fn searcher_queries() {
+        let (accounts, leader) = answers_searcher_queries(SERVER_URL1).await;
+        println!("Tip accounts: {:?}", accounts.0);
+        println!("Next leader: {} at slot {}", leader.identity, leader.slot);
+    }
+
+    fn memo_transaction() -> VersionedTransaction {
         let signer_keypair = Keypair::new();
         let bh = Hash::new_unique();
         let tip_account = Pubkey::from_str("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5").unwrap();
@@ -330,15 +1290,6 @@ mod tests {
             Some(&signer_keypair.pubkey()),
             &bh,
         ));
-        let transaction = VersionedTransaction::try_new(message, &[signer_keypair]).unwrap();
-
-        match client
-            .send_with_retry(&[transaction], RetryLogic::new(3))
-            .await
-        {
-            Ok(out) => println!("bundle id: {out}"),
-            Err(e) => println!("Send error: {e}"),
-        }
-        println!("Elapsed: {} ms", start.elapsed().as_millis());
+        VersionedTransaction::try_new(message, &[signer_keypair]).unwrap()
     }
 }
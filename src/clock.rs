@@ -0,0 +1,308 @@
+use crate::grpc::shared::Header;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "native")]
+use std::time::Instant;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(feature = "native")]
+use tonic::metadata::MetadataMap;
+
+// Number of most recent samples the estimate is picked from
+const RECENT_SAMPLES: usize = 64;
+// Resolution of an HTTP `date` header, which is truncated to whole seconds
+const HTTP_DATE_RESOLUTION: Duration = Duration::from_secs(1);
+
+/// Estimated offset between the local clock and a block engine's clock.
+///
+/// Round trips and slot deadlines are measured on the monotonic clock, which skew doesn't affect. Converting between local wall-clock
+/// times and the block engine's, such as bundle header timestamps or the start time of a slot reported by another host, goes through the skew.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    // Block engine time minus local time
+    offset_nanos: i64,
+    uncertainty: Duration,
+    measured_at: SystemTime,
+}
+
+impl ClockSkew {
+    /// Returns the block engine's clock minus the local clock in milliseconds, positive when the local clock is behind.
+    pub fn offset_millis(&self) -> i64 {
+        self.offset_nanos / 1_000_000
+    }
+
+    /// Returns the most the offset may be off by, half the round trip it was measured over plus the resolution of the server's time.
+    pub fn uncertainty(&self) -> Duration {
+        self.uncertainty
+    }
+
+    /// Returns the local time the offset was measured at.
+    pub fn measured_at(&self) -> SystemTime {
+        self.measured_at
+    }
+
+    /// Returns whether the local clock is ahead of the block engine's by more than the uncertainty of the estimate.
+    pub fn local_ahead(&self) -> bool {
+        self.offset_nanos < 0
+            && self.offset_nanos.unsigned_abs() > self.uncertainty.as_nanos() as u64
+    }
+
+    /// Returns whether the local clock is behind the block engine's by more than the uncertainty of the estimate.
+    pub fn local_behind(&self) -> bool {
+        self.offset_nanos > 0
+            && self.offset_nanos.unsigned_abs() > self.uncertainty.as_nanos() as u64
+    }
+
+    /// Converts a local time to the block engine's clock.
+    pub fn to_remote(&self, local: SystemTime) -> SystemTime {
+        shift(local, self.offset_nanos)
+    }
+
+    /// Converts a time on the block engine's clock to the local clock.
+    pub fn to_local(&self, remote: SystemTime) -> SystemTime {
+        shift(remote, self.offset_nanos.saturating_neg())
+    }
+
+    /// Returns the current time on the block engine's clock.
+    pub fn remote_now(&self) -> SystemTime {
+        self.to_remote(SystemTime::now())
+    }
+}
+
+/// Estimates the offset between the local clock and a block engine's clock from server timestamps.
+///
+/// Samples come from the HTTP `date` header of responses, timed by their round trip as in NTP, or from the header timestamp of streamed messages,
+/// given the expected one-way delay. The estimate is the recent sample with the lowest uncertainty. Cheap to clone, clones share their samples.
+///
+/// `JitoClient::measure_clock_skew` samples the client's block engine on demand. Bundle results are streamed without a header timestamp,
+/// so the client also samples the `date` header of every bundle result subscription it opens, and an `Authenticator` that of its auth responses.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None).await?;
+/// let skew = client.measure_clock_skew(5).await?;
+/// println!("Block engine clock is {} ms ahead, ± {:?}", skew.offset_millis(), skew.uncertainty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClockSkewEstimator {
+    samples: Arc<Mutex<VecDeque<ClockSkew>>>,
+}
+
+impl ClockSkewEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a server time received in a response.
+    ///
+    /// # Arguments
+    /// * `received_at` - Local time the response was received
+    /// * `round_trip` - Time from sending the request to receiving the response, measured on the monotonic clock
+    /// * `remote` - Server time reported in the response
+    /// * `resolution` - Resolution the server time is truncated to, such as a second for an HTTP `date` header
+    pub fn record_round_trip(
+        &self,
+        received_at: SystemTime,
+        round_trip: Duration,
+        remote: SystemTime,
+        resolution: Duration,
+    ) {
+        // The server time was read halfway through the round trip, somewhere within its resolution
+        let local = received_at
+            .checked_sub(round_trip / 2)
+            .unwrap_or(received_at);
+        self.push(ClockSkew {
+            offset_nanos: offset_nanos(remote + resolution / 2, local),
+            uncertainty: round_trip / 2 + resolution / 2,
+            measured_at: received_at,
+        });
+    }
+
+    /// Records the value of an HTTP `date` header, returning whether it could be parsed.
+    pub fn record_date_header(
+        &self,
+        received_at: SystemTime,
+        round_trip: Duration,
+        date: &str,
+    ) -> bool {
+        match parse_http_date(date) {
+            Some(remote) => {
+                self.record_round_trip(received_at, round_trip, remote, HTTP_DATE_RESOLUTION);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Records the header timestamp of a message streamed by the block engine, returning whether the header carries one.
+    ///
+    /// # Arguments
+    /// * `header` - Header of the message, stamped by the block engine when the message was generated
+    /// * `received_at` - Local time the message was received
+    /// * `one_way` - Expected delay from the block engine to the client, such as half the round trip in `JitoClient::region_stats`
+    pub fn record_header(
+        &self,
+        header: &Header,
+        received_at: SystemTime,
+        one_way: Duration,
+    ) -> bool {
+        let Some(remote) = header.ts.and_then(|ts| SystemTime::try_from(ts).ok()) else {
+            return false;
+        };
+        self.push(ClockSkew {
+            offset_nanos: offset_nanos(remote + one_way, received_at),
+            uncertainty: one_way,
+            measured_at: received_at,
+        });
+        true
+    }
+
+    // Records the `date` header of a response to a request sent at `sent`, if it has one
+    #[cfg(feature = "native")]
+    pub(crate) fn record_response(&self, metadata: &MetadataMap, sent: Instant) {
+        let (received_at, round_trip) = (SystemTime::now(), sent.elapsed());
+        if let Some(date) = metadata.get("date").and_then(|date| date.to_str().ok()) {
+            self.record_date_header(received_at, round_trip, date);
+        }
+    }
+
+    /// Returns the recent sample with the lowest uncertainty, the most recent among equals, or `None` if nothing was recorded.
+    pub fn estimate(&self) -> Option<ClockSkew> {
+        self.lock()
+            .iter()
+            .rev()
+            .min_by_key(|sample| sample.uncertainty)
+            .copied()
+    }
+
+    /// Clears every sample, such as after the host's clock was stepped.
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn push(&self, sample: ClockSkew) {
+        let mut samples = self.lock();
+        if samples.len() == RECENT_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<ClockSkew>> {
+        self.samples.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// `a` minus `b` in nanoseconds, saturating
+fn offset_nanos(a: SystemTime, b: SystemTime) -> i64 {
+    match a.duration_since(b) {
+        Ok(ahead) => i64::try_from(ahead.as_nanos()).unwrap_or(i64::MAX),
+        Err(behind) => i64::try_from(behind.duration().as_nanos()).map_or(i64::MIN, |n| -n),
+    }
+}
+
+fn shift(time: SystemTime, nanos: i64) -> SystemTime {
+    let by = Duration::from_nanos(nanos.unsigned_abs());
+    if nanos >= 0 {
+        time.checked_add(by).unwrap_or(time)
+    } else {
+        time.checked_sub(by).unwrap_or(time)
+    }
+}
+
+// Parses an IMF-fixdate, the format of HTTP `date` headers, such as `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_http_date(date: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (_, date) = date.trim().split_once(", ")?;
+    let mut parts = date.split(' ');
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|&m| m == month)? as u32 + 1;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if parts.next()? != "GMT" || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60
+    {
+        return None;
+    }
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(
+        days * 86_400 + hour * 3600 + minute * 60 + second,
+    ))
+}
+
+// Days from the Unix epoch to a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    // Months counted from March, so the leap day ends the year
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_is_estimated_from_the_tightest_sample() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+
+        let estimator = ClockSkewEstimator::new();
+        assert_eq!(estimator.estimate(), None);
+        let received_at = UNIX_EPOCH + Duration::from_secs(1_000);
+        // The block engine is 2s ahead, read 50ms before the response arrived
+        let remote = received_at + Duration::from_millis(1_950);
+        estimator.record_round_trip(
+            received_at,
+            Duration::from_millis(400),
+            remote,
+            Duration::ZERO,
+        );
+        estimator.record_round_trip(
+            received_at,
+            Duration::from_millis(100),
+            remote,
+            Duration::ZERO,
+        );
+        let skew = estimator.estimate().unwrap();
+        assert_eq!(skew.offset_millis(), 2_000);
+        assert_eq!(skew.uncertainty(), Duration::from_millis(50));
+        assert!(skew.local_behind() && !skew.local_ahead());
+        assert_eq!(skew.to_local(skew.to_remote(received_at)), received_at);
+
+        // A stream header stamped 1s behind the local clock, 10ms before it arrived
+        let header = Header {
+            ts: Some((received_at - Duration::from_millis(1_010)).into()),
+        };
+        assert!(estimator.record_header(&header, received_at, Duration::from_millis(10)));
+        let skew = estimator.estimate().unwrap();
+        assert_eq!(skew.offset_millis(), -1_000);
+        assert!(skew.local_ahead());
+        assert_eq!(
+            skew.to_remote(received_at),
+            received_at - Duration::from_secs(1)
+        );
+
+        estimator.reset();
+        assert_eq!(estimator.estimate(), None);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use solana_pubkey::Pubkey;
+use solana_transaction::Instruction;
+use solana_transaction::versioned::VersionedTransaction;
+
+/// Program whose instructions set a transaction's compute unit limit and price.
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ComputeBudget111111111111111111111111111111");
+
+// Instruction discriminants of the compute budget program
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Compute unit limit and priority fee of a transaction.
+///
+/// The priority fee paid by a bundle's transactions counts towards its auction bid alongside the tip, so set it deliberately.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::compute_budget::ComputeBudget;
+/// # use solana_transaction::Instruction;
+/// let mut instructions: Vec<Instruction> = vec![];
+/// ComputeBudget::new(200_000, 10_000).prepend_to(&mut instructions);
+/// assert_eq!(ComputeBudget::from_instructions(&instructions), ComputeBudget::new(200_000, 10_000));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// Maximum compute units the transaction may consume
+    pub unit_limit: Option<u32>,
+    /// Price of a compute unit in micro-lamports
+    pub unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// Sets both the compute unit limit and price.
+    pub fn new(unit_limit: u32, unit_price: u64) -> Self {
+        Self {
+            unit_limit: Some(unit_limit),
+            unit_price: Some(unit_price),
+        }
+    }
+
+    /// Reads the compute budget set by the instructions.
+    pub fn from_instructions(instructions: &[Instruction]) -> Self {
+        let mut budget = Self::default();
+        for instruction in instructions {
+            if instruction.program_id == COMPUTE_BUDGET_PROGRAM_ID {
+                budget.read(&instruction.data);
+            }
+        }
+        budget
+    }
+
+    /// Reads the compute budget set by a transaction's instructions.
+    pub fn from_transaction(txn: &VersionedTransaction) -> Self {
+        let keys = txn.message.static_account_keys();
+        let mut budget = Self::default();
+        for instruction in txn.message.instructions() {
+            if keys.get(instruction.program_id_index as usize) == Some(&COMPUTE_BUDGET_PROGRAM_ID) {
+                budget.read(&instruction.data);
+            }
+        }
+        budget
+    }
+
+    // Records the limit or price set by one compute budget instruction, ignoring the program's other instructions
+    fn read(&mut self, data: &[u8]) {
+        match data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, limit)) => {
+                self.unit_limit = limit.try_into().ok().map(u32::from_le_bytes);
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, price)) => {
+                self.unit_price = price.try_into().ok().map(u64::from_le_bytes);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the instructions setting this budget.
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::with_capacity(2);
+        if let Some(limit) = self.unit_limit {
+            instructions.push(set_compute_unit_limit(limit));
+        }
+        if let Some(price) = self.unit_price {
+            instructions.push(set_compute_unit_price(price));
+        }
+        instructions
+    }
+
+    /// Prepends the instructions setting this budget, replacing any compute budget instructions setting the same limit or price.
+    ///
+    /// Call on a transaction's instructions before it is signed, a transaction cannot contain two instructions setting the same value.
+    pub fn prepend_to(&self, instructions: &mut Vec<Instruction>) {
+        instructions.retain(|instruction| {
+            if instruction.program_id != COMPUTE_BUDGET_PROGRAM_ID {
+                return true;
+            }
+            match instruction.data.first() {
+                Some(&SET_COMPUTE_UNIT_LIMIT) => self.unit_limit.is_none(),
+                Some(&SET_COMPUTE_UNIT_PRICE) => self.unit_price.is_none(),
+                _ => true,
+            }
+        });
+        instructions.splice(0..0, self.instructions());
+    }
+}
+
+/// Returns an instruction setting the transaction's compute unit limit.
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_LIMIT];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+/// Returns an instruction setting the transaction's compute unit price in micro-lamports.
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![SET_COMPUTE_UNIT_PRICE];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
+
+/// Checks that every transaction sets a compute unit price, so none of a bundle's transactions lands without a priority fee.
+///
+/// # Errors
+/// This function will return `MissingComputeBudget` with the index of the first transaction setting no compute unit price.
+pub fn require_unit_price(txns: &[VersionedTransaction]) -> JitoClientResult<()> {
+    match txns
+        .iter()
+        .position(|txn| ComputeBudget::from_transaction(txn).unit_price.is_none())
+    {
+        Some(index) => Err(JitoClientError::MissingComputeBudget(index)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_keypair::{Keypair, Signer};
+    use solana_system_interface::instruction::transfer;
+    use solana_transaction::{Message, VersionedMessage};
+
+    #[test]
+    fn prepended_budget_replaces_existing() {
+        let payer = Keypair::new();
+        let mut instructions = vec![
+            set_compute_unit_price(1),
+            transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000),
+        ];
+        ComputeBudget::new(300_000, 5_000).prepend_to(&mut instructions);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            ComputeBudget::from_instructions(&instructions),
+            ComputeBudget::new(300_000, 5_000)
+        );
+
+        let message = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer.pubkey())));
+        let priced = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        assert_eq!(
+            ComputeBudget::from_transaction(&priced),
+            ComputeBudget::new(300_000, 5_000)
+        );
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let unpriced = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        assert!(require_unit_price(std::slice::from_ref(&priced)).is_ok());
+        assert!(matches!(
+            require_unit_price(&[priced, unpriced]),
+            Err(JitoClientError::MissingComputeBudget(1))
+        ));
+    }
+}
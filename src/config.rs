@@ -0,0 +1,424 @@
+use crate::builder::{ChannelLimits, JitoClientBuilder, normalize_endpoint};
+use crate::bundle::BundleOptions;
+use crate::client::{Backoff, JitoClient, RetryLogic};
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::region::NodeRegion;
+use crate::tips::{TipPercentile, TipStrategy};
+use serde::Deserialize;
+use solana_keypair::{Keypair, read_keypair_file};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+const ENV_PREFIX: &str = "JITO_";
+
+/// Declarative client configuration, deserializable from TOML or read from `JITO_*` environment variables.
+///
+/// Every field is optional; unset fields keep the client defaults.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, config::JitoConfig, errors::JitoClientResult};
+/// # async fn example() -> JitoClientResult<()> {
+/// let config = JitoConfig::from_toml_str(
+///     r#"
+///     region = "ny"
+///     connect_timeout_ms = 2000
+///     request_timeout_ms = 500
+///
+///     [retry]
+///     max_retries = 3
+///     "#,
+/// )?;
+/// let client = JitoClient::from_config(&config).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JitoConfig {
+    /// Block engine endpoint URL. Takes precedence over `region`
+    pub endpoint: Option<String>,
+    /// Region to connect to, such as `ny` or `fra`. The fastest region is used if neither this nor `endpoint` is set
+    pub region: Option<String>,
+    /// Time allowed to establish the connection, in milliseconds
+    pub connect_timeout_ms: Option<u64>,
+    /// Deadline of each request, in milliseconds
+    pub request_timeout_ms: Option<u64>,
+    /// Interval of keep-alive pings on an idle connection, in milliseconds. Disabled if not set
+    pub keep_alive_ms: Option<u64>,
+    /// Retry policy for `send_with_retry`
+    pub retry: Option<RetryConfig>,
+    /// Maximum number of transactions in a bundle, see `BundleOptions::max_txns`
+    pub max_txns: Option<usize>,
+    /// Path to a keypair JSON file, used to authenticate with the block engine
+    pub keypair_path: Option<PathBuf>,
+    /// Bot name and version appended to the `user-agent`, see `JitoClientBuilder::user_agent`
+    pub user_agent: Option<String>,
+    /// Bounds on the resources of the client's channel, see `ChannelLimits`
+    pub channel_limits: Option<ChannelLimitsConfig>,
+    /// How bundles tip, see `TipStrategy`
+    pub tip: Option<TipConfig>,
+}
+
+/// Tip settings, see `TipStrategy`. Exactly one of `lamports`, `percentile` and `profit_share_bps` must be set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TipConfig {
+    /// Tips a fixed amount, see `TipStrategy::Fixed`
+    pub lamports: Option<u64>,
+    /// Tips a percentile of recently landed tips, one of 25, 50, 75, 95 or 99, see `TipStrategy::FloorPercentile`
+    pub percentile: Option<u8>,
+    /// Tips a share of the expected profit in basis points, see `TipStrategy::ProfitShare`
+    pub profit_share_bps: Option<u16>,
+    /// Lower bound of a profit share tip. Defaults to 0
+    pub min_lamports: Option<u64>,
+    /// Upper bound of a profit share tip. Unbounded if not set
+    pub max_lamports: Option<u64>,
+}
+
+/// Channel limit settings, see `ChannelLimits`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChannelLimitsConfig {
+    pub concurrency_limit: Option<usize>,
+    /// Requests allowed per `rate_limit_period_ms`
+    pub rate_limit: Option<u64>,
+    /// Period of the rate limit, in milliseconds. Defaults to a second
+    pub rate_limit_period_ms: Option<u64>,
+    pub buffer_size: Option<usize>,
+}
+
+impl From<&ChannelLimitsConfig> for ChannelLimits {
+    fn from(config: &ChannelLimitsConfig) -> Self {
+        let period = Duration::from_millis(config.rate_limit_period_ms.unwrap_or(1000));
+        Self {
+            concurrency_limit: config.concurrency_limit,
+            rate_limit: config.rate_limit.map(|limit| (limit, period)),
+            buffer_size: config.buffer_size,
+        }
+    }
+}
+
+/// Retry policy settings, see `RetryLogic`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    pub max_retries: u8,
+    pub min_wait_ms: Option<u64>,
+    pub max_wait_ms: Option<u64>,
+    /// Doubles the wait after every failed attempt, see `Backoff::Exponential`
+    pub exponential_backoff: bool,
+}
+
+impl JitoConfig {
+    /// Parses a configuration from a TOML string.
+    ///
+    /// # Errors
+    /// This function will return an error if the TOML is invalid or has unknown fields.
+    pub fn from_toml_str(toml: &str) -> JitoClientResult<Self> {
+        toml::from_str(toml).map_err(|e| JitoClientError::ConfigError(e.to_string()))
+    }
+
+    /// Reads a configuration from a TOML file.
+    ///
+    /// # Errors
+    /// This function will return an error if the file cannot be read or is invalid.
+    pub fn from_file(path: impl AsRef<Path>) -> JitoClientResult<Self> {
+        let path = path.as_ref();
+        let toml = std::fs::read_to_string(path)
+            .map_err(|e| JitoClientError::ConfigError(format!("{}: {e}", path.display())))?;
+        Self::from_toml_str(&toml)
+    }
+
+    /// Reads a configuration from environment variables named after the fields, prefixed with `JITO_`: `JITO_ENDPOINT`, `JITO_REGION`,
+    /// `JITO_CONNECT_TIMEOUT_MS`, `JITO_REQUEST_TIMEOUT_MS`, `JITO_KEEP_ALIVE_MS`, `JITO_MAX_RETRIES`, `JITO_RETRY_MIN_WAIT_MS`, `JITO_RETRY_MAX_WAIT_MS`,
+    /// `JITO_RETRY_EXPONENTIAL_BACKOFF`, `JITO_MAX_TXNS`, `JITO_KEYPAIR_PATH`, `JITO_USER_AGENT`, `JITO_CONCURRENCY_LIMIT`, `JITO_RATE_LIMIT`,
+    /// `JITO_RATE_LIMIT_PERIOD_MS`, `JITO_BUFFER_SIZE`, `JITO_TIP_LAMPORTS`, `JITO_TIP_PERCENTILE`, `JITO_TIP_PROFIT_SHARE_BPS`,
+    /// `JITO_TIP_MIN_LAMPORTS` and `JITO_TIP_MAX_LAMPORTS`.
+    ///
+    /// # Errors
+    /// This function will return an error if a numeric variable cannot be parsed.
+    pub fn from_env() -> JitoClientResult<Self> {
+        Self::from_vars(|name| std::env::var(format!("{ENV_PREFIX}{name}")).ok())
+    }
+
+    // Builds the configuration from a variable lookup, so parsing can be tested without touching the process environment
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> JitoClientResult<Self> {
+        let retry = match var("MAX_RETRIES") {
+            Some(max_retries) => Some(RetryConfig {
+                max_retries: parse_var("MAX_RETRIES", &max_retries)?,
+                min_wait_ms: var("RETRY_MIN_WAIT_MS")
+                    .map(|v| parse_var("RETRY_MIN_WAIT_MS", &v))
+                    .transpose()?,
+                max_wait_ms: var("RETRY_MAX_WAIT_MS")
+                    .map(|v| parse_var("RETRY_MAX_WAIT_MS", &v))
+                    .transpose()?,
+                exponential_backoff: var("RETRY_EXPONENTIAL_BACKOFF")
+                    .map(|v| parse_var("RETRY_EXPONENTIAL_BACKOFF", &v))
+                    .transpose()?
+                    .unwrap_or_default(),
+            }),
+            None => None,
+        };
+        let channel_limits = ChannelLimitsConfig {
+            concurrency_limit: var("CONCURRENCY_LIMIT")
+                .map(|v| parse_var("CONCURRENCY_LIMIT", &v))
+                .transpose()?,
+            rate_limit: var("RATE_LIMIT")
+                .map(|v| parse_var("RATE_LIMIT", &v))
+                .transpose()?,
+            rate_limit_period_ms: var("RATE_LIMIT_PERIOD_MS")
+                .map(|v| parse_var("RATE_LIMIT_PERIOD_MS", &v))
+                .transpose()?,
+            buffer_size: var("BUFFER_SIZE")
+                .map(|v| parse_var("BUFFER_SIZE", &v))
+                .transpose()?,
+        };
+        let tip = TipConfig {
+            lamports: var("TIP_LAMPORTS")
+                .map(|v| parse_var("TIP_LAMPORTS", &v))
+                .transpose()?,
+            percentile: var("TIP_PERCENTILE")
+                .map(|v| parse_var("TIP_PERCENTILE", &v))
+                .transpose()?,
+            profit_share_bps: var("TIP_PROFIT_SHARE_BPS")
+                .map(|v| parse_var("TIP_PROFIT_SHARE_BPS", &v))
+                .transpose()?,
+            min_lamports: var("TIP_MIN_LAMPORTS")
+                .map(|v| parse_var("TIP_MIN_LAMPORTS", &v))
+                .transpose()?,
+            max_lamports: var("TIP_MAX_LAMPORTS")
+                .map(|v| parse_var("TIP_MAX_LAMPORTS", &v))
+                .transpose()?,
+        };
+        Ok(Self {
+            endpoint: var("ENDPOINT"),
+            region: var("REGION"),
+            connect_timeout_ms: var("CONNECT_TIMEOUT_MS")
+                .map(|v| parse_var("CONNECT_TIMEOUT_MS", &v))
+                .transpose()?,
+            request_timeout_ms: var("REQUEST_TIMEOUT_MS")
+                .map(|v| parse_var("REQUEST_TIMEOUT_MS", &v))
+                .transpose()?,
+            keep_alive_ms: var("KEEP_ALIVE_MS")
+                .map(|v| parse_var("KEEP_ALIVE_MS", &v))
+                .transpose()?,
+            retry,
+            max_txns: var("MAX_TXNS")
+                .map(|v| parse_var("MAX_TXNS", &v))
+                .transpose()?,
+            keypair_path: var("KEYPAIR_PATH").map(PathBuf::from),
+            user_agent: var("USER_AGENT"),
+            channel_limits: (channel_limits != ChannelLimitsConfig::default())
+                .then_some(channel_limits),
+            tip: (tip != TipConfig::default()).then_some(tip),
+        })
+    }
+
+    /// Returns a client builder with the configured endpoint or region, timeouts, keep-alive, user agent and channel limits.
+    ///
+    /// # Errors
+    /// This function will return an error if the endpoint is malformed or the region is unknown.
+    pub fn builder(&self) -> JitoClientResult<JitoClientBuilder> {
+        let mut builder = JitoClientBuilder::new();
+        if let Some(endpoint) = &self.endpoint {
+            builder = builder.endpoint_owned(normalize_endpoint(endpoint)?);
+        } else if let Some(region) = &self.region {
+            builder = builder.endpoint(NodeRegion::from_str(region)?.endpoint());
+        }
+        if let Some(ms) = self.connect_timeout_ms {
+            builder = builder.connect_timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.request_timeout_ms {
+            builder = builder.request_timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.keep_alive_ms {
+            builder = builder.keep_alive(Duration::from_millis(ms));
+        }
+        if let Some(agent) = &self.user_agent {
+            builder = builder.user_agent(agent.clone());
+        }
+        if let Some(limits) = &self.channel_limits {
+            builder = builder.channel_limits(limits.into());
+        }
+        Ok(builder)
+    }
+
+    /// Returns the configured retry policy, if any.
+    ///
+    /// # Errors
+    /// This function will return an error if the wait bounds are invalid.
+    pub fn retry_logic(&self) -> JitoClientResult<Option<RetryLogic>> {
+        let Some(retry) = &self.retry else {
+            return Ok(None);
+        };
+        let default = RetryLogic::new(retry.max_retries);
+        let backoff = match retry.exponential_backoff {
+            true => Backoff::Exponential,
+            false => Backoff::Constant,
+        };
+        RetryLogic::builder()
+            .max_retries(retry.max_retries)
+            .bounds_ms(
+                retry.min_wait_ms.unwrap_or(default.min_wait),
+                retry.max_wait_ms.unwrap_or(default.max_wait),
+            )
+            .backoff(backoff)
+            .build()
+            .map(Some)
+    }
+
+    /// Returns the configured tip strategy, if any.
+    ///
+    /// # Errors
+    /// This function will return an error if more or less than one strategy is set, or the percentile is not one of 25, 50, 75, 95 or 99.
+    pub fn tip_strategy(&self) -> JitoClientResult<Option<TipStrategy>> {
+        let Some(tip) = &self.tip else {
+            return Ok(None);
+        };
+        let strategy = match (tip.lamports, tip.percentile, tip.profit_share_bps) {
+            (Some(lamports), None, None) => TipStrategy::Fixed(lamports),
+            (None, Some(percentile), None) => TipStrategy::FloorPercentile(match percentile {
+                25 => TipPercentile::P25,
+                50 => TipPercentile::P50,
+                75 => TipPercentile::P75,
+                95 => TipPercentile::P95,
+                99 => TipPercentile::P99,
+                _ => {
+                    return Err(JitoClientError::ConfigError(format!(
+                        "tip.percentile: invalid value {percentile}"
+                    )));
+                }
+            }),
+            (None, None, Some(basis_points)) => TipStrategy::ProfitShare {
+                basis_points,
+                min: tip.min_lamports.unwrap_or(0),
+                max: tip.max_lamports.unwrap_or(u64::MAX),
+            },
+            _ => {
+                return Err(JitoClientError::ConfigError(
+                    "tip: set exactly one of lamports, percentile and profit_share_bps".to_string(),
+                ));
+            }
+        };
+        Ok(Some(strategy))
+    }
+
+    /// Reads the configured keypair file, if any.
+    ///
+    /// # Errors
+    /// This function will return an error if the keypair file cannot be read.
+    pub fn keypair(&self) -> JitoClientResult<Option<Keypair>> {
+        let Some(path) = &self.keypair_path else {
+            return Ok(None);
+        };
+        read_keypair_file(path)
+            .map(Some)
+            .map_err(|e| JitoClientError::ConfigError(format!("{}: {e}", path.display())))
+    }
+}
+
+impl JitoClient {
+    /// Creates a new gRPC client from a `JitoConfig`.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The configured region is unknown
+    /// - Region latency measurement fails
+    /// - Connection to the endpoint fails
+    pub async fn from_config(config: &JitoConfig) -> JitoClientResult<Self> {
+        let client = config.builder()?.build().await?;
+        Ok(match config.max_txns {
+            Some(max_txns) => client.with_bundle_options(BundleOptions {
+                max_txns: Some(max_txns),
+                ..BundleOptions::default()
+            }),
+            None => client,
+        })
+    }
+}
+
+fn parse_var<T: FromStr>(name: &str, value: &str) -> JitoClientResult<T> {
+    value.parse().map_err(|_| {
+        JitoClientError::ConfigError(format!("{ENV_PREFIX}{name}: invalid value {value}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn toml_and_env_agree() {
+        let from_toml = JitoConfig::from_toml_str(
+            r#"
+            region = "fra"
+            request_timeout_ms = 500
+            keep_alive_ms = 30000
+            user_agent = "arb-bot/2.1"
+
+            [retry]
+            max_retries = 3
+            max_wait_ms = 40
+
+            [channel_limits]
+            concurrency_limit = 16
+            rate_limit = 100
+
+            [tip]
+            profit_share_bps = 5000
+            min_lamports = 1000
+            "#,
+        )
+        .unwrap();
+
+        let vars = HashMap::from([
+            ("REGION", "fra"),
+            ("REQUEST_TIMEOUT_MS", "500"),
+            ("KEEP_ALIVE_MS", "30000"),
+            ("USER_AGENT", "arb-bot/2.1"),
+            ("MAX_RETRIES", "3"),
+            ("RETRY_MAX_WAIT_MS", "40"),
+            ("CONCURRENCY_LIMIT", "16"),
+            ("RATE_LIMIT", "100"),
+            ("TIP_PROFIT_SHARE_BPS", "5000"),
+            ("TIP_MIN_LAMPORTS", "1000"),
+        ]);
+        let from_env = JitoConfig::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(from_toml, from_env);
+
+        let retry = from_toml.retry_logic().unwrap().unwrap();
+        assert_eq!(
+            (retry.max_retries, retry.min_wait, retry.max_wait),
+            (3, 5, 40)
+        );
+        let limits = ChannelLimits::from(from_toml.channel_limits.as_ref().unwrap());
+        assert_eq!(limits.concurrency_limit, Some(16));
+        assert_eq!(limits.rate_limit, Some((100, Duration::from_secs(1))));
+        assert_eq!(limits.buffer_size, None);
+        assert_eq!(
+            from_toml.tip_strategy().unwrap(),
+            Some(TipStrategy::ProfitShare {
+                basis_points: 5000,
+                min: 1000,
+                max: u64::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        assert!(JitoConfig::from_toml_str("timeout = 5").is_err());
+        assert!(JitoConfig::from_vars(|_| Some("soon".to_string())).is_err());
+        for tip in [
+            "lamports = 1000\npercentile = 75",
+            "percentile = 60",
+            "min_lamports = 1000",
+        ] {
+            let config = JitoConfig::from_toml_str(&format!("[tip]\n{tip}")).unwrap();
+            assert!(config.tip_strategy().is_err(), "{tip}");
+        }
+    }
+}
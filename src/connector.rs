@@ -0,0 +1,499 @@
+use crate::resolver::Resolver;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use futures::future;
+use hyper_util::rt::TokioIo;
+use std::future::Future;
+use std::io;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time;
+use tonic::codegen::http::Uri;
+use tower_service::Service;
+
+// Upper bound on the size of a CONNECT response header, to not read forever from a misbehaving proxy
+const MAX_CONNECT_RESPONSE: usize = 8192;
+
+// How long connection attempts to the non-preferred address family wait, giving the preferred family a head start
+const FALLBACK_DELAY: Duration = Duration::from_millis(250);
+
+const SOCKS_VERSION: u8 = 0x05;
+const SOCKS_NO_AUTH: u8 = 0x00;
+const SOCKS_USER_PASS: u8 = 0x02;
+const SOCKS_CONNECT: u8 = 0x01;
+const SOCKS_IPV4: u8 = 0x01;
+const SOCKS_DOMAIN: u8 = 0x03;
+const SOCKS_IPV6: u8 = 0x04;
+
+/// A proxy to tunnel the block engine connection through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Proxy {
+    /// An HTTP proxy supporting the CONNECT method, at `host:port`
+    Http {
+        address: String,
+        credentials: Option<ProxyCredentials>,
+    },
+    /// A SOCKS5 proxy, at `host:port`. The block engine host name is resolved by the proxy
+    Socks5 {
+        address: String,
+        credentials: Option<ProxyCredentials>,
+    },
+}
+
+/// Which IP address family to connect over when a host resolves to both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpPreference {
+    /// Races all addresses at once, and uses whichever connects first
+    #[default]
+    Any,
+    /// Tries IPv4 addresses first, falling back to IPv6 after a short delay
+    PreferV4,
+    /// Tries IPv6 addresses first, falling back to IPv4 after a short delay
+    PreferV6,
+}
+
+impl IpPreference {
+    fn prefers(&self, ip: &IpAddr) -> bool {
+        match self {
+            Self::Any => true,
+            Self::PreferV4 => ip.is_ipv4(),
+            Self::PreferV6 => ip.is_ipv6(),
+        }
+    }
+}
+
+/// Username and password to authenticate with a proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Proxy {
+    /// An HTTP CONNECT proxy at `address`, given as `host:port`.
+    pub fn http(address: impl Into<String>) -> Self {
+        Self::Http {
+            address: address.into(),
+            credentials: None,
+        }
+    }
+
+    /// A SOCKS5 proxy at `address`, given as `host:port`.
+    pub fn socks5(address: impl Into<String>) -> Self {
+        Self::Socks5 {
+            address: address.into(),
+            credentials: None,
+        }
+    }
+
+    /// Authenticates with the proxy using a username and password.
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        let new = Some(ProxyCredentials {
+            username: username.into(),
+            password: password.into(),
+        });
+        match &mut self {
+            Self::Http { credentials, .. } | Self::Socks5 { credentials, .. } => *credentials = new,
+        }
+        self
+    }
+
+    // Connects to the proxy and opens a tunnel to `host:port` through it
+    async fn tunnel(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        match self {
+            Self::Http {
+                address,
+                credentials,
+            } => {
+                let mut stream = TcpStream::connect(address.as_str()).await?;
+                stream.set_nodelay(true)?;
+                http_connect(&mut stream, host, port, credentials.as_ref()).await?;
+                Ok(stream)
+            }
+            Self::Socks5 {
+                address,
+                credentials,
+            } => {
+                let mut stream = TcpStream::connect(address.as_str()).await?;
+                stream.set_nodelay(true)?;
+                socks5_connect(&mut stream, host, port, credentials.as_ref()).await?;
+                Ok(stream)
+            }
+        }
+    }
+}
+
+// Tonic connector that opens every connection through a proxy. TLS is layered on top by tonic
+#[derive(Debug, Clone)]
+pub(crate) struct ProxyConnector {
+    proxy: Proxy,
+}
+
+impl ProxyConnector {
+    pub(crate) fn new(proxy: Proxy) -> Self {
+        Self { proxy }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let proxy = self.proxy.clone();
+        Box::pin(async move {
+            let (host, port) = host_port(&uri)?;
+            proxy.tunnel(host, port).await.map(TokioIo::new)
+        })
+    }
+}
+
+// Tonic connector that resolves the host with a `Resolver`, then connects to the first reachable address
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvingConnector {
+    resolver: Resolver,
+    preference: IpPreference,
+}
+
+impl ResolvingConnector {
+    pub(crate) fn new(resolver: Resolver, preference: IpPreference) -> Self {
+        Self {
+            resolver,
+            preference,
+        }
+    }
+}
+
+impl Service<Uri> for ResolvingConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let resolver = self.resolver.clone();
+        let preference = self.preference;
+        Box::pin(async move {
+            let (host, port) = host_port(&uri)?;
+            let addrs = resolver.lookup(host).await.map_err(io::Error::other)?;
+            connect_any(&addrs, port, preference)
+                .await
+                .map(TokioIo::new)
+        })
+    }
+}
+
+// Tonic connector handing over a connection established beforehand, so the TLS handshake can be timed apart from the TCP connect.
+// Only the first connection is handed over, reconnects fail
+#[derive(Debug, Clone)]
+pub(crate) struct EstablishedConnector {
+    stream: Arc<Mutex<Option<TcpStream>>>,
+}
+
+impl EstablishedConnector {
+    pub(crate) fn new(stream: TcpStream) -> Self {
+        Self {
+            stream: Arc::new(Mutex::new(Some(stream))),
+        }
+    }
+}
+
+impl Service<Uri> for EstablishedConnector {
+    type Response = TokioIo<TcpStream>;
+    type Error = io::Error;
+    type Future = future::Ready<io::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let stream = self.stream.lock().unwrap_or_else(|e| e.into_inner()).take();
+        future::ready(
+            stream.map(TokioIo::new).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotConnected, "Connection already used")
+            }),
+        )
+    }
+}
+
+// Happy eyeballs: attempts all addresses concurrently, giving the preferred family a head start, and returns the first connection established
+pub(crate) async fn connect_any(
+    addrs: &[IpAddr],
+    port: u16,
+    preference: IpPreference,
+) -> io::Result<TcpStream> {
+    if addrs.is_empty() {
+        return Err(io::Error::other("No address to connect to"));
+    }
+    let has_preferred = addrs.iter().any(|ip| preference.prefers(ip));
+    let attempts = addrs.iter().map(|ip| {
+        let ip = *ip;
+        let delayed = has_preferred && !preference.prefers(&ip);
+        Box::pin(async move {
+            if delayed {
+                time::sleep(FALLBACK_DELAY).await;
+            }
+            TcpStream::connect((ip, port)).await
+        })
+    });
+    let (stream, _) = future::select_ok(attempts).await?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+// Host name and port of a URI, defaulting the port from the scheme
+pub(crate) fn host_port(uri: &Uri) -> io::Result<(&str, u16)> {
+    let host = uri
+        .host()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URI has no host"))?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = uri
+        .port_u16()
+        .unwrap_or(if uri.scheme_str() == Some("http") {
+            80
+        } else {
+            443
+        });
+    Ok((host, port))
+}
+
+async fn http_connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    credentials: Option<&ProxyCredentials>,
+) -> io::Result<()> {
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{host}]:{port}"),
+        _ => format!("{host}:{port}"),
+    };
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some(credentials) = credentials {
+        let token = STANDARD.encode(format!("{}:{}", credentials.username, credentials.password));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read one byte at a time so no bytes of the tunneled stream are consumed
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_CONNECT_RESPONSE {
+            return Err(io::Error::other("Proxy CONNECT response too large"));
+        }
+        response.push(stream.read_u8().await?);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some("200") => Ok(()),
+        _ => Err(io::Error::other(format!(
+            "Proxy refused CONNECT: {status_line}"
+        ))),
+    }
+}
+
+async fn socks5_connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    credentials: Option<&ProxyCredentials>,
+) -> io::Result<()> {
+    let method = match credentials {
+        Some(_) => SOCKS_USER_PASS,
+        None => SOCKS_NO_AUTH,
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(io::Error::other("Proxy is not a SOCKS5 proxy"));
+    }
+    if reply[1] != method {
+        return Err(io::Error::other(
+            "SOCKS5 proxy rejected the authentication method",
+        ));
+    }
+
+    if let Some(credentials) = credentials {
+        let mut auth = vec![0x01];
+        push_len_prefixed(&mut auth, credentials.username.as_bytes())?;
+        push_len_prefixed(&mut auth, credentials.password.as_bytes())?;
+        stream.write_all(&auth).await?;
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0x00 {
+            return Err(io::Error::other("SOCKS5 proxy authentication failed"));
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, SOCKS_CONNECT, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(SOCKS_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(SOCKS_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            request.push(SOCKS_DOMAIN);
+            push_len_prefixed(&mut request, host.as_bytes())?;
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await?;
+    if header[1] != 0x00 {
+        return Err(io::Error::other(format!(
+            "SOCKS5 proxy refused connection, reply code {}",
+            header[1]
+        )));
+    }
+    // Discard the bound address the proxy replies with
+    let address_len = match header[3] {
+        SOCKS_IPV4 => 4,
+        SOCKS_IPV6 => 16,
+        SOCKS_DOMAIN => stream.read_u8().await? as usize,
+        _ => return Err(io::Error::other("Invalid SOCKS5 reply")),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+fn push_len_prefixed(buf: &mut Vec<u8>, value: &[u8]) -> io::Result<()> {
+    let len = u8::try_from(value.len()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "SOCKS5 field longer than 255 bytes",
+        )
+    })?;
+    buf.push(len);
+    buf.extend_from_slice(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn http_connect_with_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\ntunneled")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let proxy = Proxy::http(address).with_credentials("user", "pass");
+        let mut stream = proxy.tunnel("example.com", 443).await.unwrap();
+        let mut tunneled = [0u8; 8];
+        stream.read_exact(&mut tunneled).await.unwrap();
+        assert_eq!(&tunneled, b"tunneled");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT example.com:443 HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwYXNz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn socks5_connect_with_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            stream
+                .write_all(&[SOCKS_VERSION, SOCKS_USER_PASS])
+                .await
+                .unwrap();
+            let mut auth = [0u8; 11];
+            stream.read_exact(&mut auth).await.unwrap();
+            stream.write_all(&[0x01, 0x00]).await.unwrap();
+            let mut request = [0u8; 7 + 11];
+            stream.read_exact(&mut request).await.unwrap();
+            stream
+                .write_all(&[SOCKS_VERSION, 0, 0, SOCKS_IPV4, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+            (greeting, auth, request)
+        });
+
+        let proxy = Proxy::socks5(address).with_credentials("user", "pass");
+        proxy.tunnel("example.com", 443).await.unwrap();
+
+        let (greeting, auth, request) = server.await.unwrap();
+        assert_eq!(greeting, [SOCKS_VERSION, 1, SOCKS_USER_PASS]);
+        assert_eq!(&auth, b"\x01\x04user\x04pass");
+        assert_eq!(
+            &request[..5],
+            &[SOCKS_VERSION, SOCKS_CONNECT, 0, SOCKS_DOMAIN, 11]
+        );
+        assert_eq!(&request[5..16], b"example.com");
+        assert_eq!(&request[16..], &443u16.to_be_bytes());
+    }
+
+    #[tokio::test]
+    async fn connect_any_skips_unreachable_addresses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Nothing listens on the IPv6 loopback, so the preferred family fails and IPv4 is used
+        let addrs = [
+            IpAddr::from([0u16, 0, 0, 0, 0, 0, 0, 1]),
+            IpAddr::from([127, 0, 0, 1]),
+        ];
+
+        let stream = connect_any(&addrs, port, IpPreference::PreferV6)
+            .await
+            .unwrap();
+        assert!(stream.peer_addr().unwrap().is_ipv4());
+    }
+
+    #[tokio::test]
+    async fn http_connect_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await;
+        });
+
+        let outcome = Proxy::http(address).tunnel("example.com", 443).await;
+        assert!(outcome.unwrap_err().to_string().contains("407"));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::bundle::Bundle;
+use solana_transaction::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Suppresses re-submission of identical bundles within a time window, so accidental double-sends don't burn rate limit.
+///
+/// Bundles are identical if their serialized transactions are byte for byte equal. Re-sending a bundle that was accepted within the window returns the earlier bundle's uuid
+/// without sending it again, while re-sending one that is still in flight fails with `DuplicateBundle`. Failed sends are forgotten, so they can be retried,
+/// but a send that is dropped before it completes counts as in flight until the window passes.
+///
+/// Share one dedup between clients with an `Arc` to deduplicate across them.
+#[derive(Debug)]
+pub struct BundleDedup {
+    window: Duration,
+    seen: Mutex<HashMap<u64, Submission>>,
+}
+
+#[derive(Debug)]
+struct Submission {
+    at: Instant,
+    // None while the send is in flight
+    uuid: Option<String>,
+}
+
+// Whether a bundle should be sent, or was already sent
+pub(crate) enum Claim {
+    New(u64),
+    Sent(String),
+}
+
+impl BundleDedup {
+    /// Creates a dedup that suppresses identical bundles sent within `window` of each other.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Marks the bundle as in flight unless it was seen within the window. Expired entries are pruned on the way
+    pub(crate) fn claim(&self, bundle: &Bundle) -> JitoClientResult<Claim> {
+        let key = Self::key(bundle);
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        seen.retain(|_, submission| now.duration_since(submission.at) < self.window);
+        match seen.get(&key) {
+            Some(Submission {
+                uuid: Some(uuid), ..
+            }) => Ok(Claim::Sent(uuid.clone())),
+            Some(Submission { uuid: None, .. }) => Err(JitoClientError::DuplicateBundle),
+            None => {
+                seen.insert(
+                    key,
+                    Submission {
+                        at: now,
+                        uuid: None,
+                    },
+                );
+                Ok(Claim::New(key))
+            }
+        }
+    }
+
+    // Remembers the uuid of a successful send, or forgets a failed one so it can be sent again
+    pub(crate) fn complete(&self, key: u64, result: &JitoClientResult<String>) {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(uuid) => {
+                if let Some(submission) = seen.get_mut(&key) {
+                    submission.uuid = Some(uuid.clone());
+                }
+            }
+            Err(_) => {
+                seen.remove(&key);
+            }
+        }
+    }
+
+    fn key(bundle: &Bundle) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for packet in &bundle.packets {
+            packet.data.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Rejects bundles containing a transaction that was submitted within a time window, so two tasks can't submit the same opportunity in different bundles.
+///
+/// Transactions are identified by their first signature. A bundle reusing the signature of a transaction in flight or sent within the TTL fails with `DuplicateInFlight`,
+/// without being sent. Signatures of failed sends are released, so the transactions can be sent again.
+///
+/// Share one guard between clients with an `Arc` to guard across them.
+#[derive(Debug)]
+pub struct SignatureGuard {
+    ttl: Duration,
+    submitted: Mutex<HashMap<Signature, Instant>>,
+}
+
+impl SignatureGuard {
+    /// Creates a guard that remembers submitted signatures for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            submitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Claims the signature of every transaction, unless one was submitted within the TTL. Expired signatures are pruned on the way
+    pub(crate) fn claim(
+        &self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<Vec<Signature>> {
+        let signatures: Vec<Signature> = transactions
+            .iter()
+            .filter_map(|txn| txn.signatures.first().copied())
+            .collect();
+        let mut submitted = self.submitted.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        submitted.retain(|_, at| now.duration_since(*at) < self.ttl);
+        if let Some(duplicate) = signatures.iter().find(|s| submitted.contains_key(s)) {
+            return Err(JitoClientError::DuplicateInFlight(duplicate.to_string()));
+        }
+        for signature in &signatures {
+            submitted.insert(*signature, now);
+        }
+        Ok(signatures)
+    }
+
+    // Keeps the signatures of a successful send until the TTL passes, or releases those of a failed one
+    pub(crate) fn complete(&self, signatures: &[Signature], sent: bool) {
+        if sent {
+            return;
+        }
+        let mut submitted = self.submitted.lock().unwrap_or_else(|e| e.into_inner());
+        for signature in signatures {
+            submitted.remove(signature);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::packet::Packet;
+    use bytes::Bytes;
+
+    fn bundle(data: &[u8]) -> Bundle {
+        Bundle {
+            header: None,
+            packets: vec![Packet {
+                data: Bytes::copy_from_slice(data),
+                meta: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn identical_bundles_are_suppressed() {
+        let dedup = BundleDedup::new(Duration::from_secs(60));
+
+        let Claim::New(key) = dedup.claim(&bundle(b"a")).unwrap() else {
+            panic!("first send must be new");
+        };
+        assert!(matches!(
+            dedup.claim(&bundle(b"a")),
+            Err(JitoClientError::DuplicateBundle)
+        ));
+        assert!(matches!(dedup.claim(&bundle(b"b")), Ok(Claim::New(_))));
+
+        dedup.complete(key, &Ok("uuid".to_string()));
+        assert!(matches!(dedup.claim(&bundle(b"a")), Ok(Claim::Sent(uuid)) if uuid == "uuid"));
+    }
+
+    #[test]
+    fn reused_signatures_are_rejected() {
+        let transaction = |signature: Signature| VersionedTransaction {
+            signatures: vec![signature],
+            ..VersionedTransaction::default()
+        };
+        let (first, second) = (Signature::from([1; 64]), Signature::from([2; 64]));
+        let guard = SignatureGuard::new(Duration::from_secs(60));
+
+        let claimed = guard.claim(&[transaction(first)]).unwrap();
+        assert!(matches!(
+            guard.claim(&[transaction(second), transaction(first)]),
+            Err(JitoClientError::DuplicateInFlight(signature)) if signature == first.to_string()
+        ));
+        // The rejected bundle's other transaction was not claimed
+        let other = guard.claim(&[transaction(second)]).unwrap();
+
+        guard.complete(&claimed, false);
+        assert!(guard.claim(&[transaction(first)]).is_ok());
+        guard.complete(&other, true);
+        assert!(guard.claim(&[transaction(second)]).is_err());
+    }
+
+    #[test]
+    fn failed_and_expired_bundles_can_be_resent() {
+        let dedup = BundleDedup::new(Duration::ZERO);
+        assert!(matches!(dedup.claim(&bundle(b"a")), Ok(Claim::New(_))));
+        assert!(matches!(dedup.claim(&bundle(b"a")), Ok(Claim::New(_))));
+
+        let dedup = BundleDedup::new(Duration::from_secs(60));
+        let Ok(Claim::New(key)) = dedup.claim(&bundle(b"a")) else {
+            panic!("first send must be new");
+        };
+        dedup.complete(key, &Err(JitoClientError::MaxRetriesError));
+        assert!(matches!(dedup.claim(&bundle(b"a")), Ok(Claim::New(_))));
+    }
+}
@@ -1,29 +1,433 @@
+use crate::bundle::BundleSizeError;
+use crate::region::{Network, NodeRegion};
+use std::fmt;
 use thiserror::Error;
 
 pub type JitoClientResult<T> = std::result::Result<T, JitoClientError>;
 
+/// Errors returned by the client.
+///
+/// The send methods attach the details of the send to their errors, wrapping them in `Context`. Match on `inner()` rather than on the error
+/// itself to tell the variants apart, and use `kind()`, `status_code()` and the `is_*` methods, which look through the context.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::{JitoClientError, JitoClientResult}};
+/// # async fn example(client: &mut JitoClient) -> JitoClientResult<()> {
+/// match client.send(&[]).await {
+///     Ok(uuid) => println!("Bundle sent: {uuid}"),
+///     Err(e) => match e.inner() {
+///         JitoClientError::DuplicateBundle => println!("Already sent"),
+///         _ => return Err(e),
+///     },
+/// }
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum JitoClientError {
     #[error("Latency measure error")]
     MeasureLatencyError,
-    #[error("Missing latency for all regions")]
-    AllRegionLatencyMissing,
-    #[error("DNS resolution failed: {0}")]
-    DNSResolution(std::io::Error),
-    #[error("Empty DNS resolution result")]
-    DNSEmpty,
-    #[error("TCP connection failed: {0}")]
-    TCPConnect(std::io::Error),
+    #[error("Missing latency for all regions: {}", region_failures(.0))]
+    AllRegionLatencyMissing(Vec<(NodeRegion, JitoClientError)>),
+    #[error("DNS resolution of {host}{} failed: {source}", in_region(.region))]
+    DNSResolution {
+        region: Option<NodeRegion>,
+        host: String,
+        source: std::io::Error,
+    },
+    #[error("Empty DNS resolution result for {host}{}", in_region(.region))]
+    DNSEmpty {
+        region: Option<NodeRegion>,
+        host: String,
+    },
+    #[error("TCP connection to {host}{} failed: {source}", in_region(.region))]
+    TCPConnect {
+        region: Option<NodeRegion>,
+        host: String,
+        source: std::io::Error,
+    },
     #[error("Bundle transaction size reached")]
     TooManyTxns,
+    #[error("Bundle too large: {0}")]
+    BundleTooLarge(Box<BundleSizeError>),
     #[error("Retry wait parameters invalid")]
     WaitParameterError,
     #[error("Max retries reached")]
     MaxRetriesError,
+    #[error("Send cancelled")]
+    Cancelled,
+    #[error("Bundle expired before it was accepted")]
+    BundleExpired,
+    #[error("Auth error: {0}")]
+    AuthError(&'static str),
+    #[error("Invalid pubkey: {0}")]
+    InvalidPubkey(String),
+    #[error("Malformed response: {0}")]
+    MalformedResponse(&'static str),
+    #[error("Timed out waiting for bundle result")]
+    ResultTimeout,
+    #[error("Bundle result stream closed")]
+    TrackerClosed,
+    #[error("Tracker follows results from {tracker}, but the client sends to {client}")]
+    TrackerMismatch {
+        tracker: &'static str,
+        client: &'static str,
+    },
+    #[error("Journal error: {0}")]
+    JournalError(#[source] std::io::Error),
+    #[error("Token store error: {0}")]
+    TokenStoreError(#[source] std::io::Error),
+    #[error("Latency store error: {0}")]
+    LatencyStoreError(#[source] std::io::Error),
+    #[error("Tip stream error: {0}")]
+    TipStreamError(#[source] std::io::Error),
+    #[error("No reachable warm channel to another region")]
+    NoWarmRegion,
+    #[error("No {1} block engine in region {0}")]
+    NoBlockEngine(NodeRegion, Network),
+    #[error("Invalid endpoint {endpoint:?}: {reason}")]
+    InvalidEndpoint {
+        endpoint: String,
+        reason: &'static str,
+    },
+    #[error("Compiled Jito protos ({revision}) don't match the client: {mismatches}")]
+    ProtoMismatch {
+        revision: &'static str,
+        mismatches: String,
+    },
+    #[error("Unknown region: {0}")]
+    UnknownRegion(String),
+    #[error("Config error: {0}")]
+    ConfigError(String),
+    #[error("Runtime error: {0}")]
+    RuntimeError(#[source] std::io::Error),
+    #[error("FFI error: {0}")]
+    FfiError(&'static str),
+    #[error("Identical bundle already in flight")]
+    DuplicateBundle,
+    #[error("Transaction {0} already in flight")]
+    DuplicateInFlight(String),
+    #[error("Bundle discarded for a fresher one before it was sent")]
+    Stale,
+    #[error("Send queue full")]
+    SendQueueFull,
+    #[error("Background sender stopped")]
+    SenderClosed,
+    #[error("Tip strategy requires {0}")]
+    MissingTipInput(&'static str),
+    #[error("Transaction {0} sets no compute unit price")]
+    MissingComputeBudget(usize),
+    #[error("Invalid request metadata: {0}")]
+    InvalidMetadata(String),
+    #[error("Block engine returned no tip accounts")]
+    NoTipAccounts,
+    #[error("Block engine reported no server time")]
+    NoServerTime,
+    #[error("No Jito leader within the send window, the next is {0} slots away")]
+    NoUpcomingLeader(u64),
+    #[error("Slot {current} is past the bundle's last target slot {last}")]
+    SlotDeadlinePassed { current: u64, last: u64 },
     #[error("Bincode serialize error: {0}")]
     SerializeError(#[from] bincode::Error),
+    #[error("Signing error: {0}")]
+    SignError(#[from] solana_transaction::SignerError),
+    #[cfg(feature = "native")]
     #[error("GRPC connect error: {0}")]
     GRPCError(#[from] tonic::transport::Error),
     #[error("Send Error: {0}")]
     SendError(#[from] tonic::Status),
+    #[cfg(feature = "rpc")]
+    #[error("RPC error: {0}")]
+    RpcError(#[source] Box<solana_rpc_client_api::client_error::Error>),
+    /// An error with the details of the send it occurred in. Its message includes the wrapped error's, so the wrapped error isn't its `source`,
+    /// and the rest of the chain starts at `inner()`
+    #[error("{error} ({context})")]
+    Context {
+        context: ErrorContext,
+        error: Box<JitoClientError>,
+    },
+}
+
+/// Details of the send an error occurred in, attached to errors returned by the send methods.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Endpoint the bundle was sent to
+    pub endpoint: Option<&'static str>,
+    /// Number of transactions in the bundle
+    pub bundle_size: Option<usize>,
+    /// Attempt the error occurred on, starting at 1
+    pub attempt: Option<u8>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(endpoint) = self.endpoint {
+            fields.push(format!("endpoint {endpoint}"));
+        }
+        if let Some(size) = self.bundle_size {
+            fields.push(format!("{size} transactions"));
+        }
+        if let Some(attempt) = self.attempt {
+            fields.push(format!("attempt {attempt}"));
+        }
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl From<solana_rpc_client_api::client_error::Error> for JitoClientError {
+    fn from(e: solana_rpc_client_api::client_error::Error) -> Self {
+        Self::RpcError(Box::new(e))
+    }
+}
+
+/// Broad class of a `JitoClientError`, to branch on without matching every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// DNS resolution, TCP connection or gRPC transport failure
+    Network,
+    /// A deadline passed, such as waiting for a bundle result
+    Timeout,
+    /// The block engine rate limited the request
+    RateLimited,
+    /// Authentication failed or was refused
+    Auth,
+    /// The block engine rejected the request for another reason
+    Rejected,
+    /// The input was invalid, such as too many transactions or a bad configuration
+    InvalidInput,
+    /// Retries were exhausted, or the send was cancelled or expired
+    Aborted,
+    /// A local failure, such as a journal write or a closed result stream
+    Internal,
+}
+
+// Describes the region of a host in error messages, if known
+fn in_region(region: &Option<NodeRegion>) -> String {
+    match region {
+        Some(region) => format!(" (region {region})"),
+        None => String::new(),
+    }
+}
+
+// Lists each region with the error that prevented measuring it
+fn region_failures(failures: &[(NodeRegion, JitoClientError)]) -> String {
+    failures
+        .iter()
+        .map(|(region, e)| format!("{region}: {e}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl JitoClientError {
+    // Attaches send details to the error, keeping details already attached
+    #[cfg(any(feature = "native", test))]
+    pub(crate) fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Self::Context {
+                context: existing,
+                error,
+            } => Self::Context {
+                context: ErrorContext {
+                    endpoint: existing.endpoint.or(context.endpoint),
+                    bundle_size: existing.bundle_size.or(context.bundle_size),
+                    attempt: existing.attempt.or(context.attempt),
+                },
+                error,
+            },
+            e => Self::Context {
+                context,
+                error: Box::new(e),
+            },
+        }
+    }
+
+    /// Returns the send details attached to the error, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its attached send details, to match on its variant.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Context { error, .. } => error,
+            e => e,
+        }
+    }
+
+    // Attributes a DNS or TCP error to the region whose host failed
+    #[cfg(feature = "native")]
+    pub(crate) fn in_region(mut self, node: NodeRegion) -> Self {
+        if let Self::DNSResolution { region, .. }
+        | Self::DNSEmpty { region, .. }
+        | Self::TCPConnect { region, .. } = &mut self
+        {
+            *region = Some(node);
+        }
+        self
+    }
+
+    /// Returns the broad class of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::MeasureLatencyError
+            | Self::AllRegionLatencyMissing(_)
+            | Self::DNSResolution { .. }
+            | Self::DNSEmpty { .. }
+            | Self::TCPConnect { .. }
+            | Self::NoWarmRegion
+            | Self::TipStreamError(_) => ErrorKind::Network,
+            #[cfg(feature = "native")]
+            Self::GRPCError(_) => ErrorKind::Network,
+            Self::ResultTimeout => ErrorKind::Timeout,
+            Self::AuthError(_) => ErrorKind::Auth,
+            Self::TooManyTxns
+            | Self::WaitParameterError
+            | Self::InvalidPubkey(_)
+            | Self::MalformedResponse(_)
+            | Self::UnknownRegion(_)
+            | Self::InvalidEndpoint { .. }
+            | Self::TrackerMismatch { .. }
+            | Self::NoBlockEngine(..)
+            | Self::ConfigError(_)
+            | Self::InvalidMetadata(_)
+            | Self::BundleTooLarge(_)
+            | Self::MissingComputeBudget(_)
+            | Self::MissingTipInput(_)
+            | Self::SignError(_)
+            | Self::SerializeError(_) => ErrorKind::InvalidInput,
+            Self::MaxRetriesError
+            | Self::Cancelled
+            | Self::BundleExpired
+            | Self::DuplicateBundle
+            | Self::DuplicateInFlight(_)
+            | Self::Stale
+            | Self::SendQueueFull
+            | Self::NoUpcomingLeader(_)
+            | Self::SlotDeadlinePassed { .. } => ErrorKind::Aborted,
+            Self::TrackerClosed
+            | Self::SenderClosed
+            | Self::NoTipAccounts
+            | Self::NoServerTime
+            | Self::JournalError(_)
+            | Self::TokenStoreError(_)
+            | Self::LatencyStoreError(_)
+            | Self::RuntimeError(_)
+            | Self::ProtoMismatch { .. }
+            | Self::FfiError(_) => ErrorKind::Internal,
+            Self::SendError(status) => match status.code() {
+                tonic::Code::ResourceExhausted => ErrorKind::RateLimited,
+                tonic::Code::Unauthenticated | tonic::Code::PermissionDenied => ErrorKind::Auth,
+                tonic::Code::DeadlineExceeded => ErrorKind::Timeout,
+                tonic::Code::Unavailable | tonic::Code::Cancelled => ErrorKind::Network,
+                // A connection dropped mid-request surfaces as an unknown status caused by a transport error
+                #[cfg(feature = "native")]
+                tonic::Code::Unknown
+                    if std::error::Error::source(status)
+                        .is_some_and(|source| source.is::<tonic::transport::Error>()) =>
+                {
+                    ErrorKind::Network
+                }
+                tonic::Code::InvalidArgument
+                | tonic::Code::OutOfRange
+                | tonic::Code::FailedPrecondition => ErrorKind::InvalidInput,
+                _ => ErrorKind::Rejected,
+            },
+            #[cfg(feature = "rpc")]
+            Self::RpcError(_) => ErrorKind::Network,
+            Self::Context { error, .. } => error.kind(),
+        }
+    }
+
+    /// Returns whether the same request may succeed if retried, such as after a network failure, a timeout or rate limiting.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind(),
+            ErrorKind::Network | ErrorKind::Timeout | ErrorKind::RateLimited
+        ) || self.status_code() == Some(tonic::Code::Aborted)
+    }
+
+    /// Returns whether the block engine rate limited the request.
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind() == ErrorKind::RateLimited
+    }
+
+    /// Returns whether authentication failed or the block engine refused the credentials.
+    pub fn is_auth_error(&self) -> bool {
+        self.kind() == ErrorKind::Auth
+    }
+
+    /// Returns whether a `BackgroundSender` discarded the bundle for a fresher one.
+    pub fn is_stale(&self) -> bool {
+        matches!(self.inner(), Self::Stale)
+    }
+
+    /// Returns the gRPC status code of an error returned by the block engine.
+    pub fn status_code(&self) -> Option<tonic::Code> {
+        match self.inner() {
+            Self::SendError(status) => Some(status.code()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_codes_are_classified() {
+        let rate_limited = JitoClientError::SendError(tonic::Status::resource_exhausted(
+            "Network congested. Endpoint is globally rate limited.",
+        ));
+        assert!(rate_limited.is_rate_limited());
+        assert!(rate_limited.is_retryable());
+        assert_eq!(
+            rate_limited.status_code(),
+            Some(tonic::Code::ResourceExhausted)
+        );
+
+        let rejected = JitoClientError::SendError(tonic::Status::invalid_argument("bad bundle"));
+        assert_eq!(rejected.kind(), ErrorKind::InvalidInput);
+        assert!(!rejected.is_retryable());
+
+        assert!(JitoClientError::AuthError("expired").is_auth_error());
+        assert!(!JitoClientError::TooManyTxns.is_retryable());
+        assert_eq!(JitoClientError::ResultTimeout.status_code(), None);
+    }
+
+    #[test]
+    fn context_is_merged_and_preserves_inner_error() {
+        use std::error::Error;
+
+        let e = JitoClientError::SendError(tonic::Status::unavailable("down"))
+            .with_context(ErrorContext {
+                attempt: Some(3),
+                ..Default::default()
+            })
+            .with_context(ErrorContext {
+                endpoint: Some("https://ny.mainnet.block-engine.jito.wtf"),
+                bundle_size: Some(2),
+                attempt: Some(1),
+            });
+        let context = e.context().unwrap();
+        assert_eq!(context.attempt, Some(3));
+        assert_eq!(context.bundle_size, Some(2));
+        assert_eq!(e.status_code(), Some(tonic::Code::Unavailable));
+        assert!(e.is_retryable());
+        assert!(matches!(e.inner(), JitoClientError::SendError(_)));
+        // The message is printed once along the chain
+        assert!(e.source().is_none());
+        assert!(e.inner().source().is_some());
+        assert!(e.to_string().ends_with(
+            "(endpoint https://ny.mainnet.block-engine.jito.wtf, 2 transactions, attempt 3)"
+        ));
+        assert_eq!(e.to_string().matches("down").count(), 1);
+    }
 }
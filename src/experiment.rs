@@ -0,0 +1,208 @@
+use crate::client::JitoClient;
+use crate::errors::JitoClientResult;
+use crate::stats::{BundleStats, DEFAULT_STATS_WINDOW, OutcomeCounts};
+use crate::tracker::TrackedBundle;
+use solana_transaction::versioned::VersionedTransaction;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// One of the two sides of a `RegionExperiment`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arm {
+    A,
+    B,
+}
+
+impl Display for Arm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::A => write!(f, "A"),
+            Self::B => write!(f, "B"),
+        }
+    }
+}
+
+/// Landing outcomes of the bundles sent by one arm of a `RegionExperiment`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArmStats {
+    /// Endpoint of the arm's client
+    pub endpoint: &'static str,
+    /// Outcomes of the bundles the arm's block engine accepted within the experiment's window
+    pub outcomes: OutcomeCounts,
+    /// Sends that failed
+    pub send_failures: u64,
+}
+
+impl ArmStats {
+    /// Returns the share of the bundles with a final outcome that landed, or `None` if there are none yet.
+    pub fn land_rate(&self) -> Option<f64> {
+        self.outcomes.land_rate()
+    }
+
+    /// Returns the bundles sent without a final outcome yet, either still in flight or whose results were missed.
+    pub fn unresolved(&self) -> u64 {
+        let resolved = self.outcomes.landed + self.outcomes.rejected + self.outcomes.dropped;
+        self.outcomes.sent.saturating_sub(resolved)
+    }
+}
+
+/// Alternates sends between two clients, such as connected to two regions or sending over two transports, to find which one lands more bundles.
+///
+/// Time is cut into slices of equal length, and every send in a slice goes to the slice's arm, starting with arm A. Alternating over time
+/// rather than per send exposes both arms to the same market conditions, without sending the same opportunity twice. The outcome of every
+/// bundle is recorded in its arm's `BundleStats` as results stream to the sending client's tracker, leaving the results to the caller.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, experiment::{Arm, RegionExperiment}, region::NodeRegion};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let ny = JitoClient::new(NodeRegion::NY.endpoint(), None).await?;
+/// let slc = JitoClient::new(NodeRegion::SLC.endpoint(), None).await?;
+/// let mut experiment = RegionExperiment::new(ny, slc, Duration::from_secs(60));
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// let (arm, bundle) = experiment.send(&transactions).await?;
+/// println!("Sent {} through arm {arm}", bundle.uuid());
+///
+/// // ... after a few hours of sends ...
+/// for arm in [Arm::A, Arm::B] {
+///     let stats = experiment.stats(arm);
+///     println!("{}: {:?} of {} bundles landed", stats.endpoint, stats.land_rate(), stats.outcomes.sent);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct RegionExperiment {
+    arms: [JitoClient; 2],
+    slice: Duration,
+    started: Instant,
+    send_failures: [u64; 2],
+}
+
+impl RegionExperiment {
+    /// Alternates between `a` and `b` every `slice`, starting now with `a`.
+    ///
+    /// Each client records its outcomes in new `BundleStats`, replacing its `outcome_stats`. A client whose tracker is already subscribed
+    /// keeps the statistics its tracker records results in, so its bundles sent outside the experiment count too.
+    pub fn new(a: JitoClient, b: JitoClient, slice: Duration) -> Self {
+        let mut experiment = Self {
+            arms: [a, b],
+            slice,
+            started: Instant::now(),
+            send_failures: [0; 2],
+        };
+        experiment.record_outcomes(DEFAULT_STATS_WINDOW);
+        experiment
+    }
+
+    /// Counts the outcomes of the bundles sent within the last `window`. Defaults to `DEFAULT_STATS_WINDOW`. Set it before the first send,
+    /// as the outcomes recorded so far are discarded.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.record_outcomes(window);
+        self
+    }
+
+    // Gives each arm statistics of its own over `window`, unless its tracker already records results in the client's
+    fn record_outcomes(&mut self, window: Duration) {
+        for client in &mut self.arms {
+            if client.tracker.is_none() {
+                client.hooks.outcomes = BundleStats::new(window);
+            }
+        }
+    }
+
+    /// Returns the arm sends currently go to.
+    pub fn active_arm(&self) -> Arm {
+        arm_at(self.started.elapsed(), self.slice)
+    }
+
+    /// Sends the bundle through the active arm, following its results with the arm client's tracker.
+    ///
+    /// # Returns
+    /// Returns the arm the bundle was sent through, and the bundle, whose results are the caller's to await.
+    ///
+    /// # Errors
+    /// This function will return the send error of the active arm's client, counted in its `send_failures`.
+    pub async fn send(
+        &mut self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<(Arm, TrackedBundle)> {
+        let arm = self.active_arm();
+        match self.arms[arm as usize].send_and_track(transactions).await {
+            Ok(bundle) => Ok((arm, bundle)),
+            Err(e) => {
+                self.send_failures[arm as usize] += 1;
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the outcomes recorded for `arm` so far.
+    pub fn stats(&self, arm: Arm) -> ArmStats {
+        let client = &self.arms[arm as usize];
+        let endpoint = client.get_endpoint();
+        ArmStats {
+            endpoint,
+            outcomes: client
+                .outcome_stats()
+                .summary()
+                .endpoints
+                .get(endpoint)
+                .copied()
+                .unwrap_or_default(),
+            send_failures: self.send_failures[arm as usize],
+        }
+    }
+
+    /// Returns the client of `arm`.
+    pub fn client(&mut self, arm: Arm) -> &mut JitoClient {
+        &mut self.arms[arm as usize]
+    }
+
+    /// Ends the experiment, returning the clients of arms A and B, which keep recording their outcomes in their `outcome_stats`.
+    pub fn into_clients(self) -> (JitoClient, JitoClient) {
+        let [a, b] = self.arms;
+        (a, b)
+    }
+}
+
+// Arm of the slice `elapsed` falls in, alternating from A
+fn arm_at(elapsed: Duration, slice: Duration) -> Arm {
+    if slice.is_zero() {
+        return Arm::A;
+    }
+    match (elapsed.as_nanos() / slice.as_nanos()) % 2 {
+        0 => Arm::A,
+        _ => Arm::B,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arms_alternate_every_slice() {
+        let slice = Duration::from_secs(60);
+        assert_eq!(arm_at(Duration::ZERO, slice), Arm::A);
+        assert_eq!(arm_at(Duration::from_secs(59), slice), Arm::A);
+        assert_eq!(arm_at(Duration::from_secs(60), slice), Arm::B);
+        assert_eq!(arm_at(Duration::from_secs(150), slice), Arm::A);
+        assert_eq!(arm_at(Duration::from_secs(5), Duration::ZERO), Arm::A);
+
+        let stats = ArmStats {
+            outcomes: OutcomeCounts {
+                sent: 4,
+                landed: 1,
+                dropped: 2,
+                ..OutcomeCounts::default()
+            },
+            ..ArmStats::default()
+        };
+        assert_eq!(stats.land_rate(), Some(1.0 / 3.0));
+        assert_eq!(stats.unresolved(), 1);
+        assert_eq!(ArmStats::default().land_rate(), None);
+    }
+}
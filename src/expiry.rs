@@ -0,0 +1,147 @@
+#[cfg(any(feature = "native", test))]
+use solana_transaction::{Hash, versioned::VersionedTransaction};
+#[cfg(any(feature = "native", test))]
+use std::collections::HashMap;
+use std::time::Duration;
+#[cfg(any(feature = "native", test))]
+use std::time::Instant;
+
+/// Number of slots a blockhash remains valid for after it is produced.
+pub const MAX_BLOCKHASH_AGE_SLOTS: u32 = 150;
+/// Average slot time, used to convert slot counts into wall-clock time.
+pub const DEFAULT_SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// Determines when a bundle can no longer land, so retries stop instead of resending a dead bundle.
+#[derive(Debug, Clone, Copy)]
+pub enum BundleExpiry {
+    /// Expires once the oldest blockhash referenced by the bundle is too old to be valid, counted from when the client first saw that blockhash.
+    Blockhash,
+    /// Expires once `expiry_slot` is reached, estimated from `current_slot` and the average slot time.
+    Slot { current_slot: u64, expiry_slot: u64 },
+}
+
+impl BundleExpiry {
+    // Returns the instant the bundle expires, or None if it has no blockhash to track
+    #[cfg(any(feature = "native", test))]
+    pub(crate) fn deadline(
+        &self,
+        tracker: &mut BlockhashTracker,
+        txns: &[VersionedTransaction],
+    ) -> Option<Instant> {
+        match self {
+            BundleExpiry::Blockhash => tracker.expires_at(txns),
+            BundleExpiry::Slot {
+                current_slot,
+                expiry_slot,
+            } => {
+                let slots_left = expiry_slot.saturating_sub(*current_slot);
+                let slots_left = u32::try_from(slots_left).unwrap_or(u32::MAX);
+                Some(Instant::now() + DEFAULT_SLOT_DURATION.saturating_mul(slots_left))
+            }
+        }
+    }
+}
+
+/// Slots a bundle is meant to land in. Sends targeting a deadline are rejected locally once the current slot is past the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotDeadline {
+    /// First slot the bundle may be sent for
+    pub first: u64,
+    /// Last slot the bundle may land in
+    pub last: u64,
+}
+
+impl SlotDeadline {
+    /// Targets a single slot.
+    pub fn at(slot: u64) -> Self {
+        Self {
+            first: slot,
+            last: slot,
+        }
+    }
+
+    /// Targets the slots from `first` to `last`, inclusive.
+    pub fn range(first: u64, last: u64) -> Self {
+        Self { first, last }
+    }
+
+    /// Targets any slot up to `last`, inclusive.
+    pub fn until(last: u64) -> Self {
+        Self { first: 0, last }
+    }
+
+    /// Returns whether `slot` is past the last target slot.
+    pub fn is_past(&self, slot: u64) -> bool {
+        slot > self.last
+    }
+
+    /// Returns whether `slot` is one of the target slots.
+    pub fn contains(&self, slot: u64) -> bool {
+        (self.first..=self.last).contains(&slot)
+    }
+}
+
+/// Remembers when each blockhash was first seen, so bundles reusing it share the same expiry.
+#[cfg(any(feature = "native", test))]
+#[derive(Debug, Default)]
+pub(crate) struct BlockhashTracker {
+    first_seen: HashMap<Hash, Instant>,
+}
+
+#[cfg(any(feature = "native", test))]
+impl BlockhashTracker {
+    // Records the bundle's blockhashes and returns the instant the oldest of them expires. Blockhashes that already expired are forgotten
+    pub(crate) fn expires_at(&mut self, txns: &[VersionedTransaction]) -> Option<Instant> {
+        let now = Instant::now();
+        let lifetime = DEFAULT_SLOT_DURATION * MAX_BLOCKHASH_AGE_SLOTS;
+        self.first_seen
+            .retain(|_, seen| now.duration_since(*seen) < lifetime);
+
+        txns.iter()
+            .map(|txn| {
+                let seen = self
+                    .first_seen
+                    .entry(*txn.message.recent_blockhash())
+                    .or_insert(now);
+                *seen + lifetime
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blockhash_expiry_is_stable() {
+        let mut tracker = BlockhashTracker::default();
+        let txns = vec![VersionedTransaction::default()];
+
+        let first = tracker.expires_at(&txns).unwrap();
+        let second = tracker.expires_at(&txns).unwrap();
+        assert_eq!(first, second);
+        assert!(tracker.expires_at(&[]).is_none());
+    }
+
+    #[test]
+    fn past_slot_is_expired() {
+        let mut tracker = BlockhashTracker::default();
+        let expiry = BundleExpiry::Slot {
+            current_slot: 100,
+            expiry_slot: 90,
+        };
+        let deadline = expiry.deadline(&mut tracker, &[]).unwrap();
+        assert!(deadline <= Instant::now());
+    }
+
+    #[test]
+    fn slot_deadline_range() {
+        let deadline = SlotDeadline::range(10, 12);
+        assert!(!deadline.contains(9));
+        assert!(deadline.contains(12));
+        assert!(!deadline.is_past(12));
+        assert!(deadline.is_past(13));
+        assert!(SlotDeadline::until(5).contains(0));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::builder::JitoClientBuilder;
+use crate::client::JitoClient;
+use crate::errors::JitoClientResult;
+use crate::region::NodeRegion;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use solana_transaction::versioned::VersionedTransaction;
+use std::time::{Duration, Instant};
+
+/// Sends every bundle to several block engines at once, improving the odds that the leader's block engine sees it.
+///
+/// Every send is awaited, so the returned `FanoutReport` tells which block engines accepted the bundle, not just the first one.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{builder::JitoClientBuilder, errors::JitoClientResult, fanout::FanoutClient, region::NodeRegion};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut fanout = FanoutClient::connect(&[NodeRegion::NY, NodeRegion::FRA], JitoClientBuilder::new()).await?;
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// let report = fanout.send_all(&transactions).await;
+/// for outcome in &report.outcomes {
+///     match &outcome.result {
+///         Ok(uuid) => println!("{} accepted {uuid} in {:?}", outcome.endpoint, outcome.latency),
+///         Err(e) => println!("{} rejected the bundle: {e}", outcome.endpoint),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FanoutClient {
+    clients: Vec<JitoClient>,
+}
+
+/// Outcome of sending a bundle to one block engine.
+#[derive(Debug)]
+pub struct RegionOutcome {
+    /// Endpoint of the block engine
+    pub endpoint: &'static str,
+    /// Region of the block engine, if it is one of the known regions
+    pub region: Option<NodeRegion>,
+    /// The bundle's uuid, or why the block engine didn't accept it
+    pub result: JitoClientResult<String>,
+    /// Round trip of the send
+    pub latency: Duration,
+}
+
+/// Outcome of sending a bundle to every block engine of a `FanoutClient`, in the order the clients were given.
+#[derive(Debug)]
+pub struct FanoutReport {
+    /// One outcome per block engine
+    pub outcomes: Vec<RegionOutcome>,
+    /// Index into `outcomes` of the block engine that accepted the bundle first, if any did
+    pub first_success: Option<usize>,
+}
+
+impl FanoutReport {
+    /// Returns the outcome of the block engine that accepted the bundle first.
+    pub fn first(&self) -> Option<&RegionOutcome> {
+        self.first_success.map(|index| &self.outcomes[index])
+    }
+
+    /// Returns the uuid given by the block engine that accepted the bundle first.
+    pub fn uuid(&self) -> Option<&str> {
+        self.first()?.result.as_deref().ok()
+    }
+
+    /// Returns the outcomes of the block engines that accepted the bundle.
+    pub fn accepted(&self) -> impl Iterator<Item = &RegionOutcome> {
+        self.outcomes
+            .iter()
+            .filter(|outcome| outcome.result.is_ok())
+    }
+}
+
+impl FanoutClient {
+    /// Fans out over already connected clients.
+    pub fn new(clients: Vec<JitoClient>) -> Self {
+        Self { clients }
+    }
+
+    /// Connects to every region concurrently, with the connection settings, network and region overrides of `builder`.
+    ///
+    /// Regions that cannot be connected are left out, so one unreachable region doesn't prevent fanning out to the others.
+    ///
+    /// # Errors
+    /// This function will return the first region's connection error if no region can be connected.
+    pub async fn connect(
+        regions: &[NodeRegion],
+        builder: JitoClientBuilder,
+    ) -> JitoClientResult<Self> {
+        let connects = regions.iter().map(|region| {
+            let endpoint = builder.region_endpoint(*region);
+            let builder = builder.clone();
+            async move {
+                let client = match endpoint {
+                    Ok(endpoint) => builder.endpoint(endpoint).build().await,
+                    Err(e) => Err(e),
+                };
+                (*region, client)
+            }
+        });
+        let mut clients = Vec::new();
+        let mut first_error = None;
+        for (region, result) in futures::future::join_all(connects).await {
+            match result {
+                Ok(client) => clients.push(client),
+                Err(e) => {
+                    debug!("Fanout connection to {region} failed: {e}");
+                    first_error.get_or_insert(e.in_region(region));
+                }
+            }
+        }
+        match first_error {
+            Some(e) if clients.is_empty() => Err(e),
+            _ => Ok(Self::new(clients)),
+        }
+    }
+
+    /// Returns the connected clients, in fan-out order.
+    pub fn clients(&self) -> &[JitoClient] {
+        &self.clients
+    }
+
+    /// Sends the bundle to every block engine concurrently, and waits for every send to complete.
+    ///
+    /// # Returns
+    /// Returns a report with every block engine's uuid or error and send latency, and which one accepted the bundle first.
+    pub async fn send_all(&mut self, transactions: &[VersionedTransaction]) -> FanoutReport {
+        let mut sends: FuturesUnordered<_> = self
+            .clients
+            .iter_mut()
+            .enumerate()
+            .map(|(index, client)| async move {
+                let start = Instant::now();
+                let result = client.send(transactions).await;
+                (index, client.get_endpoint(), result, start.elapsed())
+            })
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(sends.len());
+        let mut first_success = None;
+        while let Some((index, endpoint, result, latency)) = sends.next().await {
+            if result.is_ok() && first_success.is_none() {
+                first_success = Some(index);
+            }
+            outcomes.push((
+                index,
+                RegionOutcome {
+                    endpoint,
+                    region: NodeRegion::all()
+                        .iter()
+                        .find(|region| region.endpoint() == endpoint)
+                        .copied(),
+                    result,
+                    latency,
+                },
+            ));
+        }
+        outcomes.sort_by_key(|(index, _)| *index);
+        FanoutReport {
+            outcomes: outcomes.into_iter().map(|(_, outcome)| outcome).collect(),
+            first_success,
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! C interface for embedding the client in other languages. The matching header is `include/jito_grpc_client.h`.
+//!
+//! Build a linkable library with `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`).
+//! Functions returning a status return 0 on success and -1 on failure, in which case `jito_last_error` describes the failure.
+
+use crate::api::BundleOutcome;
+use crate::client::JitoClient;
+use crate::errors::{JitoClientError, JitoClientResult};
+use solana_transaction::versioned::VersionedTransaction;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::ptr;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+/// Bundle outcomes returned by `jito_client_wait_result`.
+pub const JITO_OUTCOME_UNKNOWN: c_int = 0;
+pub const JITO_OUTCOME_ACCEPTED: c_int = 1;
+pub const JITO_OUTCOME_REJECTED: c_int = 2;
+pub const JITO_OUTCOME_FINALIZED: c_int = 3;
+pub const JITO_OUTCOME_PROCESSED: c_int = 4;
+pub const JITO_OUTCOME_DROPPED: c_int = 5;
+
+/// Smallest `uuid_cap` accepted by `jito_client_send`: a 36-character uuid and its NUL.
+pub const JITO_UUID_CAP: usize = 37;
+
+const ERROR: c_int = -1;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Opaque client handle owned by the caller.
+pub struct JitoFfiClient {
+    runtime: Runtime,
+    client: JitoClient,
+}
+
+fn set_last_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+// Converts a result to a status code, recording the error for `jito_last_error`
+fn status(result: JitoClientResult<()>) -> c_int {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            set_last_error(e);
+            ERROR
+        }
+    }
+}
+
+/// Returns the message of the last error on this thread, or null if there was none. Valid until the next failing call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn jito_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Connects a new client to `endpoint`, or to the fastest region if `endpoint` is null. A `timeout_ms` of 0 uses the default timeout.
+///
+/// Returns null on failure. The client must be freed with `jito_client_free`.
+///
+/// # Safety
+/// `endpoint` must be null or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jito_client_new(
+    endpoint: *const c_char,
+    timeout_ms: u64,
+) -> *mut JitoFfiClient {
+    let endpoint = if endpoint.is_null() {
+        None
+    } else {
+        // SAFETY: the caller guarantees a valid NUL-terminated string
+        match unsafe { CStr::from_ptr(endpoint) }.to_str() {
+            Ok(endpoint) => Some(endpoint.to_string()),
+            Err(e) => {
+                set_last_error(e);
+                return ptr::null_mut();
+            }
+        }
+    };
+
+    let connect = || -> JitoClientResult<JitoFfiClient> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(JitoClientError::RuntimeError)?;
+        let mut builder = JitoClient::builder();
+        if timeout_ms > 0 {
+            builder = builder.timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(endpoint) = endpoint {
+            builder = builder.endpoint_owned(endpoint);
+        }
+        let client = runtime.block_on(builder.build())?;
+        Ok(JitoFfiClient { runtime, client })
+    };
+    match connect() {
+        Ok(client) => Box::into_raw(Box::new(client)),
+        Err(e) => {
+            set_last_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a client created by `jito_client_new`, closing its connection. Null is ignored.
+///
+/// # Safety
+/// `client` must be null or a pointer returned by `jito_client_new` that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jito_client_free(client: *mut JitoFfiClient) {
+    if !client.is_null() {
+        // SAFETY: the caller guarantees the pointer came from `jito_client_new` and is freed once
+        drop(unsafe { Box::from_raw(client) });
+    }
+}
+
+/// Sends a bundle of `count` bincode-serialized versioned transactions, where transaction `i` is `lens[i]` bytes at `txns[i]`.
+///
+/// On success, the NUL-terminated bundle uuid is written to `uuid_out`, which holds `uuid_cap` bytes. A `uuid_cap` below `JITO_UUID_CAP` fails before anything is sent.
+///
+/// # Safety
+/// `client` must be a live client, `txns` and `lens` must point to `count` valid entries, and `uuid_out` must be writable for `uuid_cap` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jito_client_send(
+    client: *mut JitoFfiClient,
+    txns: *const *const u8,
+    lens: *const usize,
+    count: usize,
+    uuid_out: *mut c_char,
+    uuid_cap: usize,
+) -> c_int {
+    // SAFETY: the caller guarantees a live client
+    let Some(client) = (unsafe { client.as_mut() }) else {
+        set_last_error("Null client");
+        return ERROR;
+    };
+    if count > 0 && (txns.is_null() || lens.is_null()) || uuid_out.is_null() {
+        set_last_error("Null argument");
+        return ERROR;
+    }
+    if uuid_cap < JITO_UUID_CAP {
+        set_last_error(JitoClientError::FfiError("uuid buffer too small"));
+        return ERROR;
+    }
+
+    status((|| {
+        let mut transactions = Vec::with_capacity(count);
+        for i in 0..count {
+            // SAFETY: the caller guarantees `count` valid entries, each pointing to `lens[i]` bytes
+            let bytes = unsafe { std::slice::from_raw_parts(*txns.add(i), *lens.add(i)) };
+            transactions.push(bincode::deserialize::<VersionedTransaction>(bytes)?);
+        }
+        let uuid = client.runtime.block_on(client.client.send(&transactions))?;
+        if uuid.len() >= uuid_cap {
+            return Err(JitoClientError::FfiError("uuid buffer too small"));
+        }
+        // SAFETY: the caller guarantees `uuid_cap` writable bytes, and the uuid and its NUL fit
+        unsafe {
+            ptr::copy_nonoverlapping(uuid.as_ptr(), uuid_out.cast::<u8>(), uuid.len());
+            *uuid_out.add(uuid.len()) = 0;
+        }
+        Ok(())
+    })())
+}
+
+/// Waits up to `timeout_ms` for the result of the bundle `uuid`, returning one of the `JITO_OUTCOME_*` values, or -1 on failure or timeout.
+///
+/// The first call subscribes the client to bundle results; results that arrive before the wait starts are kept for a minute.
+///
+/// # Safety
+/// `client` must be a live client and `uuid` a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn jito_client_wait_result(
+    client: *mut JitoFfiClient,
+    uuid: *const c_char,
+    timeout_ms: u64,
+) -> c_int {
+    // SAFETY: the caller guarantees a live client
+    let Some(client) = (unsafe { client.as_mut() }) else {
+        set_last_error("Null client");
+        return ERROR;
+    };
+    if uuid.is_null() {
+        set_last_error("Null argument");
+        return ERROR;
+    }
+    // SAFETY: the caller guarantees a valid NUL-terminated string
+    let Ok(uuid) = unsafe { CStr::from_ptr(uuid) }.to_str() else {
+        set_last_error("uuid is not valid UTF-8");
+        return ERROR;
+    };
+
+    let outcome = client.runtime.block_on(async {
+        let tracker = client.client.tracker().await?;
+        tracker
+            .await_result(uuid, Duration::from_millis(timeout_ms))
+            .await
+    });
+    match outcome {
+        Ok(result) => match result
+            .outcome()
+            .as_ref()
+            .map_or("unknown", BundleOutcome::name)
+        {
+            "accepted" => JITO_OUTCOME_ACCEPTED,
+            "rejected" => JITO_OUTCOME_REJECTED,
+            "finalized" => JITO_OUTCOME_FINALIZED,
+            "processed" => JITO_OUTCOME_PROCESSED,
+            "dropped" => JITO_OUTCOME_DROPPED,
+            _ => JITO_OUTCOME_UNKNOWN,
+        },
+        Err(e) => {
+            set_last_error(e);
+            ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_set_last_error() {
+        let endpoint = CString::new("https://127.0.0.1:1").unwrap();
+        let client = unsafe { jito_client_new(endpoint.as_ptr(), 200) };
+        assert!(client.is_null());
+        assert!(!jito_last_error().is_null());
+
+        let mut uuid = [0 as c_char; 64];
+        let status = unsafe {
+            jito_client_send(
+                ptr::null_mut(),
+                ptr::null(),
+                ptr::null(),
+                0,
+                uuid.as_mut_ptr(),
+                64,
+            )
+        };
+        assert_eq!(status, ERROR);
+        let message = unsafe { CStr::from_ptr(jito_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "Null client");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn small_uuid_buffers_fail_before_sending() {
+        use crate::testing::MockBlockEngine;
+
+        let runtime = Runtime::new().unwrap();
+        let mock = runtime.block_on(MockBlockEngine::start()).unwrap();
+        let endpoint = CString::new(mock.endpoint()).unwrap();
+        let client = unsafe { jito_client_new(endpoint.as_ptr(), 0) };
+        assert!(!client.is_null());
+
+        let mut uuid = [0 as c_char; JITO_UUID_CAP - 1];
+        let status = unsafe {
+            jito_client_send(
+                client,
+                ptr::null(),
+                ptr::null(),
+                0,
+                uuid.as_mut_ptr(),
+                uuid.len(),
+            )
+        };
+        assert_eq!(status, ERROR);
+        let message = unsafe { CStr::from_ptr(jito_last_error()) };
+        assert_eq!(
+            message.to_str().unwrap(),
+            "FFI error: uuid buffer too small"
+        );
+        assert!(mock.bundles().is_empty());
+        unsafe { jito_client_free(client) };
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Replaces the file at `path` with `contents`, written beside it and renamed over it so a crash never leaves a partial file.
+// A `private` file is created readable by its owner only on unix
+pub(crate) fn atomic_write(path: &Path, contents: &str, private: bool) -> io::Result<()> {
+    let temp = temp_path(path)?;
+    // A leftover temp file, from a crash or created by someone else, keeps its permissions when opened, so it is replaced by a new one
+    match fs::remove_file(&temp) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    let mut file = options.open(&temp)?;
+    writeln!(file, "{contents}")?;
+    file.sync_data()?;
+    fs::rename(&temp, path)
+}
+
+// The file the contents are written to before the rename: the file name with `.tmp` appended, so files differing only by extension don't share it
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let mut name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_os_string();
+    name.push(".tmp");
+    Ok(path.with_file_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_files_keep_the_extension() {
+        assert_eq!(
+            temp_path(Path::new("/tmp/tokens.json")).unwrap(),
+            Path::new("/tmp/tokens.json.tmp")
+        );
+        assert_ne!(
+            temp_path(Path::new("tokens.json")).unwrap(),
+            temp_path(Path::new("tokens.toml")).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stale_temp_files_are_not_reused() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("jito-files-{}.json", std::process::id()));
+        let temp = temp_path(&path).unwrap();
+        fs::write(&temp, "stale").unwrap();
+        fs::set_permissions(&temp, fs::Permissions::from_mode(0o644)).unwrap();
+
+        atomic_write(&path, "secret", true).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let contents = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(contents, "secret\n");
+        assert!(!temp.exists());
+    }
+}
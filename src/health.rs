@@ -0,0 +1,175 @@
+use crate::auth::Authenticator;
+use crate::builder::JitoClientBuilder;
+use crate::errors::{ErrorKind, JitoClientError, JitoClientResult};
+use crate::grpc::searcher::GetTipAccountsRequest;
+use crate::signing::{SearcherClient, SignerInterceptor};
+use std::fmt::{self, Display};
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::time;
+
+/// How long `JitoClient::health` waits for the block engine to answer.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// State of the client's channel, as observed by a health check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelState {
+    /// The block engine answered the health check
+    Ready,
+    /// The block engine could not be reached, or didn't answer in time
+    Unreachable,
+    /// The block engine was reached but failed the request, such as rejecting the auth token or rate limiting
+    Degraded,
+}
+
+/// Health of a client's connection to its block engine, as returned by `JitoClient::health`.
+///
+/// Meant to be exposed through an application's health endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Health {
+    /// Endpoint of the block engine
+    pub endpoint: &'static str,
+    pub state: ChannelState,
+    /// Round trip of the health check, if it succeeded
+    pub rtt: Option<Duration>,
+    /// Why the health check failed, if it did
+    pub error: Option<String>,
+    /// When a bundle was last sent successfully, across every client sharing the latency statistics
+    pub last_success: Option<SystemTime>,
+    /// Expiry of the auth token on the local clock, if checked with `JitoClient::health_with_auth`
+    pub auth_expires_at: Option<SystemTime>,
+}
+
+impl Health {
+    /// Returns true if the block engine answered, and the auth token, if checked, is not expired.
+    pub fn is_healthy(&self) -> bool {
+        self.state == ChannelState::Ready && self.auth_token_valid() != Some(false)
+    }
+
+    /// Returns whether the auth token is valid, or `None` if it wasn't checked.
+    pub fn auth_token_valid(&self) -> Option<bool> {
+        self.auth_expires_at
+            .map(|expires_at| expires_at > SystemTime::now())
+    }
+
+    pub(crate) fn with_auth(mut self, authenticator: &Authenticator) -> Self {
+        let expires_at = authenticator.access_token().expires_at;
+        let skew = authenticator.clock_skew().estimate();
+        self.auth_expires_at = Some(skew.map_or(expires_at, |skew| skew.to_local(expires_at)));
+        self
+    }
+}
+
+/// Time spent in each phase of connecting to a block engine and sending it a first request, as returned by `diagnose`.
+///
+/// Tells apart a slow network, a slow TLS handshake and a slow server: DNS and TCP connect times reflect the network path,
+/// the TLS handshake adds the round trips and certificate checks of TLS, and the first request's server timing, if reported, its processing on the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// Endpoint of the block engine, normalized
+    pub endpoint: &'static str,
+    /// Addresses the host resolved to
+    pub addresses: Vec<IpAddr>,
+    /// Address the TCP connection was established with
+    pub connected_to: SocketAddr,
+    /// Time to resolve the host
+    pub dns: Duration,
+    /// Time to establish the TCP connection, racing the addresses
+    pub tcp_connect: Duration,
+    /// Time of the TLS handshake and HTTP/2 connection setup, `None` for a plaintext endpoint
+    pub tls_handshake: Option<Duration>,
+    /// Round trip of the first request over the new connection, a signed `GetTipAccounts`
+    pub first_rpc: Duration,
+    /// Server-side duration of the first request, if reported in a `server-timing` header
+    pub server_timing: Option<Duration>,
+}
+
+impl Diagnosis {
+    /// Returns the time from starting the lookup to receiving the first response.
+    pub fn total(&self) -> Duration {
+        self.dns + self.tcp_connect + self.tls_handshake.unwrap_or_default() + self.first_rpc
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): dns {:?}, tcp connect {:?}",
+            self.endpoint, self.connected_to, self.dns, self.tcp_connect
+        )?;
+        if let Some(tls_handshake) = self.tls_handshake {
+            write!(f, ", tls handshake {tls_handshake:?}")?;
+        }
+        write!(f, ", first rpc {:?}", self.first_rpc)?;
+        if let Some(server_timing) = self.server_timing {
+            write!(f, " (server {server_timing:?})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Connects to `endpoint` phase by phase with the default settings, timing the DNS lookup, TCP connect, TLS handshake and first request separately.
+///
+/// Far more actionable than a single ping when a region is slow. See `JitoClientBuilder::diagnose` to diagnose with custom TLS or DNS settings.
+///
+/// # Errors
+/// This function will return the error of the phase that failed, such as `DNSResolution`, `TCPConnect`, or a `GRPCConnectError` for a failed TLS handshake.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{errors::JitoClientResult, health::diagnose, region::NodeRegion};
+/// # async fn example() -> JitoClientResult<()> {
+/// let diagnosis = diagnose(NodeRegion::NY.endpoint()).await?;
+/// println!("{diagnosis}");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn diagnose(endpoint: &'static str) -> JitoClientResult<Diagnosis> {
+    JitoClientBuilder::new().endpoint(endpoint).diagnose().await
+}
+
+// Probes the channel with a cheap `GetTipAccounts` request, returning its state, the round trip if it succeeded, or why it failed
+pub(crate) async fn check(
+    client: &mut SearcherClient,
+    signer: &SignerInterceptor,
+) -> (ChannelState, Option<Duration>, Option<String>) {
+    let start = Instant::now();
+    let check = client.get_tip_accounts(signer.request(GetTipAccountsRequest {}));
+    let result = match time::timeout(HEALTH_CHECK_TIMEOUT, check).await {
+        Ok(result) => result.map_err(JitoClientError::from),
+        Err(_) => Err(JitoClientError::SendError(
+            tonic::Status::deadline_exceeded("health check timed out"),
+        )),
+    };
+    match result {
+        Ok(_) => (ChannelState::Ready, Some(start.elapsed()), None),
+        Err(e) => {
+            let state = match e.kind() {
+                ErrorKind::Network | ErrorKind::Timeout => ChannelState::Unreachable,
+                _ => ChannelState::Degraded,
+            };
+            (state, None, Some(e.to_string()))
+        }
+    }
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", self.endpoint, self.state)?;
+        if let Some(rtt) = self.rtt {
+            write!(f, " in {rtt:?}")?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, ": {error}")?;
+        }
+        if let Some(valid) = self.auth_token_valid() {
+            write!(
+                f,
+                ", auth token {}",
+                if valid { "valid" } else { "expired" }
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use crate::api::BundleOutcome;
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::bundle::BundleResult;
+use solana_transaction::versioned::VersionedTransaction;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot;
+
+const SUBMIT: &str = "SUBMIT";
+const SENT: &str = "SENT";
+const FAILED: &str = "FAILED";
+const RESULT: &str = "RESULT";
+
+/// Append-only, crash-safe log of bundle submissions and their outcomes.
+///
+/// Each submission is written and synced to disk before it is sent, then marked with the send outcome and, once known, the bundle result.
+/// After a restart, `unresolved` reports the bundles whose fate is unknown.
+///
+/// Records are written by a dedicated thread, so sends never wait on the disk inside the async runtime. The thread syncs the records queued
+/// while it was busy together, so concurrent sends share one sync. Outcome records are synced in the background, with the next batch.
+///
+/// The file is line based, one tab-separated record per line:
+/// - `SUBMIT <id> <unix ms> <signatures>`
+/// - `SENT <id> <uuid>`
+/// - `FAILED <id> <error>`
+/// - `RESULT <uuid> <outcome>`
+pub struct BundleJournal {
+    path: PathBuf,
+    writer: Option<Sender<Command>>,
+    handle: Option<JoinHandle<()>>,
+    next_seq: AtomicU64,
+}
+
+// Work queued for the writer thread, each acknowledged once synced to disk if asked to
+enum Command {
+    Append(String, Option<oneshot::Sender<io::Result<()>>>),
+    Flush(Sender<io::Result<()>>),
+}
+
+/// A submission recorded in the journal whose outcome is unknown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub id: String,
+    pub submitted_at: SystemTime,
+    /// Base58 signature of the first signer of each transaction
+    pub signatures: Vec<String>,
+    /// The bundle uuid, if the block engine accepted the send before the outcome was lost
+    pub uuid: Option<String>,
+}
+
+impl BundleJournal {
+    /// Opens the journal at `path`, creating it if it doesn't exist. Existing records are kept.
+    pub fn open(path: impl AsRef<Path>) -> JitoClientResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(JitoClientError::JournalError)?;
+        let (writer, commands) = mpsc::channel();
+        let handle = thread::Builder::new()
+            .name("jito-journal".to_string())
+            .spawn(move || write_loop(file, commands))
+            .map_err(JitoClientError::JournalError)?;
+        Ok(Self {
+            path,
+            writer: Some(writer),
+            handle: Some(handle),
+            next_seq: AtomicU64::new(0),
+        })
+    }
+
+    /// Records a bundle about to be sent, returning the id used to mark its outcome once the record is synced to disk.
+    pub async fn record_submission(
+        &self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<String> {
+        let now = unix_millis(SystemTime::now());
+        let id = format!("{now}-{}", self.next_seq.fetch_add(1, Ordering::Relaxed));
+        let signatures: Vec<String> = transactions
+            .iter()
+            .filter_map(|txn| txn.signatures.first().map(|s| s.to_string()))
+            .collect();
+        let (synced, ack) = oneshot::channel();
+        self.send(Command::Append(
+            [SUBMIT, &id, &now.to_string(), &signatures.join(",")].join("\t"),
+            Some(synced),
+        ))?;
+        ack.await
+            .map_err(|_| stopped())?
+            .map_err(JitoClientError::JournalError)?;
+        Ok(id)
+    }
+
+    /// Marks a submission as accepted by the block engine with the given uuid.
+    pub fn record_sent(&self, id: &str, uuid: &str) -> JitoClientResult<()> {
+        self.append(&[SENT, id, uuid])
+    }
+
+    /// Marks a submission as failed to send.
+    pub fn record_failed(&self, id: &str, error: &dyn Display) -> JitoClientResult<()> {
+        let error = error.to_string().replace(['\t', '\n', '\r'], " ");
+        self.append(&[FAILED, id, &error])
+    }
+
+    /// Records the eventual result of a sent bundle, as received from the bundle result stream.
+    pub fn record_result(&self, result: &BundleResult) -> JitoClientResult<()> {
+        let outcome = result.outcome();
+        let outcome = outcome.as_ref().map_or("unknown", BundleOutcome::name);
+        self.append(&[RESULT, &result.bundle_id, outcome])
+    }
+
+    /// Returns the submissions whose fate is unknown: those never marked as sent or failed, and those sent without a recorded result.
+    pub fn unresolved(&self) -> JitoClientResult<Vec<JournalEntry>> {
+        self.flush()?;
+        let file = File::open(&self.path).map_err(JitoClientError::JournalError)?;
+        let mut entries: Vec<JournalEntry> = Vec::new();
+        let mut index = HashMap::new();
+        let mut resolved = HashMap::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(JitoClientError::JournalError)?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                [SUBMIT, id, millis, signatures] => {
+                    let Ok(millis) = millis.parse() else { continue };
+                    index.insert(id.to_string(), entries.len());
+                    entries.push(JournalEntry {
+                        id: id.to_string(),
+                        submitted_at: UNIX_EPOCH + Duration::from_millis(millis),
+                        signatures: signatures
+                            .split(',')
+                            .filter(|s| !s.is_empty())
+                            .map(str::to_string)
+                            .collect(),
+                        uuid: None,
+                    });
+                }
+                [SENT, id, uuid] => {
+                    if let Some(entry) = index.get(*id).map(|i| &mut entries[*i]) {
+                        entry.uuid = Some(uuid.to_string());
+                    }
+                }
+                [FAILED, id, _] => {
+                    resolved.insert(id.to_string(), true);
+                }
+                [RESULT, uuid, _] => {
+                    resolved.insert(uuid.to_string(), true);
+                }
+                _ => debug!("Skipping malformed journal line: {line}"),
+            }
+        }
+
+        entries.retain(|entry| {
+            !resolved.contains_key(&entry.id)
+                && !entry
+                    .uuid
+                    .as_ref()
+                    .is_some_and(|uuid| resolved.contains_key(uuid))
+        });
+        Ok(entries)
+    }
+
+    /// Waits until every record queued so far is written and synced to disk. Blocks the calling thread for the length of a sync.
+    pub fn flush(&self) -> JitoClientResult<()> {
+        let (synced, ack) = mpsc::channel();
+        self.send(Command::Flush(synced))?;
+        ack.recv()
+            .map_err(|_| stopped())?
+            .map_err(JitoClientError::JournalError)
+    }
+
+    // Queues one record, synced in the background with the next batch
+    fn append(&self, fields: &[&str]) -> JitoClientResult<()> {
+        self.send(Command::Append(fields.join("\t"), None))
+    }
+
+    fn send(&self, command: Command) -> JitoClientResult<()> {
+        self.writer
+            .as_ref()
+            .and_then(|writer| writer.send(command).ok())
+            .ok_or_else(stopped)
+    }
+}
+
+// Waits for the writer to drain the queue, so records outlive the journal
+impl Drop for BundleJournal {
+    fn drop(&mut self) {
+        drop(self.writer.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// Writes the queued records, syncing every batch received while the previous one was written once, then acknowledges them
+fn write_loop(mut file: File, commands: Receiver<Command>) {
+    while let Ok(first) = commands.recv() {
+        let batch: Vec<Command> = std::iter::once(first).chain(commands.try_iter()).collect();
+        let written = batch.iter().try_for_each(|command| match command {
+            Command::Append(line, _) => writeln!(file, "{line}"),
+            Command::Flush(_) => Ok(()),
+        });
+        let synced = written.and_then(|()| file.sync_data());
+        if let Err(e) = &synced {
+            debug!("Journal write error: {e}");
+        }
+        let result = || match &synced {
+            Ok(()) => Ok(()),
+            Err(e) => Err(io::Error::new(e.kind(), e.to_string())),
+        };
+        for command in batch {
+            match command {
+                Command::Append(_, Some(ack)) => {
+                    let _ = ack.send(result());
+                }
+                Command::Append(_, None) => {}
+                Command::Flush(ack) => {
+                    let _ = ack.send(result());
+                }
+            }
+        }
+    }
+}
+
+fn stopped() -> JitoClientError {
+    JitoClientError::JournalError(io::Error::other("journal writer stopped"))
+}
+
+fn unix_millis(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+
+    #[tokio::test]
+    async fn unresolved_submissions_survive_reopen() {
+        let path = std::env::temp_dir().join(format!(
+            "jito-journal-{}-{}.log",
+            std::process::id(),
+            unix_millis(SystemTime::now())
+        ));
+        let txns = vec![VersionedTransaction::default()];
+        let (crashed, unknown) = {
+            let journal = BundleJournal::open(&path).unwrap();
+            let crashed = journal.record_submission(&txns).await.unwrap();
+
+            let failed = journal.record_submission(&txns).await.unwrap();
+            journal.record_failed(&failed, &"unavailable").unwrap();
+
+            let landed = journal.record_submission(&txns).await.unwrap();
+            journal.record_sent(&landed, "uuid-landed").unwrap();
+            journal
+                .record_result(&BundleResult {
+                    bundle_id: "uuid-landed".to_string(),
+                    result: Some(ResultKind::Finalized(Finalized {})),
+                })
+                .unwrap();
+
+            let unknown = journal.record_submission(&txns).await.unwrap();
+            journal.record_sent(&unknown, "uuid-unknown").unwrap();
+            (crashed, unknown)
+        };
+
+        let unresolved = BundleJournal::open(&path).unwrap().unresolved().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(unresolved.len(), 2);
+        assert_eq!(unresolved[0].id, crashed);
+        assert_eq!(unresolved[0].uuid, None);
+        assert_eq!(unresolved[1].id, unknown);
+        assert_eq!(unresolved[1].uuid.as_deref(), Some("uuid-unknown"));
+    }
+}
@@ -1,7 +1,88 @@
+#[cfg(feature = "native")]
+#[macro_use]
+mod logging;
+
+pub mod api;
+#[cfg(feature = "native")]
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "native")]
+pub mod builder;
 pub mod bundle;
+#[cfg(feature = "native")]
 pub mod client;
+pub mod clock;
+pub mod compute_budget;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "native")]
+pub mod connector;
+#[cfg(feature = "native")]
+pub mod dedup;
 pub mod errors;
+#[cfg(feature = "native")]
+pub mod experiment;
+pub mod expiry;
+#[cfg(feature = "native")]
+pub mod fanout;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "native")]
+mod files;
+#[cfg(feature = "native")]
+pub mod health;
+#[cfg(feature = "native")]
+pub mod journal;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+#[cfg(feature = "latency")]
 pub mod nodes;
+#[cfg(feature = "native")]
+pub mod pipeline;
+#[cfg(feature = "native")]
+pub mod prelude;
+pub mod protos;
+mod random;
+pub mod region;
+#[cfg(feature = "native")]
+pub mod relayer;
+pub mod report;
+#[cfg(feature = "native")]
+pub mod resolver;
+pub mod retry;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "latency")]
+pub mod selection;
+#[cfg(feature = "native")]
+pub mod sender;
+#[cfg(feature = "native")]
+pub mod signing;
+#[cfg(feature = "native")]
+pub mod sink;
+#[cfg(feature = "native")]
+pub mod stats;
+#[cfg(feature = "native")]
+pub mod submit;
+#[cfg(feature = "native")]
+pub mod subscription;
+#[cfg(all(feature = "native", any(test, feature = "testing")))]
+pub mod testing;
+#[cfg(feature = "tip-stream")]
+pub mod tip_stream;
+#[cfg(feature = "native")]
+pub mod tips;
+#[cfg(feature = "native")]
+pub mod tracker;
+#[cfg(feature = "native")]
+pub mod transport;
+#[cfg(feature = "latency")]
+mod warm;
+#[cfg(feature = "web")]
+pub mod web;
 
 pub mod grpc {
     pub mod searcher {
@@ -16,4 +97,13 @@ pub mod grpc {
     pub mod shared {
         tonic::include_proto!("shared");
     }
+    pub mod auth {
+        tonic::include_proto!("auth");
+    }
+    pub mod block_engine {
+        tonic::include_proto!("block_engine");
+    }
+    pub mod relayer {
+        tonic::include_proto!("relayer");
+    }
 }
@@ -0,0 +1,9 @@
+// Logs through the `log` crate with the `log` feature. Without it the message is still type checked, so its arguments don't go unused, but nothing is logged
+macro_rules! debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        ::log::debug!($($arg)+);
+        #[cfg(not(feature = "log"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
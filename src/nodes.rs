@@ -1,47 +1,182 @@
+use crate::connector::{IpPreference, connect_any};
 use crate::errors::{JitoClientError, JitoClientResult};
-use std::fmt::{Display, Formatter};
-use std::net::{TcpStream, ToSocketAddrs};
-use std::time::{Duration, Instant};
+use crate::files::atomic_write;
+pub use crate::region::{Network, NodeRegion, RegionOverrides};
+use crate::resolver::Resolver;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::time;
 
 const TIMEOUT: Duration = Duration::from_secs(3);
+const DEADLINE: Duration = Duration::from_secs(5);
 
-#[derive(Debug, Clone, Copy)]
-pub enum NodeRegion {
-    AM,
-    DB,
-    FRA,
-    LN,
-    NY,
-    SLC,
-    SG,
-    TOK,
+/// Options for measuring the latency to each region.
+#[derive(Debug, Clone)]
+pub struct PingConfig {
+    /// Resolves the region host names. Pin or cache hosts to keep DNS variance out of the measurements
+    pub resolver: Resolver,
+    /// Which address family to connect over when a region resolves to both
+    pub ip_preference: IpPreference,
+    /// Maximum time to wait for each TCP connection
+    pub timeout: Duration,
+    /// Maximum time to wait for all regions together, including DNS resolution. Regions not measured by then count as timed out
+    pub deadline: Duration,
+    /// Endpoints measured instead of the regions' own
+    pub overrides: RegionOverrides,
+    /// Network whose block engines are measured. Only regions with a block engine on the network, or an override, are measured
+    pub network: Network,
+}
+
+impl Default for PingConfig {
+    fn default() -> Self {
+        Self {
+            resolver: Resolver::default(),
+            ip_preference: IpPreference::default(),
+            timeout: TIMEOUT,
+            deadline: DEADLINE,
+            overrides: RegionOverrides::default(),
+            network: Network::default(),
+        }
+    }
+}
+
+impl PingConfig {
+    // Regions to measure: those with a block engine on the network, or an override
+    fn regions(&self) -> Vec<NodeRegion> {
+        NodeRegion::ALL
+            .into_iter()
+            .filter(|region| self.overrides.endpoint_on(*region, self.network).is_some())
+            .collect()
+    }
+}
+
+/// Region latencies measured at one point in time, as kept by a `LatencyStore`.
+#[derive(Debug, Clone)]
+pub struct LatencySnapshot {
+    /// When the latencies were measured
+    pub measured_at: SystemTime,
+    /// Latency of every region that could be measured
+    pub latencies: Vec<(NodeRegion, Duration)>,
+}
+
+impl LatencySnapshot {
+    /// Takes a snapshot of the successfully measured latencies, such as those returned by `NodeRegion::measure_all`.
+    pub fn new(results: &[(NodeRegion, JitoClientResult<Duration>)]) -> Self {
+        Self {
+            measured_at: SystemTime::now(),
+            latencies: results
+                .iter()
+                .filter_map(|(region, latency)| Some((*region, *latency.as_ref().ok()?)))
+                .collect(),
+        }
+    }
+
+    /// Returns whether the latencies were measured less than `max_age` ago. A snapshot from the future, after the clock was set back, is not fresh.
+    pub fn is_fresh(&self, max_age: Duration) -> bool {
+        SystemTime::now()
+            .duration_since(self.measured_at)
+            .is_ok_and(|age| age < max_age)
+    }
+}
+
+/// Keeps the last region latency measurements across restarts, so a client can connect to the fastest region without measuring every region first.
+///
+/// Implement it to keep measurements in a database or shared cache, or use `FileLatencyStore`. The client builder calls the store on tokio's
+/// blocking thread pool, so implementations may block on IO.
+pub trait LatencyStore: Send + Sync {
+    /// Returns the last saved snapshot, if any.
+    fn load(&self) -> JitoClientResult<Option<LatencySnapshot>>;
+
+    /// Saves a snapshot, replacing the previous one.
+    fn save(&self, snapshot: &LatencySnapshot) -> JitoClientResult<()>;
+}
+
+/// Keeps the last region latency measurements in a file, one region code and latency in microseconds per line after the measurement time.
+#[derive(Debug)]
+pub struct FileLatencyStore {
+    path: PathBuf,
+    // Serializes saves, which rewrite the whole file
+    lock: Mutex<()>,
+}
+
+impl FileLatencyStore {
+    /// Stores measurements in the file at `path`, which is created on the first save.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl LatencyStore for FileLatencyStore {
+    fn load(&self) -> JitoClientResult<Option<LatencySnapshot>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(JitoClientError::LatencyStoreError(e)),
+        };
+        let mut lines = contents.lines();
+        let Some(Ok(measured_ms)) = lines.next().map(str::parse) else {
+            debug!("Skipping malformed latency store");
+            return Ok(None);
+        };
+        let latencies = lines
+            .filter_map(|line| {
+                let (code, micros) = line.split_once('\t')?;
+                Some((
+                    code.parse().ok()?,
+                    Duration::from_micros(micros.parse().ok()?),
+                ))
+            })
+            .collect();
+        Ok(Some(LatencySnapshot {
+            measured_at: UNIX_EPOCH + Duration::from_millis(measured_ms),
+            latencies,
+        }))
+    }
+
+    fn save(&self, snapshot: &LatencySnapshot) -> JitoClientResult<()> {
+        let _lock = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let measured_ms = snapshot
+            .measured_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut lines = vec![measured_ms.to_string()];
+        for (region, latency) in &snapshot.latencies {
+            lines.push(format!("{}\t{}", region.code(), latency.as_micros()));
+        }
+
+        atomic_write(&self.path, &lines.join("\n"), false)
+            .map_err(JitoClientError::LatencyStoreError)
+    }
 }
 
 impl NodeRegion {
-    const ALL: [NodeRegion; 8] = [
-        NodeRegion::AM,
-        NodeRegion::DB,
-        NodeRegion::FRA,
-        NodeRegion::LN,
-        NodeRegion::NY,
-        NodeRegion::SLC,
-        NodeRegion::SG,
-        NodeRegion::TOK,
-    ];
-
-    /// Pings each endpoint by performing a DNS resolution and establishing a TCP connection, and returns the endpoint with the fastest response time, along with the time (ms) it took.
+    /// Pings each endpoint by performing a DNS resolution and establishing a TCP connection, racing all resolved addresses, and returns the endpoint with the fastest response time, along with the time (ms) it took.
     pub async fn measure_latency() -> JitoClientResult<(Self, Duration)> {
-        let tasks: Vec<_> = Self::ALL
-            .iter()
-            .map(|region| async move { (*region, region.ping()) })
-            .collect();
+        Self::measure_latency_with(&PingConfig::default()).await
+    }
 
-        let results = futures::future::join_all(tasks).await;
+    /// Same as `measure_latency`, with the given resolver, timeout and region overrides.
+    pub async fn measure_latency_with(config: &PingConfig) -> JitoClientResult<(Self, Duration)> {
+        Self::fastest(Self::measure_all(config).await)
+    }
 
+    // Returns the region with the lowest successfully measured latency, or every region's failure if none was measured
+    pub(crate) fn fastest(
+        results: Vec<(Self, JitoClientResult<Duration>)>,
+    ) -> JitoClientResult<(Self, Duration)> {
         let mut successful_pings = Vec::new();
+        let mut failures = Vec::new();
         for (region, result) in results {
-            if let Ok(duration) = result {
-                successful_pings.push((region, duration));
+            match result {
+                Ok(duration) => successful_pings.push((region, duration)),
+                Err(e) => failures.push((region, e)),
             }
         }
 
@@ -55,57 +190,105 @@ impl NodeRegion {
                 _ => {}
             }
         }
-        fastest.ok_or(JitoClientError::AllRegionLatencyMissing)
+        fastest.ok_or(JitoClientError::AllRegionLatencyMissing(failures))
     }
 
-    // Attempts to perform a DNS resolution and establish a TCP connection, and returns the total execution time (ms)
-    fn ping(&self) -> JitoClientResult<Duration> {
-        let start = Instant::now();
-        let addr = self
-            .host()
-            .to_socket_addrs()
-            .map_err(|e| JitoClientError::DNSResolution(e))?
-            .next()
-            .ok_or(JitoClientError::DNSEmpty)?;
-        let _ = TcpStream::connect_timeout(&addr, TIMEOUT)
-            .map_err(|e| JitoClientError::TCPConnect(e))?;
-        Ok(start.elapsed())
-    }
-
-    pub fn all() -> &'static [NodeRegion] {
-        &Self::ALL
-    }
-
-    pub fn endpoint(&self) -> &'static str {
-        match self {
-            NodeRegion::AM => "https://amsterdam.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::DB => "https://dublin.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::FRA => "https://frankfurt.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::LN => "https://london.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::NY => "https://ny.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::SLC => "https://slc.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::SG => "https://singapore.mainnet.block-engine.jito.wtf:443",
-            NodeRegion::TOK => "https://tokyo.mainnet.block-engine.jito.wtf:443",
+    /// Pings every region of the config's network concurrently, returning each region's response time or the error that prevented measuring it.
+    ///
+    /// Each ping runs as its own task on the tokio runtime, so the pings don't delay each other. Returns once every region is measured or the config's deadline passes,
+    /// whichever comes first, with the regions still unmeasured at the deadline reported as timed out. Must be called within a tokio runtime.
+    pub async fn measure_all(config: &PingConfig) -> Vec<(Self, JitoClientResult<Duration>)> {
+        let regions = config.regions();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let tasks: Vec<_> = regions
+            .iter()
+            .enumerate()
+            .map(|(index, &region)| {
+                let (config, sender) = (config.clone(), sender.clone());
+                tokio::spawn(async move {
+                    let _ = sender.send((index, region.ping_async(&config).await));
+                })
+            })
+            .collect();
+        drop(sender);
+
+        let mut measured: Vec<Option<JitoClientResult<Duration>>> =
+            regions.iter().map(|_| None).collect();
+        {
+            let collect = async {
+                while let Some((index, result)) = receiver.recv().await {
+                    measured[index] = Some(result);
+                }
+            };
+            let _ = time::timeout(config.deadline, collect).await;
+        }
+        for task in tasks {
+            task.abort();
         }
-    }
 
-    fn host(&self) -> &'static str {
-        &self.endpoint()[8..]
+        regions
+            .into_iter()
+            .zip(measured)
+            .map(|(region, result)| {
+                let result = result.unwrap_or_else(|| {
+                    Err(JitoClientError::TCPConnect {
+                        region: Some(region),
+                        host: config
+                            .overrides
+                            .address_of(region, config.network)
+                            .map(|(host, _)| host)
+                            .unwrap_or_default(),
+                        source: io::ErrorKind::TimedOut.into(),
+                    })
+                });
+                (region, result)
+            })
+            .collect()
     }
-}
 
-impl Display for NodeRegion {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            NodeRegion::AM => write!(f, "Amsterdam"),
-            NodeRegion::DB => write!(f, "Dublin"),
-            NodeRegion::FRA => write!(f, "Frankfurt"),
-            NodeRegion::LN => write!(f, "London"),
-            NodeRegion::NY => write!(f, "New York"),
-            NodeRegion::SLC => write!(f, "Salt Lake City"),
-            NodeRegion::SG => write!(f, "Singapore"),
-            NodeRegion::TOK => write!(f, "Tokyo"),
-        }
+    /// Measures the latency to this region by resolving its host and establishing a TCP connection, racing all resolved addresses, and returns the total time it took.
+    ///
+    /// Useful to monitor the reachability of each region, for example with `NodeRegion::all`.
+    ///
+    /// # Errors
+    /// This function will return an error if the region has no block engine on the config's network, the host cannot be resolved,
+    /// or no connection is established within the config's timeout.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::nodes::{NodeRegion, PingConfig};
+    /// # async fn example() {
+    /// let config = PingConfig::default();
+    /// for region in NodeRegion::all() {
+    ///     match region.ping_async(&config).await {
+    ///         Ok(latency) => println!("{region} ({}): {latency:?}", region.host()),
+    ///         Err(e) => println!("{region} is unreachable: {e}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn ping_async(&self, config: &PingConfig) -> JitoClientResult<Duration> {
+        let start = Instant::now();
+        let (host, port) = config
+            .overrides
+            .address_of(*self, config.network)
+            .ok_or(JitoClientError::NoBlockEngine(*self, config.network))?;
+        let addrs = config
+            .resolver
+            .lookup(&host)
+            .await
+            .map_err(|e| e.in_region(*self))?;
+        let connect = connect_any(&addrs, port, config.ip_preference);
+        let source = match time::timeout(config.timeout, connect).await {
+            Ok(Ok(_)) => return Ok(start.elapsed()),
+            Ok(Err(e)) => e,
+            Err(_) => io::ErrorKind::TimedOut.into(),
+        };
+        Err(JitoClientError::TCPConnect {
+            region: Some(*self),
+            host,
+            source,
+        })
     }
 }
 
@@ -114,26 +297,182 @@ mod tests {
     use super::*;
     use serial_test::serial;
 
-    #[test]
+    #[tokio::test]
     #[serial]
-    fn print_all() {
+    #[ignore = "connects to Jito's public block engines"]
+    async fn print_all() {
         println!("All Node Regions:");
         for region in NodeRegion::ALL {
             println!(
                 "Region: {}, URL: {}; ping: {} ms",
                 region,
                 region.endpoint(),
-                region.ping().unwrap_or(Duration::from_secs(0)).as_millis()
+                region
+                    .ping_async(&PingConfig::default())
+                    .await
+                    .unwrap_or(Duration::from_secs(0))
+                    .as_millis()
             );
         }
     }
 
     #[tokio::test]
     #[serial]
+    #[ignore = "connects to Jito's public block engines"]
     async fn measure_latency() {
         match NodeRegion::measure_latency().await {
             Ok(a) => println!("Lowest latency node: {}, {} ms", a.0, a.1.as_millis()),
             Err(e) => panic!("Measure latency failed: {e}"),
         }
     }
+
+    // Points `region` at a local listener, which accepts the TCP connections of the pings
+    async fn local_endpoint(
+        overrides: RegionOverrides,
+        region: NodeRegion,
+    ) -> (RegionOverrides, tokio::net::TcpListener) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let overrides = overrides.endpoint(region, Box::leak(endpoint.into_boxed_str()));
+        (overrides, listener)
+    }
+
+    #[tokio::test]
+    async fn measure_latency_with_injected_endpoints() {
+        let (overrides, _ny) = local_endpoint(RegionOverrides::new(), NodeRegion::NY).await;
+        let (overrides, _fra) = local_endpoint(overrides, NodeRegion::FRA).await;
+        let config = PingConfig {
+            overrides,
+            network: Network::Devnet,
+            ..PingConfig::default()
+        };
+        let (region, _) = NodeRegion::measure_latency_with(&config).await.unwrap();
+        assert!(matches!(region, NodeRegion::NY | NodeRegion::FRA));
+    }
+
+    #[test]
+    fn fastest_reports_every_failure() {
+        let failure = |region: NodeRegion| JitoClientError::TCPConnect {
+            region: Some(region),
+            host: region.host().to_string(),
+            source: io::ErrorKind::TimedOut.into(),
+        };
+        let results = vec![
+            (NodeRegion::NY, Err(failure(NodeRegion::NY))),
+            (NodeRegion::TOK, Err(failure(NodeRegion::TOK))),
+        ];
+        let Err(JitoClientError::AllRegionLatencyMissing(failures)) = NodeRegion::fastest(results)
+        else {
+            panic!("no region succeeded");
+        };
+        assert_eq!(failures.len(), 2);
+        assert!(failures[1].1.to_string().contains(NodeRegion::TOK.host()));
+
+        let results = vec![
+            (NodeRegion::NY, Err(failure(NodeRegion::NY))),
+            (NodeRegion::TOK, Ok(Duration::from_millis(5))),
+        ];
+        assert_eq!(NodeRegion::fastest(results).unwrap().0, NodeRegion::TOK);
+    }
+
+    #[tokio::test]
+    async fn unfinished_pings_time_out_at_deadline() {
+        use crate::resolver::Resolve;
+        use futures::future::BoxFuture;
+        use std::net::IpAddr;
+
+        struct Hanging;
+        impl Resolve for Hanging {
+            fn resolve<'a>(&'a self, _: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+                Box::pin(futures::future::pending())
+            }
+        }
+
+        let config = PingConfig {
+            resolver: Resolver::new(Hanging),
+            deadline: Duration::from_millis(50),
+            ..PingConfig::default()
+        };
+        let start = Instant::now();
+        let results = NodeRegion::measure_all(&config).await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(results.len(), NodeRegion::ALL.len());
+        assert!(results.iter().all(|(_, result)| matches!(
+            result,
+            Err(JitoClientError::TCPConnect { source, .. }) if source.kind() == io::ErrorKind::TimedOut
+        )));
+    }
+
+    #[tokio::test]
+    async fn overridden_region_is_pinged_at_override() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let overrides =
+            RegionOverrides::new().endpoint(NodeRegion::NY, Box::leak(endpoint.into_boxed_str()));
+        assert_eq!(
+            overrides.endpoint_of(NodeRegion::TOK),
+            NodeRegion::TOK.endpoint()
+        );
+        let config = PingConfig {
+            overrides,
+            ..PingConfig::default()
+        };
+        assert!(NodeRegion::NY.ping_async(&config).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn only_regions_on_the_network_are_measured() {
+        assert_eq!(
+            NodeRegion::NY.host_on(Network::Testnet),
+            Some("ny.testnet.block-engine.jito.wtf")
+        );
+        assert_eq!(NodeRegion::TOK.endpoint_on(Network::Testnet), None);
+
+        let config = PingConfig {
+            network: Network::Devnet,
+            ..PingConfig::default()
+        };
+        assert!(NodeRegion::measure_all(&config).await.is_empty());
+        assert!(matches!(
+            NodeRegion::NY.ping_async(&config).await,
+            Err(JitoClientError::NoBlockEngine(
+                NodeRegion::NY,
+                Network::Devnet
+            ))
+        ));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let config = PingConfig {
+            overrides: RegionOverrides::new()
+                .endpoint(NodeRegion::FRA, Box::leak(endpoint.into_boxed_str())),
+            ..config
+        };
+        let results = NodeRegion::measure_all(&config).await;
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], (NodeRegion::FRA, Ok(_))));
+    }
+
+    #[test]
+    fn file_store_round_trips_latencies() {
+        let path = std::env::temp_dir().join(format!("jito-latency-{}", std::process::id()));
+        let store = FileLatencyStore::new(&path);
+        assert!(store.load().unwrap().is_none());
+
+        let snapshot = LatencySnapshot::new(&[
+            (NodeRegion::NY, Ok(Duration::from_micros(1500))),
+            (NodeRegion::TOK, Err(JitoClientError::MeasureLatencyError)),
+        ]);
+        store.save(&snapshot).unwrap();
+        let loaded = store.load().unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.latencies.len(), 1);
+        assert_eq!(
+            loaded.latencies,
+            [(NodeRegion::NY, Duration::from_micros(1500))]
+        );
+        assert!(loaded.is_fresh(Duration::from_secs(60)));
+        assert!(!loaded.is_fresh(Duration::ZERO));
+    }
 }
@@ -0,0 +1,235 @@
+use crate::client::JitoClient;
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::tips::{TipInputs, TipStrategy};
+use futures::stream::{self, FusedStream, Stream, StreamExt};
+use solana_transaction::versioned::VersionedTransaction;
+use std::time::Duration;
+use tokio::time::{self, Instant};
+
+type TipFn =
+    Box<dyn FnMut(&[VersionedTransaction]) -> JitoClientResult<VersionedTransaction> + Send>;
+
+/// Groups transactions from a stream into bundles and submits them through a `JitoClient`.
+///
+/// Each bundle is opened by the first transaction received and closed once it is full or the configured time window elapses, whichever comes first.
+pub struct BundlePipeline {
+    client: JitoClient,
+    window: Duration,
+    tip: Option<TipFn>,
+}
+
+impl BundlePipeline {
+    /// Creates a new pipeline that submits through `client`, waiting at most `window` for a bundle to fill up after its first transaction arrives.
+    pub fn new(client: JitoClient, window: Duration) -> Self {
+        Self {
+            client,
+            window,
+            tip: None,
+        }
+    }
+
+    /// Appends a tip transaction to every bundle before it is submitted.
+    ///
+    /// The closure receives the grouped transactions and returns the tip transaction to append. One bundle slot is reserved for the tip, so at most `txns_limit() - 1` transactions of the client's bundle options are grouped per bundle.
+    ///
+    /// # Errors
+    /// This function will return `TooManyTxns` if the client's bundle options leave no room for a transaction besides the tip.
+    pub fn with_tip<F>(mut self, tip: F) -> JitoClientResult<Self>
+    where
+        F: FnMut(&[VersionedTransaction]) -> JitoClientResult<VersionedTransaction>
+            + Send
+            + 'static,
+    {
+        if self.client.bundle_options().txns_limit() < 2 {
+            return Err(JitoClientError::TooManyTxns);
+        }
+        self.tip = Some(Box::new(tip));
+        Ok(self)
+    }
+
+    /// Appends a tip transaction sized by `strategy` to every bundle before it is submitted.
+    ///
+    /// `inputs` returns the market data the strategy sizes the tip of the grouped transactions from, and `tip` builds the tip transaction
+    /// paying the sized amount of lamports. Reserves a bundle slot for the tip like `with_tip`.
+    ///
+    /// # Errors
+    /// This function will return `TooManyTxns` if the client's bundle options leave no room for a transaction besides the tip.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, pipeline::BundlePipeline};
+    /// # use jito_grpc_client::tips::{TipInputs, TipStrategy};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use std::time::Duration;
+    /// # fn build_tip(lamports: u64) -> JitoClientResult<VersionedTransaction> { unimplemented!() }
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    /// let pipeline = BundlePipeline::new(client, Duration::from_millis(50)).with_tip_strategy(
+    ///     TipStrategy::ProfitShare { basis_points: 5_000, min: 1_000, max: 1_000_000 },
+    ///     |_| TipInputs { expected_profit: Some(50_000), ..Default::default() },
+    ///     |_, lamports| build_tip(lamports),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tip_strategy<I, F>(
+        self,
+        strategy: TipStrategy,
+        mut inputs: I,
+        mut tip: F,
+    ) -> JitoClientResult<Self>
+    where
+        I: FnMut(&[VersionedTransaction]) -> TipInputs + Send + 'static,
+        F: FnMut(&[VersionedTransaction], u64) -> JitoClientResult<VersionedTransaction>
+            + Send
+            + 'static,
+    {
+        self.with_tip(move |batch| {
+            let lamports = strategy.lamports(&inputs(batch))?;
+            tip(batch, lamports)
+        })
+    }
+
+    /// Consumes the transaction stream, returning a stream with the result of each submitted bundle in submission order.
+    ///
+    /// The returned stream ends once the input stream ends and the last partial bundle has been submitted.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, pipeline::BundlePipeline};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use futures::StreamExt;
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    /// let transactions = futures::stream::iter(Vec::<VersionedTransaction>::new());
+    ///
+    /// let mut results = Box::pin(BundlePipeline::new(client, Duration::from_millis(50)).run(transactions));
+    /// while let Some(result) = results.next().await {
+    ///     match result {
+    ///         Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///         Err(e) => eprintln!("Failed to send: {}", e),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run<S>(self, transactions: S) -> impl Stream<Item = JitoClientResult<String>>
+    where
+        S: Stream<Item = VersionedTransaction> + Unpin,
+    {
+        stream::unfold(
+            (self, transactions.fuse()),
+            |(mut pipeline, mut transactions)| async move {
+                let capacity = pipeline.capacity();
+                let batch = next_batch(&mut transactions, capacity, pipeline.window).await?;
+                let result = pipeline.submit(batch).await;
+                Some((result, (pipeline, transactions)))
+            },
+        )
+    }
+
+    fn capacity(&self) -> usize {
+        let limit = self.client.bundle_options().txns_limit();
+        match self.tip {
+            Some(_) => limit - 1,
+            None => limit,
+        }
+    }
+
+    async fn submit(&mut self, mut batch: Vec<VersionedTransaction>) -> JitoClientResult<String> {
+        if let Some(tip) = self.tip.as_mut() {
+            let tip_txn = tip(&batch)?;
+            batch.push(tip_txn);
+        }
+        self.client.send(&batch).await
+    }
+}
+
+// Waits for the first transaction, then keeps collecting until the batch is full, the window elapses, or the stream ends. Returns None if the stream ended before any transaction arrived
+async fn next_batch<S>(
+    transactions: &mut S,
+    capacity: usize,
+    window: Duration,
+) -> Option<Vec<VersionedTransaction>>
+where
+    S: FusedStream<Item = VersionedTransaction> + Unpin,
+{
+    let first = transactions.next().await?;
+    let mut batch = Vec::with_capacity(capacity);
+    batch.push(first);
+
+    let deadline = Instant::now() + window;
+    while batch.len() < capacity {
+        match time::timeout_at(deadline, transactions.next()).await {
+            Ok(Some(txn)) => batch.push(txn),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    Some(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::{BundleOptions, DEFAULT_TXNS_LIMIT};
+    use crate::errors::JitoClientError;
+    use crate::signing::SignerInterceptor;
+    use crate::stats::LatencyStats;
+    use tonic::transport::Channel;
+
+    fn client(max_txns: usize) -> JitoClient {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        JitoClient::from_searcher(
+            SignerInterceptor::default().client(channel),
+            "http://127.0.0.1:1",
+            LatencyStats::default(),
+        )
+        .with_bundle_options(BundleOptions {
+            max_txns: Some(max_txns),
+            ..BundleOptions::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn tip_needs_room_in_the_bundle() {
+        let tip = |_: &[VersionedTransaction]| Ok(VersionedTransaction::default());
+        let pipeline = BundlePipeline::new(client(2), Duration::ZERO);
+        assert_eq!(pipeline.with_tip(tip).unwrap().capacity(), 1);
+        let pipeline = BundlePipeline::new(client(1), Duration::ZERO);
+        assert!(matches!(
+            pipeline.with_tip(tip),
+            Err(JitoClientError::TooManyTxns)
+        ));
+    }
+
+    #[tokio::test]
+    async fn batches_are_capped() {
+        let mut transactions = stream::iter(vec![VersionedTransaction::default(); 7]).fuse();
+        let window = Duration::from_secs(1);
+
+        let first = next_batch(&mut transactions, DEFAULT_TXNS_LIMIT, window).await;
+        let second = next_batch(&mut transactions, DEFAULT_TXNS_LIMIT, window).await;
+        let third = next_batch(&mut transactions, DEFAULT_TXNS_LIMIT, window).await;
+        assert_eq!(first.map(|b| b.len()), Some(5));
+        assert_eq!(second.map(|b| b.len()), Some(2));
+        assert!(third.is_none());
+    }
+
+    #[tokio::test]
+    async fn window_closes_partial_batch() {
+        let mut transactions = stream::iter(vec![VersionedTransaction::default(); 2])
+            .chain(stream::pending())
+            .fuse();
+
+        let start = std::time::Instant::now();
+        let batch = next_batch(
+            &mut transactions,
+            DEFAULT_TXNS_LIMIT,
+            Duration::from_millis(20),
+        )
+        .await;
+        assert_eq!(batch.map(|b| b.len()), Some(2));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,50 @@
+//! The commonly needed types in one import: the client and its wrappers, and the generated bundle result messages with their rejection reasons.
+//!
+//! # Examples
+//! ```no_run
+//! use jito_grpc_client::prelude::*;
+//!
+//! fn describe(result: &BundleResult) -> String {
+//!     match &result.result {
+//!         Some(BundleResultKind::Rejected(Rejected {
+//!             reason: Some(RejectedReason::SimulationFailure(failure)),
+//!         })) => format!("{} failed simulation", failure.tx_signature),
+//!         Some(BundleResultKind::Processed(Processed { slot, .. })) => format!("landed in slot {slot}"),
+//!         _ => match result.outcome() {
+//!             Ok(outcome) => format!("{outcome:?}"),
+//!             Err(e) => e.to_string(),
+//!         },
+//!     }
+//! }
+//!
+//! # async fn example() -> JitoClientResult<()> {
+//! let mut client = JitoClient::new_dynamic_region(None).await?;
+//! let tips = client.tip_account_provider(DEFAULT_TIP_ACCOUNTS_TTL);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::api::{BundleOutcome, NextLeader, RejectionReason, Slot, TipAccounts};
+pub use crate::builder::JitoClientBuilder;
+pub use crate::bundle::{BundleEncoder, BundleOptions, BundleSizeError, PreparedBundle};
+pub use crate::client::{
+    AttemptReport, Backoff, BundleSubmission, JitoClient, RequestOptions, ResponseMetadata,
+    RetryLogic, SubmitPath,
+};
+pub use crate::compute_budget::ComputeBudget;
+pub use crate::errors::{ErrorKind, JitoClientError, JitoClientResult};
+pub use crate::fanout::FanoutClient;
+pub use crate::grpc::bundle::{
+    Accepted, Bundle, BundleResult, Dropped, DroppedReason, Finalized, Processed, Rejected,
+    SimulationFailure, StateAuctionBidRejected, WinningBatchBidRejected,
+    bundle_result::Result as BundleResultKind, rejected::Reason as RejectedReason,
+};
+pub use crate::region::{Network, NodeRegion, RegionOverrides};
+pub use crate::report::BundleReport;
+pub use crate::sender::{BackgroundSender, QueueMode, SendQueuePolicy};
+pub use crate::sink::{Submission, SubmissionSink};
+pub use crate::submit::Submitter;
+pub use crate::tips::{
+    DEFAULT_TIP_ACCOUNTS_TTL, TipAccountProvider, TipStrategy, known_tip_accounts,
+};
+pub use crate::tracker::{BundleTracker, TrackedBundle};
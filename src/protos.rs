@@ -0,0 +1,180 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use prost::Message;
+use prost_types::field_descriptor_proto::Type;
+use prost_types::{FileDescriptorSet, MethodDescriptorProto};
+use std::sync::OnceLock;
+
+/// Revision of the Jito protos the crate was compiled against.
+///
+/// The vendored protos report `vendored`. Setting `JITO_PROTO_DIR` at build time compiles against another checkout instead, such as one
+/// fetched with `scripts/refresh-protos.sh <revision>`, which reports the revision it was fetched at.
+pub const REVISION: &str = env!("JITO_PROTO_REVISION");
+
+const DESCRIPTORS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/jito_descriptors.bin"));
+
+// Methods the client calls, as (service, method, whether the server streams)
+const METHODS: &[(&str, &str, bool)] = &[
+    ("searcher.SearcherService", "SendBundle", false),
+    ("searcher.SearcherService", "SubscribeBundleResults", true),
+    ("searcher.SearcherService", "GetNextScheduledLeader", false),
+    ("searcher.SearcherService", "GetConnectedLeaders", false),
+    (
+        "searcher.SearcherService",
+        "GetConnectedLeadersRegioned",
+        false,
+    ),
+    ("searcher.SearcherService", "GetTipAccounts", false),
+    ("searcher.SearcherService", "GetRegions", false),
+    ("auth.AuthService", "GenerateAuthChallenge", false),
+    ("auth.AuthService", "GenerateAuthTokens", false),
+    ("auth.AuthService", "RefreshAccessToken", false),
+    ("relayer.Relayer", "GetTpuConfigs", false),
+    ("relayer.Relayer", "SubscribePackets", true),
+];
+
+// Fields whose number or type the client relies on, as (message, field, number, type).
+// `Bundle::estimated_wire_size` also assumes bundle and packet fields are numbered below 16, for one byte tags.
+const FIELDS: &[(&str, &str, i32, Type)] = &[
+    ("searcher.SendBundleRequest", "bundle", 1, Type::Message),
+    ("searcher.SendBundleResponse", "uuid", 1, Type::String),
+    (
+        "searcher.GetTipAccountsResponse",
+        "accounts",
+        1,
+        Type::String,
+    ),
+    ("bundle.Bundle", "header", 2, Type::Message),
+    ("bundle.Bundle", "packets", 3, Type::Message),
+    ("bundle.BundleResult", "bundle_id", 1, Type::String),
+    ("packet.Packet", "data", 1, Type::Bytes),
+    ("packet.Packet", "meta", 2, Type::Message),
+    ("packet.Meta", "size", 1, Type::Uint64),
+    ("packet.Meta", "flags", 4, Type::Message),
+];
+
+/// Returns the descriptors of the protos the crate was compiled against.
+pub fn descriptors() -> FileDescriptorSet {
+    FileDescriptorSet::decode(DESCRIPTORS).expect("descriptors are encoded by the build script")
+}
+
+/// Checks the compiled protos declare the methods and fields the client relies on.
+///
+/// Protos from another revision can renumber or retype a field without breaking the build, which then surfaces as confusing decode errors
+/// or silently empty fields at runtime. `JitoClientBuilder::build` runs this check once, so drift fails at startup instead.
+///
+/// # Errors
+/// This function will return a `ProtoMismatch` error listing every expectation the compiled protos don't meet.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::protos;
+/// protos::verify().expect("protos match the client");
+/// println!("Compiled against Jito protos {}", protos::REVISION);
+/// ```
+pub fn verify() -> JitoClientResult<()> {
+    static VERIFIED: OnceLock<Result<(), String>> = OnceLock::new();
+    VERIFIED
+        .get_or_init(|| mismatches(&descriptors()))
+        .clone()
+        .map_err(|mismatches| JitoClientError::ProtoMismatch {
+            revision: REVISION,
+            mismatches,
+        })
+}
+
+// Collects every unmet expectation into one message
+fn mismatches(set: &FileDescriptorSet) -> Result<(), String> {
+    let mut mismatches = Vec::new();
+    for &(service, method, server_streaming) in METHODS {
+        match find_method(set, service, method) {
+            None => mismatches.push(format!("missing method {service}/{method}")),
+            Some(found)
+                if found.client_streaming() || found.server_streaming() != server_streaming =>
+            {
+                mismatches.push(format!("{service}/{method} changed streaming"))
+            }
+            Some(_) => {}
+        }
+    }
+    for &(message, field, number, kind) in FIELDS {
+        let (package, name) = split(message);
+        let found = set
+            .file
+            .iter()
+            .filter(|file| file.package() == package)
+            .flat_map(|file| &file.message_type)
+            .find(|descriptor| descriptor.name() == name)
+            .and_then(|descriptor| descriptor.field.iter().find(|f| f.name() == field));
+        match found {
+            None => mismatches.push(format!("missing field {message}.{field}")),
+            Some(found) if found.number() != number || found.r#type() != kind => {
+                mismatches.push(format!(
+                    "{message}.{field} is {:?} = {}, expected {kind:?} = {number}",
+                    found.r#type(),
+                    found.number()
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches.join(", "))
+    }
+}
+
+fn find_method<'a>(
+    set: &'a FileDescriptorSet,
+    service: &str,
+    method: &str,
+) -> Option<&'a MethodDescriptorProto> {
+    let (package, service) = split(service);
+    set.file
+        .iter()
+        .filter(|file| file.package() == package)
+        .flat_map(|file| &file.service)
+        .find(|descriptor| descriptor.name() == service)?
+        .method
+        .iter()
+        .find(|descriptor| descriptor.name() == method)
+}
+
+// Splits a qualified name such as `packet.Packet` into its package and name
+fn split(qualified: &str) -> (&str, &str) {
+    qualified.split_once('.').unwrap_or(("", qualified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiled_protos_match_expectations() {
+        assert!(verify().is_ok());
+
+        let mut drifted = descriptors();
+        let packet = drifted
+            .file
+            .iter_mut()
+            .filter(|file| file.package() == "packet")
+            .flat_map(|file| &mut file.message_type)
+            .find(|message| message.name() == "Packet")
+            .unwrap();
+        packet.field.retain(|field| field.name() != "meta");
+        packet.field[0].number = Some(7);
+        let searcher = drifted
+            .file
+            .iter_mut()
+            .find(|file| file.package() == "searcher")
+            .unwrap();
+        searcher.service[0]
+            .method
+            .retain(|method| method.name() != "GetRegions");
+
+        let error = mismatches(&drifted).unwrap_err();
+        assert!(error.contains("missing method searcher.SearcherService/GetRegions"));
+        assert!(error.contains("missing field packet.Packet.meta"));
+        assert!(error.contains("packet.Packet.data is Bytes = 7, expected Bytes = 1"));
+    }
+}
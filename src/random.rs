@@ -0,0 +1,60 @@
+// Random numbers for retry jitter, tip account and region selection. Drawn from `rand` with the `jitter` feature,
+// and from the standard library's randomly keyed hasher otherwise, which is good enough to spread waits and picks
+use std::ops::RangeInclusive;
+
+// Returns a number picked uniformly from the range
+#[cfg(feature = "jitter")]
+pub(crate) fn in_range(range: RangeInclusive<u64>) -> u64 {
+    rand::random_range(range)
+}
+
+#[cfg(not(feature = "jitter"))]
+pub(crate) fn in_range(range: RangeInclusive<u64>) -> u64 {
+    let (low, high) = range.into_inner();
+    match (high.saturating_sub(low)).checked_add(1) {
+        Some(span) => low + next() % span,
+        None => next(),
+    }
+}
+
+// Returns an index below `len`, or 0 if `len` is 0
+#[cfg(any(feature = "native", test))]
+pub(crate) fn index(len: usize) -> usize {
+    in_range(0..=len.saturating_sub(1) as u64) as usize
+}
+
+// Returns a number in [0, 1)
+#[cfg(all(feature = "jitter", any(feature = "latency", test)))]
+pub(crate) fn unit() -> f64 {
+    rand::random()
+}
+
+#[cfg(all(not(feature = "jitter"), any(feature = "latency", test)))]
+pub(crate) fn unit() -> f64 {
+    (next() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(not(feature = "jitter"))]
+fn next() -> u64 {
+    use std::hash::{BuildHasher, RandomState};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    RandomState::new().hash_one(COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_stay_in_range() {
+        for _ in 0..1000 {
+            assert!((5..=10).contains(&in_range(5..=10)));
+            assert_eq!(in_range(7..=7), 7);
+            assert!(index(3) < 3);
+            assert!((0.0..1.0).contains(&unit()));
+        }
+        assert_eq!(index(0), 0);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[cfg(feature = "latency")]
+// Port of the block engines, and of overrides without a port or an http scheme
+const PORT: u16 = 443;
+
+/// A Solana cluster whose Jito block engines to connect to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Network {
+    /// Block engines in every region
+    #[default]
+    Mainnet,
+    /// Jito's testnet block engine in New York
+    Testnet,
+    /// Jito runs no public devnet block engine, so devnet regions have to be given with `RegionOverrides`, such as a self-hosted block engine
+    Devnet,
+}
+
+impl Network {
+    /// Returns the regions Jito runs a block engine in on this network.
+    pub fn regions(&self) -> &'static [NodeRegion] {
+        match self {
+            Network::Mainnet => &NodeRegion::ALL,
+            Network::Testnet => &[NodeRegion::NY],
+            Network::Devnet => &[],
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = JitoClientError;
+
+    /// Parses `mainnet`, `testnet` or `devnet`, ignoring case.
+    fn from_str(s: &str) -> JitoClientResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "devnet" => Ok(Network::Devnet),
+            _ => Err(JitoClientError::ConfigError(format!("unknown network {s}"))),
+        }
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+            Network::Devnet => write!(f, "devnet"),
+        }
+    }
+}
+
+/// Endpoint URLs replacing the block engine endpoints of specific regions, such as pointing `NY` at a colo-local reverse proxy.
+///
+/// The overridden regions keep taking part in latency measurement and region selection, measured and connected to at their override.
+/// Set with `JitoClientBuilder::region_overrides`, or in `PingConfig` for `NodeRegion::measure_latency_with`.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{builder::JitoClientBuilder, errors::JitoClientResult, region::{NodeRegion, RegionOverrides}};
+/// # async fn example() -> JitoClientResult<()> {
+/// let overrides = RegionOverrides::new().endpoint(NodeRegion::NY, "https://jito-proxy.ny.internal:8443");
+/// let client = JitoClientBuilder::new()
+///     .region_overrides(overrides)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionOverrides {
+    endpoints: HashMap<NodeRegion, &'static str>,
+}
+
+impl RegionOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the endpoint URL of `region`.
+    pub fn endpoint(mut self, region: NodeRegion, endpoint: &'static str) -> Self {
+        self.endpoints.insert(region, endpoint);
+        self
+    }
+
+    /// Returns the endpoint URL of `region`: its override if it has one, its own mainnet endpoint otherwise.
+    pub fn endpoint_of(&self, region: NodeRegion) -> &'static str {
+        self.endpoints
+            .get(&region)
+            .copied()
+            .unwrap_or_else(|| region.endpoint())
+    }
+
+    /// Returns the endpoint URL of `region` on `network`: its override if it has one, its own endpoint on the network otherwise, if it has one there.
+    pub fn endpoint_on(&self, region: NodeRegion, network: Network) -> Option<&'static str> {
+        self.endpoints
+            .get(&region)
+            .copied()
+            .or_else(|| region.endpoint_on(network))
+    }
+
+    /// Returns whether no region is overridden.
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    #[cfg(feature = "latency")]
+    // Host and port to ping for `region` on `network`, if it has an endpoint there. An override without a port uses the scheme's default one
+    pub(crate) fn address_of(&self, region: NodeRegion, network: Network) -> Option<(String, u16)> {
+        let Some(endpoint) = self.endpoints.get(&region) else {
+            return region.host_on(network).map(|host| (host.to_string(), PORT));
+        };
+        Some(match endpoint.parse::<tonic::codegen::http::Uri>() {
+            Ok(uri) => {
+                let default_port = if uri.scheme_str() == Some("http") {
+                    80
+                } else {
+                    PORT
+                };
+                (
+                    uri.host().unwrap_or_default().to_string(),
+                    uri.port_u16().unwrap_or(default_port),
+                )
+            }
+            Err(_) => (endpoint.to_string(), PORT),
+        })
+    }
+}
+
+/// A Jito block engine region. Serialized as its lowercase region code, such as `ny`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum NodeRegion {
+    AM,
+    DB,
+    FRA,
+    LN,
+    NY,
+    SLC,
+    SG,
+    TOK,
+}
+
+impl NodeRegion {
+    pub(crate) const ALL: [NodeRegion; 8] = [
+        NodeRegion::AM,
+        NodeRegion::DB,
+        NodeRegion::FRA,
+        NodeRegion::LN,
+        NodeRegion::NY,
+        NodeRegion::SLC,
+        NodeRegion::SG,
+        NodeRegion::TOK,
+    ];
+
+    pub fn all() -> &'static [NodeRegion] {
+        &Self::ALL
+    }
+
+    /// Returns the mainnet endpoint URL of the region's block engine.
+    pub fn endpoint(&self) -> &'static str {
+        match self {
+            NodeRegion::AM => "https://amsterdam.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::DB => "https://dublin.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::FRA => "https://frankfurt.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::LN => "https://london.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::NY => "https://ny.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::SLC => "https://slc.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::SG => "https://singapore.mainnet.block-engine.jito.wtf:443",
+            NodeRegion::TOK => "https://tokyo.mainnet.block-engine.jito.wtf:443",
+        }
+    }
+
+    /// Returns the endpoint URL of the region's block engine on `network`, if Jito runs one there.
+    pub fn endpoint_on(&self, network: Network) -> Option<&'static str> {
+        match (network, self) {
+            (Network::Mainnet, _) => Some(self.endpoint()),
+            (Network::Testnet, NodeRegion::NY) => {
+                Some("https://ny.testnet.block-engine.jito.wtf:443")
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the host name of the region's block engine, such as `ny.mainnet.block-engine.jito.wtf`.
+    pub fn host(&self) -> &'static str {
+        Self::host_of(self.endpoint())
+    }
+
+    /// Returns the host name of the region's block engine on `network`, if Jito runs one there.
+    pub fn host_on(&self, network: Network) -> Option<&'static str> {
+        self.endpoint_on(network).map(Self::host_of)
+    }
+
+    // Strips the scheme and port off a region endpoint
+    fn host_of(endpoint: &'static str) -> &'static str {
+        &endpoint[8..endpoint.len() - 4]
+    }
+
+    #[cfg(feature = "latency")]
+    // Short code accepted by `from_str`
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            NodeRegion::AM => "am",
+            NodeRegion::DB => "db",
+            NodeRegion::FRA => "fra",
+            NodeRegion::LN => "ln",
+            NodeRegion::NY => "ny",
+            NodeRegion::SLC => "slc",
+            NodeRegion::SG => "sg",
+            NodeRegion::TOK => "tok",
+        }
+    }
+}
+
+impl FromStr for NodeRegion {
+    type Err = JitoClientError;
+
+    /// Parses a region code, such as `ny` or `fra`, ignoring case.
+    fn from_str(s: &str) -> JitoClientResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "am" => Ok(NodeRegion::AM),
+            "db" => Ok(NodeRegion::DB),
+            "fra" => Ok(NodeRegion::FRA),
+            "ln" => Ok(NodeRegion::LN),
+            "ny" => Ok(NodeRegion::NY),
+            "slc" => Ok(NodeRegion::SLC),
+            "sg" => Ok(NodeRegion::SG),
+            "tok" => Ok(NodeRegion::TOK),
+            _ => Err(JitoClientError::UnknownRegion(s.to_string())),
+        }
+    }
+}
+
+impl Display for NodeRegion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeRegion::AM => write!(f, "Amsterdam"),
+            NodeRegion::DB => write!(f, "Dublin"),
+            NodeRegion::FRA => write!(f, "Frankfurt"),
+            NodeRegion::LN => write!(f, "London"),
+            NodeRegion::NY => write!(f, "New York"),
+            NodeRegion::SLC => write!(f, "Salt Lake City"),
+            NodeRegion::SG => write!(f, "Singapore"),
+            NodeRegion::TOK => write!(f, "Tokyo"),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::auth::{AuthInterceptor, Authenticator};
+use crate::clock::{ClockSkew, ClockSkewEstimator};
+use crate::errors::JitoClientResult;
+use crate::grpc::{
+    auth::Role,
+    block_engine::{
+        AccountsOfInterestRequest, AccountsOfInterestUpdate, ExpiringPacketBatch,
+        PacketBatchUpdate, ProgramsOfInterestRequest, ProgramsOfInterestUpdate,
+        StartExpiringPacketStreamResponse, block_engine_relayer_client::BlockEngineRelayerClient,
+        packet_batch_update::Msg,
+    },
+    bundle::Bundle,
+    packet::PacketBatch,
+    shared::{Header, Heartbeat},
+};
+use crate::transport::{ConnectOptions, normalized};
+use futures::Stream;
+use solana_keypair::Keypair;
+use solana_transaction::versioned::VersionedTransaction;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tonic::Streaming;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Channel, Endpoint};
+
+/// Authenticated client that forwards packets to a block engine as a relayer.
+///
+/// The keypair must be approved by Jito for the relayer role. Access tokens are refreshed in the background for as long as the client is alive.
+pub struct RelayerClient {
+    client: BlockEngineRelayerClient<InterceptedService<Channel, AuthInterceptor>>,
+    endpoint: &'static str,
+    clock: ClockSkewEstimator,
+    refresh_task: JoinHandle<()>,
+}
+
+impl RelayerClient {
+    /// Creates a new relayer client connected and authenticated to the specified block engine endpoint.
+    ///
+    /// # Arguments
+    /// * `endpoint` - The gRPC endpoint URL
+    /// * `keypair` - The relayer's keypair used to authenticate
+    /// * `timeout` - Connection and request timeout. Defaults to 2 seconds if None is passed.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The endpoint is malformed (`InvalidEndpoint`)
+    /// - Connection to the endpoint fails
+    /// - Authentication fails
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{errors::JitoClientResult, relayer::RelayerClient};
+    /// # use solana_keypair::Keypair;
+    /// # use std::sync::Arc;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let keypair = Arc::new(Keypair::new());
+    /// let client = RelayerClient::new("https://ny.mainnet.block-engine.jito.wtf:443", keypair, None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new(
+        endpoint: &'static str,
+        keypair: Arc<Keypair>,
+        timeout: Option<Duration>,
+    ) -> JitoClientResult<Self> {
+        let endpoint = normalized(endpoint)?;
+        let channel = ConnectOptions::with_timeout(timeout)
+            .connect(Endpoint::from_static(endpoint))
+            .await?;
+        let auth = Authenticator::connect(channel.clone(), keypair, Role::Relayer).await?;
+        let client = BlockEngineRelayerClient::with_interceptor(channel, auth.interceptor());
+
+        Ok(Self {
+            client,
+            endpoint,
+            clock: auth.clock_skew(),
+            refresh_task: auth.spawn_refresh_task(),
+        })
+    }
+
+    /// Subscribes to the accounts of interest, used to decide which transactions to forward to the block engine.
+    pub async fn subscribe_accounts_of_interest(
+        &mut self,
+    ) -> JitoClientResult<Streaming<AccountsOfInterestUpdate>> {
+        let response = self
+            .client
+            .subscribe_accounts_of_interest(AccountsOfInterestRequest {})
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Subscribes to the programs of interest, used to decide which transactions to forward to the block engine.
+    pub async fn subscribe_programs_of_interest(
+        &mut self,
+    ) -> JitoClientResult<Streaming<ProgramsOfInterestUpdate>> {
+        let response = self
+            .client
+            .subscribe_programs_of_interest(ProgramsOfInterestRequest {})
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Starts forwarding packets to the block engine. Every update from `updates` is sent over the stream, and the block engine responds with heartbeats.
+    ///
+    /// Use `expiring_batch` and `heartbeat` to build the updates, stamping batches with the estimate of `clock_skew`.
+    pub async fn start_expiring_packet_stream<S>(
+        &mut self,
+        updates: S,
+    ) -> JitoClientResult<Streaming<StartExpiringPacketStreamResponse>>
+    where
+        S: Stream<Item = PacketBatchUpdate> + Send + 'static,
+    {
+        let response = self.client.start_expiring_packet_stream(updates).await?;
+        Ok(response.into_inner())
+    }
+
+    /// Returns the estimator of the block engine's clock, fed by the `date` header of the auth service's responses.
+    pub fn clock_skew(&self) -> ClockSkewEstimator {
+        self.clock.clone()
+    }
+
+    /// Returns the endpoint URL that this client is currently connected to.
+    pub fn get_endpoint(&self) -> &'static str {
+        self.endpoint
+    }
+}
+
+impl Drop for RelayerClient {
+    fn drop(&mut self) {
+        self.refresh_task.abort();
+    }
+}
+
+/// Builds a packet stream update from transactions, which the block engine holds for `expiry_ms` before they are forwarded.
+///
+/// The header is stamped with the current time, converted to the block engine's clock with `clock_skew` if given, such as the estimate of
+/// `RelayerClient::clock_skew`.
+pub fn expiring_batch(
+    transactions: &[VersionedTransaction],
+    expiry_ms: u32,
+    clock_skew: Option<ClockSkew>,
+) -> JitoClientResult<PacketBatchUpdate> {
+    let now = clock_skew.map_or_else(SystemTime::now, |skew| skew.remote_now());
+    Ok(PacketBatchUpdate {
+        msg: Some(Msg::Batches(ExpiringPacketBatch {
+            header: Some(Header {
+                ts: Some(now.into()),
+            }),
+            batch: Some(PacketBatch {
+                packets: Bundle::serialize(transactions)?,
+            }),
+            expiry_ms,
+        })),
+    })
+}
+
+/// Builds a heartbeat packet stream update.
+pub fn heartbeat(count: u64) -> PacketBatchUpdate {
+    PacketBatchUpdate {
+        msg: Some(Msg::Heartbeat(Heartbeat { count })),
+    }
+}
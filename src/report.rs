@@ -0,0 +1,178 @@
+use crate::bundle::PACKET_DATA_SIZE;
+use crate::compute_budget::ComputeBudget;
+use crate::errors::JitoClientResult;
+use crate::grpc::bundle::Bundle;
+use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+use std::fmt::{self, Display, Formatter};
+
+/// Diagnostic summary of a bundle's transactions, produced without sending it, to log alongside a rejection or debug why a bundle doesn't land.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, report::BundleReport};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None).await?;
+/// let tip_accounts = client.get_tip_accounts().await?;
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// if let Err(e) = client.send(&transactions).await {
+///     eprintln!("Bundle rejected: {e}\n{}", BundleReport::new(&transactions, &tip_accounts.0)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleReport {
+    /// Report of every transaction, in bundle order
+    pub transactions: Vec<TransactionReport>,
+    /// Sum of the transactions' serialized sizes
+    pub total_size: usize,
+    /// Size of the bundle as encoded on the wire, see `Bundle::estimated_wire_size`
+    pub wire_size: usize,
+}
+
+/// Diagnostic summary of one transaction of a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionReport {
+    /// Serialized size in bytes
+    pub size: usize,
+    /// Number of signatures attached
+    pub signatures: usize,
+    /// Number of signatures the message requires
+    pub required_signatures: usize,
+    /// Whether the transaction references one of the given tip accounts
+    pub references_tip_account: bool,
+    /// Compute unit limit set with a compute budget instruction, if any
+    pub compute_unit_limit: Option<u32>,
+    /// Compute unit price in micro-lamports set with a compute budget instruction, if any
+    pub compute_unit_price: Option<u64>,
+}
+
+impl BundleReport {
+    /// Inspects the transactions, looking for references to any of `tip_accounts`, such as those returned by `JitoClient::get_tip_accounts`.
+    ///
+    /// # Errors
+    /// This function will return an error if a transaction cannot be serialized.
+    pub fn new(txns: &[VersionedTransaction], tip_accounts: &[Pubkey]) -> JitoClientResult<Self> {
+        let mut transactions = Vec::with_capacity(txns.len());
+        for txn in txns {
+            transactions.push(TransactionReport::new(txn, tip_accounts)?);
+        }
+        Ok(Self {
+            total_size: transactions.iter().map(|txn| txn.size).sum(),
+            wire_size: Bundle::estimated_wire_size(txns)?,
+            transactions,
+        })
+    }
+
+    /// Returns whether any transaction references a tip account. Bundles without a tip are unlikely to win an auction.
+    pub fn has_tip(&self) -> bool {
+        self.transactions
+            .iter()
+            .any(|txn| txn.references_tip_account)
+    }
+}
+
+impl TransactionReport {
+    fn new(txn: &VersionedTransaction, tip_accounts: &[Pubkey]) -> JitoClientResult<Self> {
+        let budget = ComputeBudget::from_transaction(txn);
+        Ok(Self {
+            size: bincode::serialized_size(txn)? as usize,
+            signatures: txn.signatures.len(),
+            required_signatures: txn.message.header().num_required_signatures as usize,
+            references_tip_account: txn
+                .message
+                .static_account_keys()
+                .iter()
+                .any(|key| tip_accounts.contains(key)),
+            compute_unit_limit: budget.unit_limit,
+            compute_unit_price: budget.unit_price,
+        })
+    }
+
+    /// Returns whether the transaction fits into a single packet, see `Bundle::fits_in_bundle`.
+    pub fn fits_in_packet(&self) -> bool {
+        self.size <= PACKET_DATA_SIZE
+    }
+}
+
+impl Display for BundleReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} transactions, {} bytes ({} on the wire), {}",
+            self.transactions.len(),
+            self.total_size,
+            self.wire_size,
+            if self.has_tip() { "tipped" } else { "no tip" }
+        )?;
+        for (index, txn) in self.transactions.iter().enumerate() {
+            write!(f, "\n  #{index}: {txn}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TransactionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes{}, {}/{} signatures",
+            self.size,
+            if self.fits_in_packet() {
+                ""
+            } else {
+                " (oversized)"
+            },
+            self.signatures,
+            self.required_signatures
+        )?;
+        if let Some(limit) = self.compute_unit_limit {
+            write!(f, ", CU limit {limit}")?;
+        }
+        if let Some(price) = self.compute_unit_price {
+            write!(f, ", CU price {price} micro-lamports")?;
+        }
+        if self.references_tip_account {
+            write!(f, ", tip")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_budget::set_compute_unit_limit;
+    use solana_keypair::{Keypair, Signer};
+    use solana_system_interface::instruction::transfer;
+    use solana_transaction::{Message, VersionedMessage};
+
+    #[test]
+    fn report_detects_tips_and_compute_budget() {
+        let payer = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let instructions = [
+            set_compute_unit_limit(200_000),
+            transfer(&payer.pubkey(), &tip_account, 1_000),
+        ];
+        let message = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer.pubkey())));
+        let tipped = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+        let message = VersionedMessage::Legacy(Message::new(&[], Some(&payer.pubkey())));
+        let empty = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+
+        let report = BundleReport::new(&[empty, tipped], &[tip_account]).unwrap();
+        assert!(report.has_tip());
+        assert!(!report.transactions[0].references_tip_account);
+        assert_eq!(report.transactions[1].compute_unit_limit, Some(200_000));
+        assert_eq!(report.transactions[1].compute_unit_price, None);
+        assert_eq!(report.transactions[1].signatures, 1);
+        assert_eq!(
+            report.total_size,
+            report.transactions[0].size + report.transactions[1].size
+        );
+        assert!(report.to_string().contains("CU limit 200000, tip"));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::io;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Host name to the time it was resolved and its addresses
+type LookupCache = HashMap<String, (Instant, Vec<IpAddr>)>;
+
+/// Looks up the IP addresses of a host name.
+///
+/// Implement this to plug a custom DNS client into `Resolver`.
+pub trait Resolve: Send + Sync {
+    fn resolve<'a>(&'a self, host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>>;
+}
+
+/// The operating system's resolver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve<'a>(&'a self, host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host, 0)).await?;
+            Ok(addrs.map(|addr| addr.ip()).collect())
+        })
+    }
+}
+
+/// A resolver backed by hickory-dns, configured from the system's resolver configuration.
+#[cfg(feature = "hickory-dns")]
+pub struct HickoryResolver(hickory_resolver::TokioResolver);
+
+#[cfg(feature = "hickory-dns")]
+impl HickoryResolver {
+    /// Creates a resolver using the name servers from the system's resolver configuration.
+    ///
+    /// # Errors
+    /// This function will return an error if the system configuration cannot be read.
+    pub fn new() -> JitoClientResult<Self> {
+        let builder = hickory_resolver::TokioResolver::builder_tokio()
+            .map_err(|e| JitoClientError::ConfigError(format!("hickory-dns: {e}")))?;
+        Ok(Self(builder.build()))
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl Resolve for HickoryResolver {
+    fn resolve<'a>(&'a self, host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+        Box::pin(async move {
+            let lookup = self.0.lookup_ip(host).await.map_err(io::Error::other)?;
+            Ok(lookup.iter().collect())
+        })
+    }
+}
+
+/// How block engine host names are resolved, both for the gRPC connection and for region latency measurements.
+///
+/// Hosts can be pinned to fixed addresses, and lookups can be cached so DNS variance doesn't add jitter to reconnects and latency measurements.
+/// Uses the operating system's resolver by default.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, resolver::Resolver};
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let resolver = Resolver::default()
+///     .with_cache(Duration::from_secs(300))
+///     .pin("ny.mainnet.block-engine.jito.wtf", ["127.0.0.1".parse().unwrap()]);
+///
+/// let client = JitoClient::builder().resolver(resolver).build().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Resolver {
+    inner: Arc<dyn Resolve>,
+    pinned: HashMap<String, Vec<IpAddr>>,
+    cache_ttl: Option<Duration>,
+    cache: Arc<Mutex<LookupCache>>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new(SystemResolver)
+    }
+}
+
+impl std::fmt::Debug for Resolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resolver")
+            .field("pinned", &self.pinned)
+            .field("cache_ttl", &self.cache_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Resolver {
+    /// Creates a resolver that looks hosts up with `resolve`, without caching.
+    pub fn new(resolve: impl Resolve + 'static) -> Self {
+        Self {
+            inner: Arc::new(resolve),
+            pinned: HashMap::new(),
+            cache_ttl: None,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Caches successful lookups for `ttl`.
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Always resolves `host` to the given addresses, without a lookup.
+    pub fn pin(mut self, host: impl Into<String>, addrs: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.pinned.insert(host.into(), addrs.into_iter().collect());
+        self
+    }
+
+    /// Resolves `host` to its IP addresses.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The lookup fails
+    /// - The lookup returns no addresses
+    pub async fn lookup(&self, host: &str) -> JitoClientResult<Vec<IpAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![ip]);
+        }
+        if let Some(addrs) = self.pinned.get(host) {
+            return Ok(addrs.clone());
+        }
+        if let Some(ttl) = self.cache_ttl {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((resolved, addrs)) = cache.get(host)
+                && resolved.elapsed() < ttl
+            {
+                return Ok(addrs.clone());
+            }
+        }
+
+        let addrs =
+            self.inner
+                .resolve(host)
+                .await
+                .map_err(|source| JitoClientError::DNSResolution {
+                    region: None,
+                    host: host.to_string(),
+                    source,
+                })?;
+        if addrs.is_empty() {
+            return Err(JitoClientError::DNSEmpty {
+                region: None,
+                host: host.to_string(),
+            });
+        }
+        if self.cache_ttl.is_some() {
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache.insert(host.to_string(), (Instant::now(), addrs.clone()));
+        }
+        Ok(addrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingResolver(Arc<AtomicUsize>);
+
+    impl Resolve for CountingResolver {
+        fn resolve<'a>(&'a self, _host: &'a str) -> BoxFuture<'a, io::Result<Vec<IpAddr>>> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async { Ok(vec![IpAddr::from([10, 0, 0, 1])]) })
+        }
+    }
+
+    #[tokio::test]
+    async fn pinned_and_cached_lookups() {
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let pinned = IpAddr::from([10, 0, 0, 2]);
+        let resolver = Resolver::new(CountingResolver(lookups.clone()))
+            .with_cache(Duration::from_secs(60))
+            .pin("pinned.example", [pinned]);
+
+        assert_eq!(resolver.lookup("pinned.example").await.unwrap(), [pinned]);
+        assert_eq!(lookups.load(Ordering::Relaxed), 0);
+
+        resolver.lookup("cached.example").await.unwrap();
+        resolver.lookup("cached.example").await.unwrap();
+        assert_eq!(lookups.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,362 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::random;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+// Shortest timeout an attempt is given out of a deadline's remaining budget, unless less remains
+#[cfg(any(feature = "native", test))]
+const MIN_ATTEMPT_BUDGET: Duration = Duration::from_millis(50);
+
+/// How the wait between retries grows with each failed attempt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Backoff {
+    /// A random wait between the wait bounds after every attempt
+    #[default]
+    Constant,
+    /// A random wait between the minimum wait doubled once and twice per failed attempt before, capped at the maximum wait.
+    /// Backs off quickly from an overloaded block engine while the first retry stays fast
+    Exponential,
+}
+
+impl Display for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Backoff::Constant => write!(f, "constant"),
+            Backoff::Exponential => write!(f, "exponential"),
+        }
+    }
+}
+
+/// How `send_with_retry` retries failed sends: making up to `max_retries` attempts in total, the first included, and waiting a random time
+/// between `min_wait` and `max_wait` milliseconds in between.
+///
+/// A `max_retries` of 0 or 1 makes a single attempt. A policy created with `RetryLogic::unlimited_until_deadline` instead retries for as long as the next attempt starts before the deadline.
+/// Once no retry is left, the send fails with `MaxRetriesError`.
+///
+/// Defaults to 3 retries with the default wait bounds and constant backoff. Build a validated policy with `RetryLogic::builder`.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::{client::{Backoff, RetryLogic}, errors::JitoClientResult};
+/// # fn example() -> JitoClientResult<()> {
+/// let retry_logic = RetryLogic::builder()
+///     .max_retries(5)
+///     .bounds_ms(10, 50)
+///     .backoff(Backoff::Exponential)
+///     .build()?;
+/// assert_eq!(retry_logic.to_string(), "5 retries, waiting 10-50 ms with exponential backoff");
+/// # Ok(())
+/// # }
+/// # example().unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RetryLogicRepr")
+)]
+pub struct RetryLogic {
+    pub max_retries: u8,
+    pub min_wait: u64,
+    pub max_wait: u64,
+    pub backoff: Backoff,
+    /// Retries without limit until this instant, ignoring `max_retries`
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub deadline: Option<Instant>,
+    /// Share of the time left before a deadline an attempt may take, as a divisor: 2 times an attempt out after half the remaining time,
+    /// leaving room for a retry, and 1 lets one attempt take all of it. The final attempt always gets all of it.
+    ///
+    /// Applies to the `deadline` and to the expiry of `JitoClient::send_with_retry_expiring`. Defaults to 2, and 0 is treated as 1.
+    pub budget_divisor: u32,
+}
+
+#[cfg(feature = "serde")]
+// Fields of a deserialized `RetryLogic`, whose wait bounds are validated like `RetryLogicBuilder::build` before use
+#[derive(serde::Deserialize)]
+struct RetryLogicRepr {
+    max_retries: u8,
+    min_wait: u64,
+    max_wait: u64,
+    #[serde(default)]
+    backoff: Backoff,
+    #[serde(default = "default_budget_divisor")]
+    budget_divisor: u32,
+}
+
+#[cfg(feature = "serde")]
+fn default_budget_divisor() -> u32 {
+    RetryLogic::default().budget_divisor
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RetryLogicRepr> for RetryLogic {
+    type Error = JitoClientError;
+
+    fn try_from(repr: RetryLogicRepr) -> JitoClientResult<Self> {
+        RetryLogic::builder()
+            .max_retries(repr.max_retries)
+            .bounds_ms(repr.min_wait, repr.max_wait)
+            .backoff(repr.backoff)
+            .budget_divisor(repr.budget_divisor)
+            .build()
+    }
+}
+
+impl RetryLogic {
+    pub fn new(max_retries: u8) -> Self {
+        Self {
+            max_retries,
+            min_wait: 5,
+            max_wait: 25,
+            backoff: Backoff::Constant,
+            deadline: None,
+            budget_divisor: 2,
+        }
+    }
+
+    /// Retries without limit for as long as the next attempt would start before `deadline`, with the default wait bounds.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # use std::time::{Duration, Instant};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// // Keep retrying for the next 2 seconds
+    /// let retry_logic = RetryLogic::unlimited_until_deadline(Instant::now() + Duration::from_secs(2));
+    /// let uuid = client.send_with_retry(&[], retry_logic).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unlimited_until_deadline(deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..Self::new(0)
+        }
+    }
+
+    /// Returns a builder starting from the default policy.
+    pub fn builder() -> RetryLogicBuilder {
+        RetryLogicBuilder::default()
+    }
+
+    pub fn new_with_wait_bounds(
+        max_retries: u8,
+        min_wait: u64,
+        max_wait: u64,
+    ) -> JitoClientResult<Self> {
+        if min_wait >= max_wait {
+            return Err(JitoClientError::WaitParameterError);
+        }
+        Ok(Self {
+            max_retries,
+            min_wait,
+            max_wait,
+            backoff: Backoff::Constant,
+            deadline: None,
+            budget_divisor: 2,
+        })
+    }
+
+    pub fn jitter(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(random::in_range(self.min_wait..=self.max_wait))
+    }
+
+    /// Returns a random wait after the given failed attempt, starting at 1, according to the backoff.
+    pub fn wait(&self, attempt: u8) -> Duration {
+        match self.backoff {
+            Backoff::Constant => self.jitter(),
+            Backoff::Exponential => {
+                let low = self
+                    .min_wait
+                    .saturating_mul(1 << attempt.saturating_sub(1).min(32))
+                    .min(self.max_wait);
+                let high = low.saturating_mul(2).min(self.max_wait);
+                Duration::from_millis(random::in_range(low..=high))
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "native", test))]
+impl RetryLogic {
+    // Returns the timeout of the given attempt, starting at 1, out of the time left before `deadline`: a share of it, unless no retry could follow
+    pub(crate) fn attempt_budget(&self, deadline: Instant, attempt: u8) -> Duration {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if self.deadline.is_none() && attempt >= self.max_retries {
+            return remaining;
+        }
+        (remaining / self.budget_divisor.max(1)).max(remaining.min(MIN_ATTEMPT_BUDGET))
+    }
+
+    // Returns the wait before retrying after the given failed attempt, starting at 1, or None once no retry is left
+    pub(crate) fn next_wait(&self, attempt: u8) -> Option<Duration> {
+        let wait = self.wait(attempt);
+        match self.deadline {
+            Some(deadline) => (Instant::now() + wait < deadline).then_some(wait),
+            None => (attempt < self.max_retries).then_some(wait),
+        }
+    }
+}
+
+impl Default for RetryLogic {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl Display for RetryLogic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.deadline {
+            Some(deadline) => write!(
+                f,
+                "retries for {:?}",
+                deadline.saturating_duration_since(Instant::now())
+            )?,
+            None => write!(f, "{} retries", self.max_retries)?,
+        }
+        write!(
+            f,
+            ", waiting {}-{} ms with {} backoff",
+            self.min_wait, self.max_wait, self.backoff
+        )
+    }
+}
+
+/// Builds a `RetryLogic`, validating the parameters once they are all set.
+#[derive(Debug, Clone, Default)]
+pub struct RetryLogicBuilder {
+    retry_logic: RetryLogic,
+}
+
+impl RetryLogicBuilder {
+    /// Sets the maximum number of retries.
+    pub fn max_retries(mut self, max_retries: u8) -> Self {
+        self.retry_logic.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the bounds of the random wait between retries, in milliseconds.
+    pub fn bounds_ms(mut self, min_wait: u64, max_wait: u64) -> Self {
+        self.retry_logic.min_wait = min_wait;
+        self.retry_logic.max_wait = max_wait;
+        self
+    }
+
+    /// Sets how the wait grows with each failed attempt.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.retry_logic.backoff = backoff;
+        self
+    }
+
+    /// Sets the share of the time left before a deadline an attempt may take, see `RetryLogic::budget_divisor`.
+    pub fn budget_divisor(mut self, divisor: u32) -> Self {
+        self.retry_logic.budget_divisor = divisor;
+        self
+    }
+
+    /// Validates the parameters and returns the policy.
+    ///
+    /// # Errors
+    /// This function will return a `WaitParameterError` if the minimum wait is not below the maximum wait.
+    pub fn build(self) -> JitoClientResult<RetryLogic> {
+        let RetryLogic {
+            min_wait, max_wait, ..
+        } = self.retry_logic;
+        if min_wait >= max_wait {
+            return Err(JitoClientError::WaitParameterError);
+        }
+        Ok(self.retry_logic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_logic_builder_validates_bounds() {
+        assert!(matches!(
+            RetryLogic::builder().bounds_ms(50, 10).build(),
+            Err(JitoClientError::WaitParameterError)
+        ));
+
+        let retry_logic = RetryLogic::builder()
+            .bounds_ms(10, 100)
+            .backoff(Backoff::Exponential)
+            .build()
+            .unwrap();
+        let within = |attempt, low, high| {
+            let wait = retry_logic.wait(attempt);
+            (Duration::from_millis(low)..=Duration::from_millis(high)).contains(&wait)
+        };
+        assert!(within(1, 10, 20));
+        assert!(within(3, 40, 80));
+        assert!(within(4, 80, 100));
+        assert!(within(200, 100, 100));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_bounds_are_validated() {
+        let retry_logic: RetryLogic =
+            serde_json::from_str(r#"{"max_retries":3,"min_wait":10,"max_wait":50}"#).unwrap();
+        assert_eq!(
+            retry_logic.to_string(),
+            "3 retries, waiting 10-50 ms with constant backoff"
+        );
+
+        let inverted =
+            serde_json::from_str::<RetryLogic>(r#"{"max_retries":3,"min_wait":50,"max_wait":10}"#);
+        assert!(inverted.is_err());
+        let equal =
+            serde_json::from_str::<RetryLogic>(r#"{"max_retries":3,"min_wait":10,"max_wait":10}"#);
+        assert!(equal.is_err());
+    }
+
+    #[test]
+    fn max_retries_counts_every_attempt() {
+        assert!(RetryLogic::new(0).next_wait(1).is_none());
+        assert!(RetryLogic::new(1).next_wait(1).is_none());
+
+        let three = RetryLogic::new(3);
+        assert!(three.next_wait(2).is_some());
+        assert!(three.next_wait(3).is_none());
+
+        let unlimited =
+            RetryLogic::unlimited_until_deadline(Instant::now() + Duration::from_secs(60));
+        assert!(unlimited.next_wait(u8::MAX).is_some());
+        let expired = RetryLogic::unlimited_until_deadline(Instant::now());
+        assert!(expired.next_wait(1).is_none());
+    }
+
+    #[test]
+    fn attempts_take_a_share_of_the_deadline() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let within = |budget: Duration, low: u64, high: u64| {
+            assert!(budget > Duration::from_secs(low) && budget <= Duration::from_secs(high));
+        };
+        let unlimited = RetryLogic::unlimited_until_deadline(deadline);
+        within(unlimited.attempt_budget(deadline, 1), 29, 30);
+        within(unlimited.attempt_budget(deadline, 200), 29, 30);
+
+        // The final attempt has no retry to leave room for
+        let twice = RetryLogic::new(2);
+        within(twice.attempt_budget(deadline, 1), 29, 30);
+        within(twice.attempt_budget(deadline, 2), 59, 60);
+        let whole = RetryLogic {
+            budget_divisor: 0,
+            ..unlimited
+        };
+        within(whole.attempt_budget(deadline, 1), 59, 60);
+
+        let nearly = Instant::now() + Duration::from_millis(60);
+        assert!(unlimited.attempt_budget(nearly, 1) >= Duration::from_millis(45));
+    }
+}
@@ -0,0 +1,397 @@
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::bundle::Bundle;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::{request::RpcRequest, response::Response};
+use solana_transaction::{Hash, Signature, versioned::VersionedTransaction};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time;
+
+/// Thin wrapper over a Solana RPC node, providing the chain state needed by the bundle helpers (recent blockhash and current slot).
+#[derive(Clone)]
+pub struct SolanaRpc {
+    client: Arc<RpcClient>,
+}
+
+impl std::fmt::Debug for SolanaRpc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolanaRpc")
+            .field("url", &self.client.url())
+            .finish()
+    }
+}
+
+impl SolanaRpc {
+    /// Creates a new RPC wrapper for the given URL, using `confirmed` commitment.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_commitment(url, CommitmentConfig::confirmed())
+    }
+
+    /// Creates a new RPC wrapper for the given URL and commitment level.
+    pub fn with_commitment(url: impl Into<String>, commitment: CommitmentConfig) -> Self {
+        Self {
+            client: Arc::new(RpcClient::new_with_commitment(url.into(), commitment)),
+        }
+    }
+
+    /// Creates a wrapper for the JSON-RPC API served by the block engine at `endpoint`, the gRPC endpoint clients connect to.
+    pub fn block_engine(endpoint: &str) -> Self {
+        Self::new(bundles_url(endpoint))
+    }
+
+    /// Returns the underlying `RpcClient`, for calls not covered by this wrapper.
+    pub fn rpc_client(&self) -> &RpcClient {
+        &self.client
+    }
+
+    /// Fetches the latest blockhash.
+    pub async fn latest_blockhash(&self) -> JitoClientResult<Hash> {
+        Ok(self.client.get_latest_blockhash().await?)
+    }
+
+    /// Fetches the current slot.
+    pub async fn current_slot(&self) -> JitoClientResult<u64> {
+        Ok(self.client.get_slot().await?)
+    }
+
+    /// Starts a background task that polls the current slot every `interval`, and returns a watcher holding the latest value.
+    ///
+    /// The first slot is fetched before returning, so the watcher always holds a valid slot. Polling errors are logged and skipped.
+    /// The background task stops when the watcher is dropped.
+    pub async fn watch_slots(&self, interval: Duration) -> JitoClientResult<SlotWatcher> {
+        let (sender, receiver) = watch::channel(self.current_slot().await?);
+        let rpc = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                time::sleep(interval).await;
+                match rpc.current_slot().await {
+                    Ok(slot) => {
+                        if sender.send(slot).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => debug!("Slot poll error: {e}"),
+                }
+            }
+        });
+        Ok(SlotWatcher { receiver, task })
+    }
+
+    /// Simulates a bundle with the `simulateBundle` method, which is only available on Jito-enabled RPC nodes.
+    ///
+    /// Useful as a pre-flight check to drop bundles that would fail before paying tips.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of signed transactions (`VersionedTransaction`) to simulate, in bundle order
+    ///
+    /// # Returns
+    /// Returns the simulation summary along with per-transaction results.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Transaction serialization fails
+    /// - The RPC request fails, or the node does not support `simulateBundle`
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{errors::JitoClientResult, rpc::SolanaRpc};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let rpc = SolanaRpc::new("https://my-jito-rpc.example.com");
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// let simulation = rpc.simulate_bundle(&transactions).await?;
+    /// if !simulation.succeeded() {
+    ///     for (i, result) in simulation.transaction_results.iter().enumerate() {
+    ///         println!("tx {i}: err={:?}", result.err);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn simulate_bundle(
+        &self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<SimulatedBundle> {
+        let mut encoded = Vec::with_capacity(transactions.len());
+        for txn in transactions {
+            encoded.push(STANDARD.encode(bincode::serialize(txn)?));
+        }
+        let no_accounts = vec![Value::Null; transactions.len()];
+        let params = json!([
+            { "encodedTransactions": encoded },
+            {
+                "preExecutionAccountsConfigs": no_accounts,
+                "postExecutionAccountsConfigs": no_accounts,
+            }
+        ]);
+        let response: Response<SimulatedBundle> = self
+            .client
+            .send(
+                RpcRequest::Custom {
+                    method: "simulateBundle",
+                },
+                params,
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    /// Sends a bundle with the `sendBundle` JSON-RPC method, served by block engines at `<endpoint>/api/v1/bundles`.
+    ///
+    /// An alternative path to the block engine for when its gRPC frontend is unavailable, see `SubmitPath::GrpcWithHttpFallback`.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of signed transactions (`VersionedTransaction`) to send, in bundle order
+    ///
+    /// # Returns
+    /// Returns the bundle's uuid.
+    ///
+    /// # Errors
+    /// This function will return an error if transaction serialization or the request fails, or the block engine rejects the bundle.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{errors::JitoClientResult, rpc::SolanaRpc};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let block_engine = SolanaRpc::new("https://ny.mainnet.block-engine.jito.wtf/api/v1/bundles");
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// let uuid = block_engine.send_bundle(&transactions).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_bundle(
+        &self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<String> {
+        let mut encoded = Vec::with_capacity(transactions.len());
+        for txn in transactions {
+            encoded.push(STANDARD.encode(bincode::serialize(txn)?));
+        }
+        self.send_encoded_bundle(encoded).await
+    }
+
+    /// Sends a single transaction with the `sendTransaction` JSON-RPC method, as served by block engines at `<endpoint>/api/v1/transactions`.
+    ///
+    /// Unlike the RPC client's own `send_transaction`, the transaction is sent as is, without preflight or blockhash checks.
+    ///
+    /// # Errors
+    /// This function will return an error if transaction serialization or the request fails, or the node rejects the transaction.
+    pub async fn send_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> JitoClientResult<Signature> {
+        let encoded = STANDARD.encode(bincode::serialize(transaction)?);
+        let params = json!([encoded, { "encoding": "base64" }]);
+        let signature: String = self
+            .client
+            .send(RpcRequest::SendTransaction, params)
+            .await?;
+        Signature::from_str(&signature)
+            .map_err(|_| JitoClientError::MalformedResponse("invalid transaction signature"))
+    }
+
+    /// Looks up landed bundles with the block engine's `getBundleStatuses` JSON-RPC method, served at `<endpoint>/api/v1/bundles`.
+    ///
+    /// Unlike a bundle result subscription, which only streams results from when it is opened, this also finds bundles that landed before.
+    ///
+    /// # Returns
+    /// Returns the status of each bundle in order, or `None` for a bundle that has not landed or is no longer known to the block engine.
+    ///
+    /// # Errors
+    /// This function will return an error if the request fails.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{errors::JitoClientResult, rpc::SolanaRpc};
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let block_engine = SolanaRpc::block_engine("https://ny.mainnet.block-engine.jito.wtf");
+    /// let uuid = "bundle uuid";
+    /// match &block_engine.get_bundle_statuses(&[uuid]).await?[0] {
+    ///     Some(status) => println!("Landed in slot {}", status.slot),
+    ///     None => println!("Not landed"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[&str],
+    ) -> JitoClientResult<Vec<Option<LandedBundle>>> {
+        let response: Response<Vec<Option<LandedBundle>>> = self
+            .client
+            .send(
+                RpcRequest::Custom {
+                    method: "getBundleStatuses",
+                },
+                json!([bundle_ids]),
+            )
+            .await?;
+        Ok(response.value)
+    }
+
+    // Sends the already serialized transactions of a bundle built for gRPC
+    pub(crate) async fn send_packets(&self, bundle: &Bundle) -> JitoClientResult<String> {
+        let encoded = bundle
+            .packets
+            .iter()
+            .map(|packet| STANDARD.encode(&packet.data))
+            .collect();
+        self.send_encoded_bundle(encoded).await
+    }
+
+    async fn send_encoded_bundle(&self, encoded: Vec<String>) -> JitoClientResult<String> {
+        let params = json!([encoded, { "encoding": "base64" }]);
+        Ok(self
+            .client
+            .send(
+                RpcRequest::Custom {
+                    method: "sendBundle",
+                },
+                params,
+            )
+            .await?)
+    }
+}
+
+/// Status of a landed bundle, returned by `SolanaRpc::get_bundle_statuses`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LandedBundle {
+    pub bundle_id: String,
+    /// Signatures of the bundle's transactions
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    /// Commitment level reached: `processed`, `confirmed` or `finalized`
+    pub confirmation_status: Option<String>,
+    /// `{"Ok": null}` if the bundle's transactions succeeded
+    pub err: Value,
+}
+
+// JSON-RPC URL of the block engine serving gRPC at `endpoint`
+pub(crate) fn bundles_url(endpoint: &str) -> String {
+    format!("{}/api/v1/bundles", endpoint.trim_end_matches('/'))
+}
+
+/// Result of a `simulateBundle` call.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBundle {
+    pub summary: SimulationSummary,
+    pub transaction_results: Vec<SimulatedTransaction>,
+}
+
+impl SimulatedBundle {
+    /// Returns true if every transaction in the bundle simulated successfully.
+    pub fn succeeded(&self) -> bool {
+        matches!(self.summary, SimulationSummary::Succeeded)
+    }
+}
+
+/// Overall outcome of a bundle simulation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulationSummary {
+    Succeeded,
+    Failed {
+        error: Value,
+        tx_signature: Option<String>,
+    },
+}
+
+/// Simulation result of a single transaction within a bundle.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransaction {
+    pub err: Option<Value>,
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+}
+
+/// Holds the latest slot observed by a `SolanaRpc::watch_slots` background task.
+pub struct SlotWatcher {
+    receiver: watch::Receiver<u64>,
+    task: JoinHandle<()>,
+}
+
+impl SlotWatcher {
+    /// Returns the latest observed slot.
+    pub fn current(&self) -> u64 {
+        *self.receiver.borrow()
+    }
+
+    /// Waits until a new slot is observed and returns it.
+    pub async fn changed(&mut self) -> u64 {
+        // The sender lives as long as the polling task, which is only stopped by dropping self
+        let _ = self.receiver.changed().await;
+        *self.receiver.borrow_and_update()
+    }
+
+    /// Returns a new receiver of slot updates, for sharing across tasks.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.receiver.clone()
+    }
+}
+
+impl Drop for SlotWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_simulation() {
+        let succeeded: SimulatedBundle = serde_json::from_value(json!({
+            "summary": "succeeded",
+            "transactionResults": [{ "err": null, "logs": ["log"], "unitsConsumed": 150 }]
+        }))
+        .unwrap();
+        assert!(succeeded.succeeded());
+        assert_eq!(succeeded.transaction_results[0].units_consumed, Some(150));
+
+        let failed: SimulatedBundle = serde_json::from_value(json!({
+            "summary": { "failed": { "error": { "TransactionFailure": [[1], "failure"] }, "tx_signature": "sig" } },
+            "transactionResults": []
+        }))
+        .unwrap();
+        assert!(!failed.succeeded());
+    }
+
+    #[test]
+    fn parse_bundle_statuses() {
+        let response: Response<Vec<Option<LandedBundle>>> = serde_json::from_value(json!({
+            "context": { "slot": 242806119 },
+            "value": [
+                {
+                    "bundle_id": "892b79ed49138bfb3aa5441f0df6e06ef34f9ee8f3976c15b323605bae0cf51d",
+                    "transactions": ["3bC2M9fiACSjkTXZDgeNAuQ4ScTsdKGwR42ytFdhUvikqTmBheUxfsR1fDVsM5ADCMMspuwGkdm1uKbU246x5aE3"],
+                    "slot": 242804011,
+                    "confirmation_status": "finalized",
+                    "err": { "Ok": null }
+                },
+                null
+            ]
+        }))
+        .unwrap();
+        let landed = response.value[0].as_ref().unwrap();
+        assert_eq!(landed.slot, 242804011);
+        assert_eq!(landed.confirmation_status.as_deref(), Some("finalized"));
+        assert!(response.value[1].is_none());
+        assert_eq!(
+            bundles_url("https://ny.block-engine.jito.wtf/"),
+            "https://ny.block-engine.jito.wtf/api/v1/bundles"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::random;
+use crate::region::NodeRegion;
+use std::fmt::Debug;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Picks the region a client connects to from the measured latency of every reachable region.
+///
+/// Set with `JitoClientBuilder::region_selection`. Share one policy between builds with an `Arc`, so stateful policies such as `RoundRobin` see every connection.
+pub trait RegionSelectionPolicy: Debug + Send + Sync {
+    /// Returns the region to connect to, or `None` if none of the reachable regions is acceptable.
+    fn select(&self, latencies: &[(NodeRegion, Duration)]) -> Option<NodeRegion>;
+}
+
+/// Selects the region with the lowest latency. The default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Fastest;
+
+impl RegionSelectionPolicy for Fastest {
+    fn select(&self, latencies: &[(NodeRegion, Duration)]) -> Option<NodeRegion> {
+        latencies
+            .iter()
+            .min_by_key(|(_, latency)| *latency)
+            .map(|(region, _)| *region)
+    }
+}
+
+/// Selects a region at random, weighted by the inverse of its latency, spreading clients over regions while still favouring the closer ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedRandom;
+
+impl RegionSelectionPolicy for WeightedRandom {
+    fn select(&self, latencies: &[(NodeRegion, Duration)]) -> Option<NodeRegion> {
+        // A zero latency is counted as a microsecond, so its weight stays finite
+        let weights: Vec<f64> = latencies
+            .iter()
+            .map(|(_, latency)| 1.0 / latency.as_secs_f64().max(1e-6))
+            .collect();
+        let mut pick = random::unit() * weights.iter().sum::<f64>();
+        for ((region, _), weight) in latencies.iter().zip(&weights) {
+            if pick < *weight {
+                return Some(*region);
+            }
+            pick -= weight;
+        }
+        latencies.last().map(|(region, _)| *region)
+    }
+}
+
+/// Keeps selecting the same region as long as it is reachable, and only fails over to the fastest region once it is not.
+///
+/// The region selected on failover becomes the new sticky region.
+#[derive(Debug, Default)]
+pub struct StickyWithFailover {
+    current: Mutex<Option<NodeRegion>>,
+}
+
+impl StickyWithFailover {
+    /// Sticks to whichever region is fastest on the first selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sticks to `region`, such as the one closest to the application's RPC node, while it is reachable.
+    pub fn prefer(region: NodeRegion) -> Self {
+        Self {
+            current: Mutex::new(Some(region)),
+        }
+    }
+}
+
+impl RegionSelectionPolicy for StickyWithFailover {
+    fn select(&self, latencies: &[(NodeRegion, Duration)]) -> Option<NodeRegion> {
+        let mut current = self.current.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(region) = *current
+            && latencies.iter().any(|(reachable, _)| *reachable == region)
+        {
+            return Some(region);
+        }
+        let failover = Fastest.select(latencies)?;
+        if let Some(region) = *current {
+            debug!("Region {region} is unreachable, failing over to {failover}");
+        }
+        *current = Some(failover);
+        Some(failover)
+    }
+}
+
+/// Selects the reachable regions in turn, spreading successive connections evenly over them regardless of latency.
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl RoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RegionSelectionPolicy for RoundRobin {
+    fn select(&self, latencies: &[(NodeRegion, Duration)]) -> Option<NodeRegion> {
+        if latencies.is_empty() {
+            return None;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        Some(latencies[next % latencies.len()].0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latencies() -> Vec<(NodeRegion, Duration)> {
+        vec![
+            (NodeRegion::NY, Duration::from_millis(30)),
+            (NodeRegion::FRA, Duration::from_millis(10)),
+            (NodeRegion::TOK, Duration::from_millis(200)),
+        ]
+    }
+
+    #[test]
+    fn stateless_policies_pick_reachable_regions() {
+        assert_eq!(Fastest.select(&latencies()), Some(NodeRegion::FRA));
+        assert_eq!(Fastest.select(&[]), None);
+
+        let reachable: Vec<_> = latencies().iter().map(|(region, _)| *region).collect();
+        for _ in 0..10 {
+            let selected = WeightedRandom.select(&latencies()).unwrap();
+            assert!(reachable.contains(&selected));
+        }
+        assert_eq!(WeightedRandom.select(&[]), None);
+    }
+
+    #[test]
+    fn sticky_region_fails_over_once_unreachable() {
+        let policy = StickyWithFailover::prefer(NodeRegion::TOK);
+        assert_eq!(policy.select(&latencies()), Some(NodeRegion::TOK));
+
+        let without_tokyo = &latencies()[..2];
+        assert_eq!(policy.select(without_tokyo), Some(NodeRegion::FRA));
+        // Frankfurt stays selected even once Tokyo is back
+        assert_eq!(policy.select(&latencies()), Some(NodeRegion::FRA));
+    }
+
+    #[test]
+    fn round_robin_cycles_regions() {
+        let policy = RoundRobin::new();
+        let selected: Vec<_> = (0..4).filter_map(|_| policy.select(&latencies())).collect();
+        assert_eq!(
+            selected,
+            [
+                NodeRegion::NY,
+                NodeRegion::FRA,
+                NodeRegion::TOK,
+                NodeRegion::NY
+            ]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::client::JitoClient;
+use crate::errors::{JitoClientError, JitoClientResult};
+use solana_transaction::versioned::VersionedTransaction;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::{Notify, oneshot};
+use tokio::task::JoinHandle;
+
+/// Order bundles queued in a `BackgroundSender` are sent in, and what happens when the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueueMode {
+    /// Send in submission order. A bundle submitted to a full queue fails with `SendQueueFull`
+    #[default]
+    Fifo,
+    /// Send in submission order. A bundle submitted to a full queue evicts the oldest queued one, which fails with `Stale`
+    DropOldest,
+    /// Send the most recently submitted bundle first. A bundle submitted to a full queue evicts the oldest queued one, which fails with `Stale`
+    Lifo,
+}
+
+/// Bounded queue between submitters and a `BackgroundSender`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendQueuePolicy {
+    /// Maximum number of queued bundles, not counting the one being sent. A capacity of 0 is treated as 1.
+    pub capacity: usize,
+    pub mode: QueueMode,
+}
+
+impl Default for SendQueuePolicy {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            mode: QueueMode::Fifo,
+        }
+    }
+}
+
+/// Sends bundles from a background task, so submitters never wait on the block engine.
+///
+/// Bundles are sent one at a time through the owned client. For slot-critical sends, `QueueMode::DropOldest` or `QueueMode::Lifo` keeps
+/// only the freshest opportunities when the sender falls behind, discarding stale ones with a `Stale` result rather than delivering them late.
+/// The background task stops when the sender is dropped, failing every queued bundle with `SenderClosed`.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, sender::{BackgroundSender, QueueMode, SendQueuePolicy}};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # async fn example() -> JitoClientResult<()> {
+/// let client = JitoClient::new_dynamic_region(None).await?;
+/// let sender = BackgroundSender::spawn(client, SendQueuePolicy { capacity: 4, mode: QueueMode::Lifo });
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// let pending = sender.submit(transactions);
+/// match pending.result().await {
+///     Ok(uuid) => println!("Bundle ID: {uuid}"),
+///     Err(e) if e.is_stale() => println!("Superseded by a fresher bundle"),
+///     Err(e) => eprintln!("Failed to send: {e}"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BackgroundSender {
+    queue: Arc<SendQueue>,
+    task: JoinHandle<()>,
+}
+
+struct SendQueue {
+    pending: Mutex<VecDeque<Queued>>,
+    policy: SendQueuePolicy,
+    ready: Notify,
+}
+
+struct Queued {
+    transactions: Vec<VersionedTransaction>,
+    reply: oneshot::Sender<JitoClientResult<String>>,
+}
+
+/// A bundle submitted to a `BackgroundSender`, resolving once it is sent or discarded.
+pub struct PendingSend {
+    reply: oneshot::Receiver<JitoClientResult<String>>,
+}
+
+impl BackgroundSender {
+    /// Starts sending through `client` from a background task. Must be called within a tokio runtime.
+    pub fn spawn(mut client: JitoClient, policy: SendQueuePolicy) -> Self {
+        let queue = Arc::new(SendQueue {
+            pending: Mutex::new(VecDeque::new()),
+            policy: SendQueuePolicy {
+                capacity: policy.capacity.max(1),
+                ..policy
+            },
+            ready: Notify::new(),
+        });
+        let worker = queue.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let Some(queued) = worker.next() else {
+                    worker.ready.notified().await;
+                    continue;
+                };
+                // The submitter may have stopped waiting, which doesn't stop the send
+                let _ = queued.reply.send(client.send(&queued.transactions).await);
+            }
+        });
+        Self { queue, task }
+    }
+
+    /// Queues a bundle of transactions for sending.
+    ///
+    /// # Returns
+    /// Returns a `PendingSend` resolving to the bundle's uuid, or to the error it failed or was discarded with.
+    pub fn submit(&self, transactions: Vec<VersionedTransaction>) -> PendingSend {
+        let (reply, receiver) = oneshot::channel();
+        self.queue.push(Queued {
+            transactions,
+            reply,
+        });
+        PendingSend { reply: receiver }
+    }
+
+    /// Returns the number of bundles waiting to be sent.
+    pub fn queued(&self) -> usize {
+        self.queue.lock().len()
+    }
+}
+
+impl Drop for BackgroundSender {
+    fn drop(&mut self) {
+        self.task.abort();
+        // Dropping the replies fails the pending sends
+        self.queue.lock().clear();
+    }
+}
+
+impl SendQueue {
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Queued>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // Queues the bundle, rejecting it or evicting the oldest queued one if the queue is full
+    fn push(&self, queued: Queued) {
+        let mut pending = self.lock();
+        if pending.len() >= self.policy.capacity {
+            match self.policy.mode {
+                QueueMode::Fifo => {
+                    let _ = queued.reply.send(Err(JitoClientError::SendQueueFull));
+                    return;
+                }
+                QueueMode::DropOldest | QueueMode::Lifo => {
+                    if let Some(oldest) = pending.pop_front() {
+                        let _ = oldest.reply.send(Err(JitoClientError::Stale));
+                    }
+                }
+            }
+        }
+        pending.push_back(queued);
+        drop(pending);
+        self.ready.notify_one();
+    }
+
+    fn next(&self) -> Option<Queued> {
+        let mut pending = self.lock();
+        match self.policy.mode {
+            QueueMode::Fifo | QueueMode::DropOldest => pending.pop_front(),
+            QueueMode::Lifo => pending.pop_back(),
+        }
+    }
+}
+
+impl PendingSend {
+    /// Waits until the bundle is sent or discarded.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The send fails
+    /// - The bundle was discarded for a fresher one (`Stale`), or rejected by a full queue (`SendQueueFull`)
+    /// - The sender was dropped before the bundle was sent (`SenderClosed`)
+    pub async fn result(self) -> JitoClientResult<String> {
+        self.reply
+            .await
+            .unwrap_or(Err(JitoClientError::SenderClosed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(mode: QueueMode) -> SendQueue {
+        SendQueue {
+            pending: Mutex::new(VecDeque::new()),
+            policy: SendQueuePolicy { capacity: 2, mode },
+            ready: Notify::new(),
+        }
+    }
+
+    // Queues a bundle of `n` default transactions, to tell bundles apart by size
+    fn push(queue: &SendQueue, n: usize) -> PendingSend {
+        let (reply, receiver) = oneshot::channel();
+        queue.push(Queued {
+            transactions: vec![VersionedTransaction::default(); n],
+            reply,
+        });
+        PendingSend { reply: receiver }
+    }
+
+    #[tokio::test]
+    async fn full_queue_discards_stale_bundles() {
+        let lifo = queue(QueueMode::Lifo);
+        let oldest = push(&lifo, 1);
+        push(&lifo, 2);
+        push(&lifo, 3);
+        assert!(matches!(oldest.result().await, Err(JitoClientError::Stale)));
+        assert_eq!(lifo.next().map(|q| q.transactions.len()), Some(3));
+        assert_eq!(lifo.next().map(|q| q.transactions.len()), Some(2));
+
+        let fifo = queue(QueueMode::Fifo);
+        push(&fifo, 1);
+        push(&fifo, 2);
+        let rejected = push(&fifo, 3);
+        assert!(matches!(
+            rejected.result().await,
+            Err(JitoClientError::SendQueueFull)
+        ));
+        assert_eq!(fifo.next().map(|q| q.transactions.len()), Some(1));
+    }
+}
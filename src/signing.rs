@@ -0,0 +1,240 @@
+use crate::grpc::searcher::searcher_service_client::SearcherServiceClient;
+use bytes::Bytes;
+use prost::Message;
+use std::fmt;
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::Channel;
+use tonic::{GrpcMethod, IntoRequest, Request, Status};
+
+// Searcher client signing every request, used by `JitoClient` and the helpers sharing its connection
+pub(crate) type SearcherClient =
+    SearcherServiceClient<InterceptedService<Channel, SignerInterceptor>>;
+
+/// Hook invoked before every RPC of a client, to sign it for an authenticating proxy in front of the block engine.
+///
+/// Signers mutate the request's metadata, such as attaching an HMAC of the method and payload, or a bearer token. `AuthInterceptor` and
+/// `RotatingAuthInterceptor` are signers attaching the block engine's own auth token. Set signers with `JitoClientBuilder::request_signer`.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, signing::{RequestSigner, SigningRequest}};
+/// # use std::sync::Arc;
+/// # use tonic::{Status, metadata::AsciiMetadataValue};
+/// struct ProxySigner {
+///     key_id: AsciiMetadataValue,
+/// }
+///
+/// impl RequestSigner for ProxySigner {
+///     fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status> {
+///         let digest = request.payload().map_or(0, |payload| payload.iter().map(|&b| b as u64).sum::<u64>());
+///         let signature = format!("{}:{digest}", request.path().unwrap_or_default());
+///         request.metadata_mut().insert("x-key-id", self.key_id.clone());
+///         request.metadata_mut().insert("x-signature", signature.parse().map_err(|_| Status::internal("bad signature"))?);
+///         Ok(())
+///     }
+///
+///     fn signs_payload(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// # async fn example() -> JitoClientResult<()> {
+/// let client = JitoClient::builder()
+///     .endpoint("https://proxy.internal:443")
+///     .request_signer(Arc::new(ProxySigner { key_id: AsciiMetadataValue::from_static("searcher-1") }))
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait RequestSigner: Send + Sync {
+    /// Signs the request before it is sent.
+    ///
+    /// # Errors
+    /// An error fails the request with the returned status, without sending it.
+    fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status>;
+
+    /// Returns whether `sign` reads the request's payload. Defaults to false.
+    ///
+    /// The payload is only encoded for signers asking for it, as it costs an extra encoding of every bundle sent.
+    fn signs_payload(&self) -> bool {
+        false
+    }
+}
+
+/// A request about to be sent, as seen by a `RequestSigner`.
+pub struct SigningRequest<'a> {
+    request: &'a mut Request<()>,
+}
+
+impl SigningRequest<'_> {
+    /// Returns the fully qualified gRPC service, such as `searcher.SearcherService`.
+    pub fn service(&self) -> Option<&str> {
+        self.method_extension().map(|method| method.service())
+    }
+
+    /// Returns the gRPC method name, such as `SendBundle`.
+    pub fn method(&self) -> Option<&str> {
+        self.method_extension().map(|method| method.method())
+    }
+
+    /// Returns the request path, such as `/searcher.SearcherService/SendBundle`.
+    pub fn path(&self) -> Option<String> {
+        self.method_extension()
+            .map(|method| format!("/{}/{}", method.service(), method.method()))
+    }
+
+    /// Returns the encoded request message, if a signer of the client asked for it with `signs_payload`.
+    ///
+    /// Set on the unary requests of `JitoClient`. Result subscriptions and `TipAccountProvider` send empty messages and report `None`.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.request
+            .extensions()
+            .get::<Payload>()
+            .map(|payload| payload.0.as_ref())
+    }
+
+    pub fn metadata(&self) -> &MetadataMap {
+        self.request.metadata()
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut MetadataMap {
+        self.request.metadata_mut()
+    }
+
+    fn method_extension(&self) -> Option<&GrpcMethod<'static>> {
+        self.request.extensions().get::<GrpcMethod<'static>>()
+    }
+}
+
+// Encoded request message, carried to the interceptor in the request's extensions as interceptors don't see the message
+#[derive(Clone)]
+struct Payload(Bytes);
+
+/// Interceptor running a chain of `RequestSigner`s in order, for use with the generated gRPC clients.
+#[derive(Clone, Default)]
+pub struct SignerInterceptor {
+    signers: Arc<[Arc<dyn RequestSigner>]>,
+}
+
+impl SignerInterceptor {
+    pub fn new(signers: Vec<Arc<dyn RequestSigner>>) -> Self {
+        Self {
+            signers: signers.into(),
+        }
+    }
+
+    /// Returns an interceptor running `signer` after the current signers.
+    pub fn then(&self, signer: Arc<dyn RequestSigner>) -> Self {
+        Self {
+            signers: self.signers.iter().cloned().chain([signer]).collect(),
+        }
+    }
+
+    /// Returns whether any signer reads the request's payload.
+    pub fn signs_payload(&self) -> bool {
+        self.signers.iter().any(|signer| signer.signs_payload())
+    }
+
+    // Wraps the message into a request, carrying its encoding if a signer reads the payload
+    pub(crate) fn request<T: Message>(&self, request: impl IntoRequest<T>) -> Request<T> {
+        let mut request = request.into_request();
+        if self.signs_payload() {
+            let payload = Payload(request.get_ref().encode_to_vec().into());
+            request.extensions_mut().insert(payload);
+        }
+        request
+    }
+
+    pub(crate) fn client(&self, channel: Channel) -> SearcherClient {
+        SearcherServiceClient::with_interceptor(channel, self.clone())
+    }
+}
+
+impl Interceptor for SignerInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        for signer in self.signers.iter() {
+            signer.sign(&mut SigningRequest {
+                request: &mut request,
+            })?;
+        }
+        // The payload is only needed by the signers
+        request.extensions_mut().remove::<Payload>();
+        Ok(request)
+    }
+}
+
+impl fmt::Debug for SignerInterceptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignerInterceptor")
+            .field("signers", &self.signers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::metadata::AsciiMetadataValue;
+
+    struct Tagger(&'static str);
+
+    impl RequestSigner for Tagger {
+        fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status> {
+            let value = format!(
+                "{}:{}:{}",
+                self.0,
+                request.path().unwrap_or_default(),
+                request.payload().map_or(0, <[u8]>::len)
+            );
+            request
+                .metadata_mut()
+                .append("x-signed", AsciiMetadataValue::try_from(value).unwrap());
+            Ok(())
+        }
+
+        fn signs_payload(&self) -> bool {
+            self.0 == "payload"
+        }
+    }
+
+    #[test]
+    fn signers_run_in_order_and_see_the_payload() {
+        let plain = SignerInterceptor::new(vec![Arc::new(Tagger("plain"))]);
+        let mut request = plain.request(crate::grpc::searcher::SendBundleResponse {
+            uuid: "abc".to_string(),
+        });
+        assert!(request.extensions().get::<Payload>().is_none());
+
+        let mut chain =
+            SignerInterceptor::new(vec![Arc::new(Tagger("payload")), Arc::new(Tagger("plain"))]);
+        request = chain.request(request.into_inner());
+        request
+            .extensions_mut()
+            .insert(GrpcMethod::new("searcher.SearcherService", "SendBundle"));
+        let (metadata, extensions, ()) = chain
+            .call(Request::from_parts(
+                request.metadata().clone(),
+                request.extensions().clone(),
+                (),
+            ))
+            .unwrap()
+            .into_parts();
+        let signed: Vec<_> = metadata
+            .get_all("x-signed")
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            signed,
+            [
+                "payload:/searcher.SearcherService/SendBundle:5",
+                "plain:/searcher.SearcherService/SendBundle:5"
+            ]
+        );
+        assert!(extensions.get::<Payload>().is_none());
+    }
+}
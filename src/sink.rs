@@ -0,0 +1,160 @@
+use crate::errors::JitoClientError;
+use crate::grpc::bundle::BundleResult;
+use futures::FutureExt;
+use futures::future::{self, BoxFuture};
+use solana_transaction::versioned::VersionedTransaction;
+use std::time::{Duration, SystemTime};
+#[cfg(any(feature = "kafka", feature = "nats"))]
+use {
+    std::time::UNIX_EPOCH,
+    tokio::sync::mpsc::{self, error::TrySendError},
+};
+
+// Records queued by the `kafka` and `nats` sinks beyond this many are dropped, so a broker outage can't exhaust memory
+#[cfg(any(feature = "kafka", feature = "nats"))]
+const OUTBOX_CAPACITY: usize = 4096;
+
+/// Receiver of a copy of every bundle submission and result, to persist searcher activity to a database or message bus.
+///
+/// The "drop copy" of trading infrastructure: set with `JitoClient::with_submission_sink`, the client hands every send to `on_submitted`
+/// once its outcome is known, and the client's tracker hands every bundle result to `on_result`. Sends and waiters don't depend on the
+/// sink, but the caller of a send awaits `on_submitted` before the send returns, so slow sinks should queue records and write them from
+/// another task.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, grpc::bundle::BundleResult, sink::{Submission, SubmissionSink}};
+/// # use futures::{FutureExt, future::BoxFuture};
+/// # use std::sync::Arc;
+/// struct LogSink;
+///
+/// impl SubmissionSink for LogSink {
+///     fn on_submitted<'a>(&'a self, submission: &'a Submission<'a>) -> BoxFuture<'a, ()> {
+///         async move {
+///             match submission.result {
+///                 Ok(uuid) => println!("{} sent {uuid} in {:?}", submission.endpoint, submission.elapsed),
+///                 Err(e) => println!("{} failed: {e}", submission.endpoint),
+///             }
+///         }
+///         .boxed()
+///     }
+///
+///     fn on_result<'a>(&'a self, result: &'a BundleResult) -> BoxFuture<'a, ()> {
+///         async move { println!("{}: {:?}", result.bundle_id, result.outcome()) }.boxed()
+///     }
+/// }
+///
+/// # async fn example() -> JitoClientResult<()> {
+/// let client = JitoClient::new_dynamic_region(None)
+///     .await?
+///     .with_submission_sink(Arc::new(LogSink));
+/// # Ok(())
+/// # }
+/// ```
+pub trait SubmissionSink: Send + Sync {
+    /// Receives a bundle sent by the client, once the send succeeded or failed.
+    fn on_submitted<'a>(&'a self, submission: &'a Submission<'a>) -> BoxFuture<'a, ()>;
+
+    /// Receives a bundle result streamed to the client's tracker. Ignores results by default.
+    fn on_result<'a>(&'a self, result: &'a BundleResult) -> BoxFuture<'a, ()> {
+        let _ = result;
+        future::ready(()).boxed()
+    }
+}
+
+/// A bundle sent by a client, as handed to a `SubmissionSink`.
+#[derive(Debug)]
+pub struct Submission<'a> {
+    /// Endpoint of the block engine the bundle was sent to
+    pub endpoint: &'static str,
+    pub transactions: &'a [VersionedTransaction],
+    /// When the send started
+    pub submitted_at: SystemTime,
+    /// Time the send took, including retries
+    pub elapsed: Duration,
+    /// The bundle uuid, or the error the send failed with
+    pub result: Result<&'a str, &'a JitoClientError>,
+}
+
+// A record to publish, with the topic or subject it goes to
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub(crate) struct Record {
+    pub(crate) topic: String,
+    pub(crate) payload: String,
+}
+
+// Queue between the sink callbacks and the task publishing to the broker, so sends never wait on the broker.
+// Once the sink is dropped the task publishes what is left in the queue and stops.
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub(crate) struct Outbox {
+    records: mpsc::Sender<Record>,
+}
+
+#[cfg(any(feature = "kafka", feature = "nats"))]
+impl Outbox {
+    // Spawns `publish` with the receiving end of the queue. Must be called within a tokio runtime
+    pub(crate) fn spawn<F, Fut>(publish: F) -> Self
+    where
+        F: FnOnce(mpsc::Receiver<Record>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (records, receiver) = mpsc::channel(OUTBOX_CAPACITY);
+        tokio::spawn(publish(receiver));
+        Self { records }
+    }
+
+    pub(crate) fn push(&self, topic: &str, payload: String) {
+        let record = Record {
+            topic: topic.to_string(),
+            payload,
+        };
+        match self.records.try_send(record) {
+            Ok(()) => {}
+            Err(TrySendError::Full(record)) => {
+                debug!("Publish queue full, dropping record for {}", record.topic)
+            }
+            Err(TrySendError::Closed(record)) => {
+                debug!("Publisher stopped, dropping record for {}", record.topic)
+            }
+        }
+    }
+}
+
+// JSON record of a submission, as published by the `kafka` and `nats` sinks
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub(crate) fn submission_json(submission: &Submission<'_>) -> String {
+    let signatures: Vec<String> = submission
+        .transactions
+        .iter()
+        .filter_map(|txn| txn.signatures.first().map(|s| s.to_string()))
+        .collect();
+    serde_json::json!({
+        "event": "submission",
+        "endpoint": submission.endpoint,
+        "submitted_at_ms": unix_millis(submission.submitted_at),
+        "elapsed_us": submission.elapsed.as_micros() as u64,
+        "signatures": signatures,
+        "uuid": submission.result.ok(),
+        "error": submission.result.err().map(ToString::to_string),
+    })
+    .to_string()
+}
+
+// JSON record of a bundle result, as published by the `kafka` and `nats` sinks
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub(crate) fn result_json(result: &BundleResult) -> String {
+    serde_json::json!({
+        "event": "result",
+        "bundle_id": result.bundle_id,
+        "received_at_ms": unix_millis(SystemTime::now()),
+        "outcome": result.outcome().ok().map(|outcome| outcome.to_string()),
+    })
+    .to_string()
+}
+
+#[cfg(any(feature = "kafka", feature = "nats"))]
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,675 @@
+use crate::api::{BundleOutcome, TipAccounts};
+use crate::grpc::bundle::BundleResult;
+use crate::health::ChannelState;
+use crate::region::NodeRegion;
+use crate::tips::{known_tip_accounts, tip_lamports};
+use solana_transaction::versioned::VersionedTransaction;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+// Weight of the newest sample in the moving average
+const EMA_WEIGHT: f64 = 0.2;
+// Number of most recent round trips kept per endpoint to compute percentiles from
+const RECENT_SAMPLES: usize = 256;
+// Sent bundles awaiting their outcome beyond this many are forgotten, so bundles that never get a result can't exhaust memory
+const MAX_PENDING: usize = 65_536;
+// Final results of bundles not yet recorded as sent are kept this long, since a result can arrive before the send returns its uuid
+const EARLY_RESULT_TTL: Duration = Duration::from_secs(60);
+
+/// Default length of the sliding window `BundleStats` aggregates over.
+pub const DEFAULT_STATS_WINDOW: Duration = Duration::from_secs(600);
+
+/// Send latency statistics of one block engine endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RegionStats {
+    /// Exponential moving average of the round trip of successful sends
+    pub ema: Duration,
+    /// Round trip of the most recent successful send
+    pub last: Duration,
+    /// Number of successful sends measured
+    pub samples: u64,
+    /// Number of failed sends
+    pub failures: u64,
+    /// When the most recent successful send completed
+    pub last_success: Option<SystemTime>,
+    /// State of the client's channel to the endpoint at its last keep-alive check, if the channel is kept warm with `JitoClientBuilder::warm_regions`
+    pub channel: Option<ChannelState>,
+    /// When the channel was last checked
+    pub channel_checked: Option<SystemTime>,
+    /// Exponential moving average of the round trip of the channel's successful keep-alive checks
+    pub check_rtt: Option<Duration>,
+}
+
+/// Send latency statistics per endpoint, measured from real bundle sends rather than TCP connects.
+///
+/// Cheap to clone, and shared between every client it is given to with `JitoClientBuilder::latency_stats`.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyStats {
+    endpoints: Arc<Mutex<HashMap<&'static str, EndpointStats>>>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointStats {
+    stats: RegionStats,
+    recent: VecDeque<Duration>,
+}
+
+impl LatencyStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the round trip of a successful send to `endpoint`.
+    pub fn record(&self, endpoint: &'static str, latency: Duration) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let EndpointStats { stats, recent } = endpoints.entry(endpoint).or_default();
+        push_sample(recent, latency);
+        stats.ema = if stats.samples == 0 {
+            latency
+        } else {
+            stats.ema.mul_f64(1.0 - EMA_WEIGHT) + latency.mul_f64(EMA_WEIGHT)
+        };
+        stats.last = latency;
+        stats.samples += 1;
+        stats.last_success = Some(SystemTime::now());
+    }
+
+    /// Records a failed send to `endpoint`.
+    pub fn record_failure(&self, endpoint: &'static str) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints.entry(endpoint).or_default().stats.failures += 1;
+    }
+
+    /// Records a send to `endpoint` that timed out after `elapsed`.
+    ///
+    /// The send counts as failed, and `elapsed` is kept with the round trips percentiles are computed from, as a lower bound of the round trip,
+    /// so an `AdaptiveTimeout` widens when the block engine slows down instead of timing out every send at the old deadline.
+    pub fn record_timeout(&self, endpoint: &'static str, elapsed: Duration) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let EndpointStats { stats, recent } = endpoints.entry(endpoint).or_default();
+        push_sample(recent, elapsed);
+        stats.failures += 1;
+    }
+
+    /// Records the state of a warm channel to `endpoint`, as observed by a keep-alive check, and the round trip of the check if it succeeded.
+    pub fn record_channel(
+        &self,
+        endpoint: &'static str,
+        state: ChannelState,
+        rtt: Option<Duration>,
+    ) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = &mut endpoints.entry(endpoint).or_default().stats;
+        stats.channel = Some(state);
+        stats.channel_checked = Some(SystemTime::now());
+        if let Some(rtt) = rtt {
+            stats.check_rtt = Some(match stats.check_rtt {
+                Some(ema) => ema.mul_f64(1.0 - EMA_WEIGHT) + rtt.mul_f64(EMA_WEIGHT),
+                None => rtt,
+            });
+        }
+    }
+
+    /// Returns the statistics of `endpoint`, if any send to it was recorded or a channel to it is kept warm.
+    pub fn get(&self, endpoint: &str) -> Option<RegionStats> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints.get(endpoint).map(|endpoint| endpoint.stats)
+    }
+
+    /// Returns the round trip `percentile` (between 0 and 1) of the most recent sends to `endpoint`, if at least `min_samples` were recorded.
+    /// Timed out sends count with the time they timed out after.
+    pub fn percentile(
+        &self,
+        endpoint: &str,
+        percentile: f64,
+        min_samples: usize,
+    ) -> Option<Duration> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let recent = &endpoints.get(endpoint)?.recent;
+        if recent.is_empty() || recent.len() < min_samples {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = recent.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank
+        let rank = (percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+
+    /// Returns the statistics of `region`'s endpoint, if any send to it was recorded.
+    pub fn region(&self, region: NodeRegion) -> Option<RegionStats> {
+        self.get(region.endpoint())
+    }
+
+    /// Returns the statistics of every endpoint with a recorded send or a warm channel.
+    pub fn all(&self) -> Vec<(&'static str, RegionStats)> {
+        let endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints.iter().map(|(e, s)| (*e, s.stats)).collect()
+    }
+}
+
+// Keeps the most recent round trips of an endpoint
+fn push_sample(recent: &mut VecDeque<Duration>, latency: Duration) {
+    if recent.len() == RECENT_SAMPLES {
+        recent.pop_front();
+    }
+    recent.push_back(latency);
+}
+
+/// Derives per-request deadlines from the measured round trips of the endpoint, instead of a static timeout.
+///
+/// The deadline is the round trip `percentile` times `multiplier`, clamped to `min..=max`: tight enough to fail fast when the block engine stalls,
+/// loose enough to not time out on normal jitter. It adapts with every send, as the statistics are updated. Sends that time out are recorded
+/// at their deadline, so when latency rises the deadline widens with every timeout until sends complete again.
+/// Until `min_samples` sends were measured, `max` is used. Set with `JitoClient::with_adaptive_timeout`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveTimeout {
+    pub percentile: f64,
+    pub multiplier: f64,
+    pub min: Duration,
+    pub max: Duration,
+    pub min_samples: usize,
+}
+
+impl Default for AdaptiveTimeout {
+    fn default() -> Self {
+        Self {
+            percentile: 0.99,
+            multiplier: 2.0,
+            min: Duration::from_millis(50),
+            max: Duration::from_secs(5),
+            min_samples: 20,
+        }
+    }
+}
+
+impl AdaptiveTimeout {
+    /// Returns the deadline of the next request to `endpoint`.
+    pub fn timeout(&self, stats: &LatencyStats, endpoint: &str) -> Duration {
+        stats
+            .percentile(endpoint, self.percentile, self.min_samples)
+            .map_or(self.max, |rtt| {
+                rtt.mul_f64(self.multiplier)
+                    .clamp(self.min, self.max.max(self.min))
+            })
+    }
+}
+
+/// Outcome counts of the bundles sent to one endpoint, or to every endpoint, within the window of a `BundleStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutcomeCounts {
+    /// Bundles accepted by the block engine
+    pub sent: u64,
+    /// Bundles that reached the processed commitment level
+    pub landed: u64,
+    /// Bundles rejected by the block engine
+    pub rejected: u64,
+    /// Bundles forwarded but never landed
+    pub dropped: u64,
+}
+
+impl OutcomeCounts {
+    /// Returns the share of the bundles with a final outcome that landed, or `None` if there are none.
+    pub fn land_rate(&self) -> Option<f64> {
+        let resolved = self.landed + self.rejected + self.dropped;
+        (resolved > 0).then(|| self.landed as f64 / resolved as f64)
+    }
+}
+
+/// Bundle outcomes aggregated over the window of a `BundleStats`, as returned by `BundleStats::summary`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutcomeSummary {
+    /// Counts over every endpoint
+    pub total: OutcomeCounts,
+    /// Counts per endpoint, such as to compare land rates by region
+    pub endpoints: BTreeMap<&'static str, OutcomeCounts>,
+    /// Average tip of the landed bundles in lamports, paid with system transfers to the tip accounts, see `BundleStats::set_tip_accounts`
+    pub average_tip: Option<u64>,
+    /// Median time from send to landing
+    pub time_to_land_p50: Option<Duration>,
+    /// 90th percentile of the time from send to landing
+    pub time_to_land_p90: Option<Duration>,
+    /// 99th percentile of the time from send to landing
+    pub time_to_land_p99: Option<Duration>,
+}
+
+impl OutcomeSummary {
+    /// Returns the land rate over every endpoint.
+    pub fn land_rate(&self) -> Option<f64> {
+        self.total.land_rate()
+    }
+
+    /// Returns the counts of `region`'s endpoint.
+    pub fn region(&self, region: NodeRegion) -> Option<OutcomeCounts> {
+        self.endpoints.get(region.endpoint()).copied()
+    }
+}
+
+/// Aggregates the outcomes of sent bundles over a sliding window: landed, dropped and rejected counts, land rate per endpoint,
+/// average tip paid and time-to-land percentiles.
+///
+/// Every client records its successful sends in its statistics, and the results streamed to its tracker, returned by `JitoClient::outcome_stats`.
+/// Outcomes are only known for bundles followed by a tracker, such as those sent with `send_and_track`. A bundle counts once, by its first
+/// final result. Cheap to clone, and shared between clients with `JitoClient::with_bundle_stats`, or by sharing a tracker with `with_tracker`.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None).await?;
+/// // ... send and track bundles ...
+///
+/// let summary = client.outcome_stats().summary();
+/// println!("Landed {:?} of bundles, p90 time to land {:?}", summary.land_rate(), summary.time_to_land_p90);
+/// client.outcome_stats().reset();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BundleStats {
+    window: Duration,
+    state: Arc<Mutex<OutcomeState>>,
+    // Accounts whose transfers count as tips, updated whenever the client's tip accounts are fetched
+    tip_accounts: Arc<RwLock<TipAccounts>>,
+}
+
+#[derive(Debug, Default)]
+struct OutcomeState {
+    // Sent bundles awaiting a final result, by uuid
+    pending: HashMap<String, PendingBundle>,
+    // Final results of bundles not yet recorded as sent, by uuid
+    early: HashMap<String, (Instant, Resolution)>,
+    // Sends and final outcomes, oldest first
+    events: VecDeque<OutcomeEvent>,
+}
+
+#[derive(Debug)]
+struct PendingBundle {
+    endpoint: &'static str,
+    sent_at: Instant,
+    tip: u64,
+}
+
+#[derive(Debug)]
+struct OutcomeEvent {
+    at: Instant,
+    endpoint: &'static str,
+    kind: OutcomeKind,
+}
+
+// Final outcome of a bundle
+#[derive(Debug, Clone, Copy)]
+enum Resolution {
+    Landed,
+    Rejected,
+    Dropped,
+}
+
+#[derive(Debug)]
+enum OutcomeKind {
+    Sent,
+    Landed { tip: u64, time_to_land: Duration },
+    Rejected,
+    Dropped,
+}
+
+impl Default for BundleStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATS_WINDOW)
+    }
+}
+
+impl BundleStats {
+    /// Aggregates the outcomes of the bundles sent within the last `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            state: Arc::default(),
+            tip_accounts: Arc::new(RwLock::new(known_tip_accounts())),
+        }
+    }
+
+    /// Returns the length of the sliding window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Sets the accounts whose transfers count as the tip of a sent bundle. Defaults to `known_tip_accounts`, and is updated whenever a client
+    /// sharing these statistics fetches its tip accounts with `get_tip_accounts` or a `TipAccountProvider` it created.
+    pub fn set_tip_accounts(&self, accounts: &TipAccounts) {
+        *self.tip_accounts.write().unwrap_or_else(|e| e.into_inner()) = accounts.clone();
+    }
+
+    /// Records a bundle accepted by `endpoint`, whose result is then awaited.
+    pub fn record_sent(
+        &self,
+        endpoint: &'static str,
+        uuid: &str,
+        transactions: &[VersionedTransaction],
+        sent_at: Instant,
+    ) {
+        let pending = PendingBundle {
+            endpoint,
+            sent_at,
+            tip: tip_lamports(
+                transactions,
+                &self
+                    .tip_accounts
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0,
+            ),
+        };
+        let mut state = self.lock();
+        state.push(endpoint, OutcomeKind::Sent, self.window);
+        if let Some((at, resolution)) = state.early.remove(uuid) {
+            state.resolve(pending, at, resolution, self.window);
+            return;
+        }
+        if state.pending.len() >= MAX_PENDING {
+            let window = self.window;
+            state
+                .pending
+                .retain(|_, pending| pending.sent_at.elapsed() < window);
+        }
+        if state.pending.len() < MAX_PENDING {
+            state.pending.insert(uuid.to_string(), pending);
+        }
+    }
+
+    /// Records a bundle result, counting the bundle if the result is final and the bundle was recorded as sent.
+    pub fn record_result(&self, result: &BundleResult) {
+        let outcome = match result.outcome() {
+            Ok(outcome) => outcome,
+            Err(_) => return,
+        };
+        let resolution = match outcome {
+            BundleOutcome::Accepted { .. } => return,
+            BundleOutcome::Rejected(_) => Resolution::Rejected,
+            BundleOutcome::Dropped(_) => Resolution::Dropped,
+            BundleOutcome::Processed { .. } | BundleOutcome::Finalized => Resolution::Landed,
+        };
+        let now = Instant::now();
+        let mut state = self.lock();
+        match state.pending.remove(&result.bundle_id) {
+            Some(pending) => state.resolve(pending, now, resolution, self.window),
+            None => {
+                if state.early.len() >= MAX_PENDING {
+                    state
+                        .early
+                        .retain(|_, (at, _)| at.elapsed() < EARLY_RESULT_TTL);
+                }
+                if state.early.len() < MAX_PENDING {
+                    state
+                        .early
+                        .entry(result.bundle_id.clone())
+                        .or_insert((now, resolution));
+                }
+            }
+        }
+    }
+
+    /// Returns the outcomes aggregated over the window.
+    pub fn summary(&self) -> OutcomeSummary {
+        let mut state = self.lock();
+        state.expire(self.window);
+        let mut summary = OutcomeSummary::default();
+        let mut tips = Vec::new();
+        let mut times = Vec::new();
+        for event in &state.events {
+            let endpoint = summary.endpoints.entry(event.endpoint).or_default();
+            for counts in [endpoint, &mut summary.total] {
+                match event.kind {
+                    OutcomeKind::Sent => counts.sent += 1,
+                    OutcomeKind::Landed { .. } => counts.landed += 1,
+                    OutcomeKind::Rejected => counts.rejected += 1,
+                    OutcomeKind::Dropped => counts.dropped += 1,
+                }
+            }
+            if let OutcomeKind::Landed { tip, time_to_land } = event.kind {
+                tips.push(tip);
+                times.push(time_to_land);
+            }
+        }
+        if !tips.is_empty() {
+            summary.average_tip = Some(tips.iter().sum::<u64>() / tips.len() as u64);
+        }
+        times.sort_unstable();
+        // Nearest rank
+        let percentile = |p: f64| {
+            let rank = (p * times.len() as f64).ceil() as usize;
+            times.get(rank.saturating_sub(1)).copied()
+        };
+        summary.time_to_land_p50 = percentile(0.5);
+        summary.time_to_land_p90 = percentile(0.9);
+        summary.time_to_land_p99 = percentile(0.99);
+        summary
+    }
+
+    /// Clears every recorded send and outcome.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.pending.clear();
+        state.early.clear();
+        state.events.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, OutcomeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl OutcomeState {
+    // Counts the final outcome of a sent bundle, which is no longer awaited
+    fn resolve(
+        &mut self,
+        pending: PendingBundle,
+        at: Instant,
+        resolution: Resolution,
+        window: Duration,
+    ) {
+        let kind = match resolution {
+            Resolution::Landed => OutcomeKind::Landed {
+                tip: pending.tip,
+                time_to_land: at.saturating_duration_since(pending.sent_at),
+            },
+            Resolution::Rejected => OutcomeKind::Rejected,
+            Resolution::Dropped => OutcomeKind::Dropped,
+        };
+        self.push(pending.endpoint, kind, window);
+    }
+
+    fn push(&mut self, endpoint: &'static str, kind: OutcomeKind, window: Duration) {
+        self.events.push_back(OutcomeEvent {
+            at: Instant::now(),
+            endpoint,
+            kind,
+        });
+        self.expire(window);
+    }
+
+    // Drops the events older than the window
+    fn expire(&mut self, window: Duration) {
+        while self
+            .events
+            .front()
+            .is_some_and(|event| event.at.elapsed() >= window)
+        {
+            self.events.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_average_weights_new_samples() {
+        let stats = LatencyStats::new();
+        let endpoint = NodeRegion::NY.endpoint();
+        stats.record(endpoint, Duration::from_millis(100));
+        stats.record(endpoint, Duration::from_millis(200));
+        stats.record_failure(endpoint);
+
+        let region = stats.region(NodeRegion::NY).unwrap();
+        assert_eq!(region.ema, Duration::from_millis(120));
+        assert_eq!(region.last, Duration::from_millis(200));
+        assert_eq!(region.samples, 2);
+        assert_eq!(region.failures, 1);
+        assert!(region.last_success.is_some());
+        assert!(stats.region(NodeRegion::TOK).is_none());
+    }
+
+    #[test]
+    fn adaptive_timeout_follows_percentile() {
+        let stats = LatencyStats::new();
+        let endpoint = NodeRegion::NY.endpoint();
+        let timeout = AdaptiveTimeout {
+            min_samples: 10,
+            ..Default::default()
+        };
+        assert_eq!(timeout.timeout(&stats, endpoint), timeout.max);
+
+        for millis in 1..=100 {
+            stats.record(endpoint, Duration::from_millis(millis));
+        }
+        assert_eq!(
+            stats.percentile(endpoint, 0.99, 10),
+            Some(Duration::from_millis(99))
+        );
+        assert_eq!(
+            timeout.timeout(&stats, endpoint),
+            Duration::from_millis(198)
+        );
+        stats.record(endpoint, Duration::from_secs(60));
+        assert_eq!(
+            stats.percentile(endpoint, 1.0, 10),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(stats.percentile(endpoint, 0.99, 1000), None);
+    }
+
+    #[test]
+    fn adaptive_timeout_widens_after_timeouts() {
+        let stats = LatencyStats::new();
+        let endpoint = NodeRegion::NY.endpoint();
+        let timeout = AdaptiveTimeout {
+            min: Duration::from_millis(1),
+            min_samples: 10,
+            ..Default::default()
+        };
+        for _ in 0..100 {
+            stats.record(endpoint, Duration::from_millis(10));
+        }
+        assert_eq!(timeout.timeout(&stats, endpoint), Duration::from_millis(20));
+
+        // The block engine slows down to 100ms, every send times out until the deadline catches up
+        let latency = Duration::from_millis(100);
+        let mut timeouts = 0;
+        loop {
+            let deadline = timeout.timeout(&stats, endpoint);
+            if latency <= deadline {
+                break;
+            }
+            stats.record_timeout(endpoint, deadline);
+            timeouts += 1;
+            assert!(timeouts < 20, "deadline stuck at {deadline:?}");
+        }
+        assert_eq!(stats.get(endpoint).unwrap().failures, timeouts);
+        assert_eq!(stats.get(endpoint).unwrap().samples, 100);
+    }
+
+    #[test]
+    fn outcomes_are_aggregated_per_endpoint() {
+        use crate::grpc::bundle::{
+            Dropped, DroppedReason, Finalized, Processed, bundle_result::Result as ResultKind,
+        };
+
+        let stats = BundleStats::default();
+        let result = |bundle_id: &str, result| BundleResult {
+            bundle_id: bundle_id.to_string(),
+            result: Some(result),
+        };
+        let processed = |bundle_id| {
+            result(
+                bundle_id,
+                ResultKind::Processed(Processed {
+                    validator_identity: known_tip_accounts().0[0].to_string(),
+                    slot: 1,
+                    bundle_index: 0,
+                }),
+            )
+        };
+        let (ny, fra) = (NodeRegion::NY.endpoint(), NodeRegion::FRA.endpoint());
+        stats.record_sent(ny, "a", &[], Instant::now() - Duration::from_millis(400));
+        stats.record_sent(ny, "b", &[], Instant::now() - Duration::from_millis(200));
+        stats.record_sent(fra, "c", &[], Instant::now());
+        stats.record_result(&processed("a"));
+        stats.record_result(&result("a", ResultKind::Finalized(Finalized {})));
+        stats.record_result(&processed("b"));
+        stats.record_result(&result(
+            "c",
+            ResultKind::Dropped(Dropped {
+                reason: DroppedReason::BlockhashExpired as i32,
+            }),
+        ));
+        stats.record_result(&processed("unknown"));
+
+        let summary = stats.summary();
+        assert_eq!(
+            summary.total,
+            OutcomeCounts {
+                sent: 3,
+                landed: 2,
+                rejected: 0,
+                dropped: 1,
+            }
+        );
+        assert_eq!(
+            summary.region(NodeRegion::NY).unwrap().land_rate(),
+            Some(1.0)
+        );
+        assert_eq!(
+            summary.region(NodeRegion::FRA).unwrap().land_rate(),
+            Some(0.0)
+        );
+        assert_eq!(summary.average_tip, Some(0));
+        assert!(summary.time_to_land_p50.unwrap() >= Duration::from_millis(200));
+        assert!(summary.time_to_land_p99.unwrap() >= Duration::from_millis(400));
+
+        stats.reset();
+        assert_eq!(stats.summary(), OutcomeSummary::default());
+    }
+
+    #[test]
+    fn tips_are_counted_to_the_set_tip_accounts() {
+        use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+        use solana_pubkey::Pubkey;
+        use solana_transaction::{Message, VersionedMessage};
+
+        let (payer, tip_account) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let transfer = solana_system_interface::instruction::transfer(&payer, &tip_account, 5_000);
+        let tip = VersionedTransaction {
+            signatures: Vec::new(),
+            message: VersionedMessage::Legacy(Message::new(&[transfer], Some(&payer))),
+        };
+        let finalized = |bundle_id: &str| BundleResult {
+            bundle_id: bundle_id.to_string(),
+            result: Some(ResultKind::Finalized(Finalized {})),
+        };
+
+        let stats = BundleStats::default();
+        let endpoint = NodeRegion::NY.endpoint();
+        stats.record_sent(endpoint, "a", std::slice::from_ref(&tip), Instant::now());
+        stats.record_result(&finalized("a"));
+        assert_eq!(stats.summary().average_tip, Some(0));
+
+        stats.reset();
+        stats.set_tip_accounts(&TipAccounts(vec![tip_account]));
+        stats.record_sent(endpoint, "b", &[tip], Instant::now());
+        stats.record_result(&finalized("b"));
+        assert_eq!(stats.summary().average_tip, Some(5_000));
+    }
+}
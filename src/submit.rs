@@ -0,0 +1,1559 @@
+use crate::auth::AuthenticatorPool;
+use crate::bundle::PreparedBundle;
+use crate::client::{JitoClient, RetryLogic};
+use crate::dedup::{BundleDedup, Claim, SignatureGuard};
+use crate::errors::{ErrorContext, JitoClientError, JitoClientResult};
+use crate::expiry::{BundleExpiry, DEFAULT_SLOT_DURATION, SlotDeadline};
+use crate::grpc::{
+    bundle::Bundle,
+    searcher::{SendBundleRequest, SendBundleResponse},
+};
+use crate::journal::BundleJournal;
+#[cfg(feature = "rpc")]
+use crate::rpc::{SolanaRpc, bundles_url};
+use crate::signing::{SearcherClient, SignerInterceptor};
+use crate::sink::{Submission, SubmissionSink};
+use crate::stats::{AdaptiveTimeout, BundleStats, LatencyStats};
+use crate::tracker::TrackedBundle;
+use futures::FutureExt;
+use futures::future::{self, BoxFuture, Either};
+use futures::stream::{FuturesUnordered, StreamExt};
+use solana_transaction::versioned::VersionedTransaction;
+use std::fmt::{self, Display};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{Semaphore, SemaphorePermit, watch};
+use tokio::time::{self, sleep};
+use tonic::IntoRequest;
+use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
+
+// Slots before the leader's slot that a leader-gated send is submitted, so the bundle is in the auction when the slot starts
+const LEADER_LEAD_SLOTS: u32 = 2;
+
+/// A path bundles are submitted to the block engine over, so application code can switch paths, or fan out over several, without changing its call sites.
+///
+/// Implemented by `JitoClient` for gRPC, and with the `rpc` feature by `HttpBundleSubmitter` for JSON-RPC bundles and `HttpTransactionSubmitter` for single transactions.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, submit::{FanoutSubmitter, Submitter}};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// async fn submit_opportunity(submitter: &mut dyn Submitter, transactions: &[VersionedTransaction]) -> JitoClientResult<String> {
+///     submitter.submit(transactions).await
+/// }
+///
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut grpc = JitoClient::new_dynamic_region(None).await?;
+/// let mut fanout = FanoutSubmitter::new(vec![Box::new(JitoClient::new_dynamic_region(None).await?)]);
+///
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// submit_opportunity(&mut grpc, &transactions).await?;
+/// submit_opportunity(&mut fanout, &transactions).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait Submitter: Send {
+    /// Submits the transactions, in bundle order.
+    ///
+    /// # Returns
+    /// Returns the bundle's uuid, or the first transaction's signature for paths submitting transactions one by one.
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>>;
+}
+
+impl Submitter for JitoClient {
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>> {
+        self.send(transactions).boxed()
+    }
+}
+
+impl<S: Submitter + ?Sized> Submitter for Box<S> {
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>> {
+        (**self).submit(transactions)
+    }
+}
+
+/// Submits bundles with the block engine's HTTP JSON-RPC `sendBundle` method.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone)]
+pub struct HttpBundleSubmitter {
+    rpc: SolanaRpc,
+}
+
+#[cfg(feature = "rpc")]
+impl HttpBundleSubmitter {
+    /// Submits to the JSON-RPC API of the block engine at `endpoint`, such as `https://ny.mainnet.block-engine.jito.wtf`.
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            rpc: SolanaRpc::new(format!("{}/api/v1/bundles", endpoint.trim_end_matches('/'))),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Submitter for HttpBundleSubmitter {
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>> {
+        self.rpc.send_bundle(transactions).boxed()
+    }
+}
+
+/// Submits every transaction on its own with the block engine's HTTP JSON-RPC `sendTransaction` method, instead of as a bundle.
+///
+/// Transactions are sent in order, stopping at the first failure. They land independently, without the atomicity of a bundle.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone)]
+pub struct HttpTransactionSubmitter {
+    rpc: SolanaRpc,
+}
+
+#[cfg(feature = "rpc")]
+impl HttpTransactionSubmitter {
+    /// Submits to the transaction endpoint of the block engine at `endpoint`, such as `https://ny.mainnet.block-engine.jito.wtf`.
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            rpc: SolanaRpc::new(format!(
+                "{}/api/v1/transactions",
+                endpoint.trim_end_matches('/')
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Submitter for HttpTransactionSubmitter {
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>> {
+        async move {
+            let mut first = None;
+            for transaction in transactions {
+                let signature = self.rpc.send_transaction(transaction).await?;
+                first.get_or_insert(signature);
+            }
+            Ok(first
+                .map(|signature| signature.to_string())
+                .unwrap_or_default())
+        }
+        .boxed()
+    }
+}
+
+/// Submits every bundle over several paths at once, succeeding as soon as one of them accepts it.
+///
+/// The remaining submissions are dropped once one succeeds. Use `FanoutClient` to await every block engine instead.
+pub struct FanoutSubmitter {
+    submitters: Vec<Box<dyn Submitter>>,
+}
+
+impl FanoutSubmitter {
+    pub fn new(submitters: Vec<Box<dyn Submitter>>) -> Self {
+        Self { submitters }
+    }
+}
+
+impl Submitter for FanoutSubmitter {
+    fn submit<'a>(
+        &'a mut self,
+        transactions: &'a [VersionedTransaction],
+    ) -> BoxFuture<'a, JitoClientResult<String>> {
+        async move {
+            let mut submissions: FuturesUnordered<_> = self
+                .submitters
+                .iter_mut()
+                .map(|submitter| submitter.submit(transactions))
+                .collect();
+            let mut last_error = None;
+            while let Some(result) = submissions.next().await {
+                match result {
+                    Ok(id) => return Ok(id),
+                    Err(e) => {
+                        debug!("Fanout submission error: {e}");
+                        last_error = Some(e);
+                    }
+                }
+            }
+            Err(last_error.unwrap_or_else(|| {
+                JitoClientError::ConfigError("fanout has no submitters".to_string())
+            }))
+        }
+        .boxed()
+    }
+}
+
+impl JitoClient {
+    /// Sends a bundle of transactions to the node via gRPC.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - gRPC connection fails
+    /// - Node server returns an error
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// match client.send(&transactions).await {
+    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///     Err(e) => eprintln!("Failed to send: {}", e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(
+        &mut self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<String> {
+        self.send_with_options(transactions, &RequestOptions::default())
+            .await
+    }
+
+    /// Builds a bundle ahead of sending it with `send_prepared`, with the client's bundle options.
+    ///
+    /// # Errors
+    /// This function will return an error if too many transactions are provided, a transaction cannot be serialized, or the bundle is too large.
+    pub fn prepare(
+        &self,
+        transactions: Vec<VersionedTransaction>,
+    ) -> JitoClientResult<PreparedBundle> {
+        PreparedBundle::with_options(transactions, self.encoder.options())
+    }
+
+    /// Sends a bundle built ahead of time, skipping the serialization of its transactions. See `PreparedBundle`.
+    ///
+    /// The bundle goes through the client's journal, signature guard, deduplication, in-flight limit and submit path like any other send.
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if the gRPC request fails or the block engine rejects the bundle.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// let prepared = client.prepare(transactions)?;
+    /// let uuid = client.send_prepared(&prepared).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_prepared(&mut self, prepared: &PreparedBundle) -> JitoClientResult<String> {
+        let bundle = Ok(prepared.bundle().clone());
+        let (hooks, mut requester) = self.parts();
+        hooks
+            .run(
+                requester.endpoint,
+                prepared.transactions(),
+                bundle,
+                async |request| Ok(requester.send(request).await?.uuid),
+            )
+            .await
+    }
+
+    /// Sends a bundle of transactions and returns a handle to await its result, using the client's shared tracker.
+    ///
+    /// The tracker is subscribed before the bundle is sent, so no result is missed. Handles of many bundles can be awaited concurrently.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    ///
+    /// # Returns
+    /// Returns a `TrackedBundle` holding the bundle uuid.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The bundle result subscription fails
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - The gRPC request fails
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let transactions: Vec<VersionedTransaction> = vec![/* your transactions */];
+    ///
+    /// let bundle = client.send_and_track(&transactions).await?;
+    /// let outcome = bundle.result(Duration::from_secs(5)).await?;
+    /// println!("Bundle {} {outcome}", bundle.uuid());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_and_track(
+        &mut self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<TrackedBundle> {
+        let tracker = self.tracker().await?;
+        let uuid = self.send(transactions).await?;
+        Ok(TrackedBundle::new(uuid, tracker))
+    }
+
+    /// Same as `send`, attaching the metadata of `options` to the request.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RequestOptions}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// let options = RequestOptions::new()
+    ///     .with_metadata("x-correlation-id", "arb-4211")?
+    ///     .with_metadata("x-experiment", "tip-v2")?;
+    ///
+    /// let uuid = client.send_with_options(&transactions, &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_options(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        options: &RequestOptions,
+    ) -> JitoClientResult<String> {
+        self.send_detailed(transactions, options)
+            .await
+            .map(|submission| submission.uuid)
+    }
+
+    /// Same as `send_with_options`, additionally returning the metadata of the block engine's response,
+    /// such as server timing and rate limit counters, to monitor how close the client is to its limits and how long the server took.
+    ///
+    /// # Returns
+    /// Returns a `BundleSubmission` holding the bundle uuid, the decoded response and its metadata. The metadata is empty if the bundle was a suppressed duplicate or sent over an HTTP fallback.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RequestOptions}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// let submission = client.send_detailed(&transactions, &RequestOptions::default()).await?;
+    /// if let Some(rate_limit) = submission.metadata.rate_limit() {
+    ///     println!("{:?} requests left", rate_limit.remaining);
+    /// }
+    /// println!("Bundle {} handled in {:?}", submission.uuid, submission.metadata.server_timing());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_detailed(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        options: &RequestOptions,
+    ) -> JitoClientResult<BundleSubmission> {
+        let bundle = self.encoder.encode(transactions);
+        let (hooks, mut requester) = self.parts();
+        let mut submitted = None;
+        let uuid = hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                let submission = requester.send(options.request(request)).await?;
+                let uuid = submission.uuid.clone();
+                submitted = Some(submission);
+                Ok(uuid)
+            })
+            .await?;
+        Ok(submitted.unwrap_or_else(|| BundleSubmission::from_uuid(uuid)))
+    }
+
+    /// Same as `send`, returning the block engine's full decoded response and its metadata instead of only the bundle uuid,
+    /// so fields added to `SendBundleResponse` by newer proto revisions reach the caller. See `send_detailed`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// let submission = client.send_full(&transactions).await?;
+    /// println!("{:?}", submission.response);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_full(
+        &mut self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<BundleSubmission> {
+        self.send_detailed(transactions, &RequestOptions::default())
+            .await
+    }
+
+    /// Sends a bundle of transactions with automatic retries.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - gRPC connection fails
+    /// - Node server returns an error that isn't retryable, such as an invalid bundle, which is returned without retrying
+    /// - Maximum retry attempts exceeded
+    ///
+    /// # Retry Behavior
+    /// - Only retries errors for which `JitoClientError::is_retryable` holds
+    /// - Uses random jitter between min_wait and max_wait milliseconds
+    /// - Logs debug information for each failed attempt
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// // 3 retries with default timings
+    /// let retry_config = RetryLogic::new(3);
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// match client.send_with_retry(&transactions, retry_config).await {
+    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///     Err(e) => eprintln!("Failed to send: {}", e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_retry(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+    ) -> JitoClientResult<String> {
+        self.send_with_retry_options(transactions, retry_logic, &RequestOptions::default())
+            .await
+    }
+
+    /// Same as `send_with_retry`, attaching the metadata of `options` to every attempt.
+    pub async fn send_with_retry_options(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+        options: &RequestOptions,
+    ) -> JitoClientResult<String> {
+        let bundle = self.encoder.encode(transactions);
+        let (hooks, mut requester) = self.parts();
+        hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                requester
+                    .retry_loop(request, &retry_logic, Some(options), None, None)
+                    .await
+            })
+            .await
+    }
+
+    /// Same as `send_with_retry`, additionally returning a report of every attempt, so how often and why retries fire can be measured.
+    ///
+    /// # Returns
+    /// Returns the send result, along with one `AttemptReport` per attempt made, in order. The reports are empty if the bundle could not be created.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// let (result, attempts) = client.send_with_retry_reported(&transactions, RetryLogic::new(3)).await;
+    /// for attempt in &attempts {
+    ///     if let Some(error) = &attempt.error {
+    ///         println!("Attempt {} failed after {:?}: {}", attempt.attempt, attempt.elapsed, error.message());
+    ///     }
+    /// }
+    /// println!("Bundle ID: {}", result?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_retry_reported(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+    ) -> (JitoClientResult<String>, Vec<AttemptReport>) {
+        let mut reports = Vec::new();
+        let bundle = self.encoder.encode(transactions);
+        let (hooks, mut requester) = self.parts();
+        let result = hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                requester
+                    .retry_loop(request, &retry_logic, None, Some(&mut reports), None)
+                    .await
+            })
+            .await;
+        (result, reports)
+    }
+
+    /// Sends a bundle with automatic retries, building fresh transactions for every attempt.
+    ///
+    /// Resending a byte-identical bundle after it was rejected as stale is futile, so `build` is called before each attempt with the attempt number, starting at 1,
+    /// to produce the transactions to send, e.g. re-signed with a newer blockhash or with an adjusted tip. Every attempt is recorded in the journal separately.
+    ///
+    /// # Arguments
+    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    /// * `build` - Produces the transactions (`VersionedTransaction`) of each attempt
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - `build` returns an error
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - Maximum retry attempts exceeded
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn fetch_blockhash_and_sign(attempt: u8) -> JitoClientResult<Vec<VersionedTransaction>> { Ok(vec![]) }
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let uuid = client
+    ///     .send_with_retry_rebuilt(RetryLogic::new(3), |attempt| fetch_blockhash_and_sign(attempt))
+    ///     .await?;
+    /// println!("Bundle ID: {}", uuid);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_retry_rebuilt<F, Fut>(
+        &mut self,
+        retry_logic: RetryLogic,
+        mut build: F,
+    ) -> JitoClientResult<String>
+    where
+        F: FnMut(u8) -> Fut,
+        Fut: Future<Output = JitoClientResult<Vec<VersionedTransaction>>>,
+    {
+        let mut retries = 0u8;
+        loop {
+            let transactions = build(retries.saturating_add(1)).await?;
+            let bundle = self.encoder.encode(&transactions);
+            let (hooks, mut requester) = self.parts();
+            let result = hooks
+                .run(requester.endpoint, &transactions, bundle, async |request| {
+                    Ok(requester.send(request).await?.uuid)
+                })
+                .await;
+            match result {
+                Err(e) if e.status_code().is_some() => {
+                    debug!("Send error: {e}");
+                    retries = retries.saturating_add(1);
+                    let Some(wait) = retry_logic.next_wait(retries) else {
+                        return Err(JitoClientError::MaxRetriesError.with_context(ErrorContext {
+                            endpoint: Some(self.endpoint),
+                            bundle_size: Some(transactions.len()),
+                            attempt: Some(retries),
+                        }));
+                    };
+                    sleep(wait).await;
+                }
+                // Only rejected sends are retried, a bundle that cannot be created won't succeed on the next attempt either
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends a bundle of transactions with automatic retries, aborting as soon as the `cancel` future completes.
+    ///
+    /// Useful when an opportunity has a limited lifetime (e.g. the slot moved on), so the retry loop stops instead of spending rate limit on a stale bundle.
+    /// Any future can be used as the cancellation signal, such as `CancellationToken::cancelled()` from `tokio_util` or a timer.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    /// * `cancel` - Future that aborts the send when it completes
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - Maximum retry attempts exceeded
+    /// - The `cancel` future completes before the bundle is accepted
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// // Give up once the opportunity is 400ms old
+    /// let expired = tokio::time::sleep(Duration::from_millis(400));
+    ///
+    /// match client.send_with_retry_cancellable(&transactions, RetryLogic::new(10), expired).await {
+    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///     Err(e) => eprintln!("Failed to send: {}", e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_retry_cancellable<C>(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+        cancel: C,
+    ) -> JitoClientResult<String>
+    where
+        C: Future<Output = ()>,
+    {
+        let bundle = self.encoder.encode(transactions);
+        let (hooks, mut requester) = self.parts();
+        hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                let retries = requester.retry_loop(request, &retry_logic, None, None, None);
+                futures::pin_mut!(retries, cancel);
+                match future::select(retries, cancel).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right(_) => Err(JitoClientError::Cancelled),
+                }
+            })
+            .await
+    }
+
+    /// Sends a bundle of transactions with automatic retries, giving up once the bundle can no longer land.
+    ///
+    /// With `BundleExpiry::Blockhash`, the client tracks the blockhash of each transaction and stops retrying once the oldest one exceeds its 150 slot validity window.
+    /// With `BundleExpiry::Slot`, retries stop once the given expiry slot is reached.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    /// * `expiry` - How the expiry of the bundle is determined
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - Maximum retry attempts exceeded
+    /// - The bundle expires before it is accepted
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult, expiry::BundleExpiry};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    ///
+    /// match client.send_with_retry_expiring(&transactions, RetryLogic::new(10), BundleExpiry::Blockhash).await {
+    ///     Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///     Err(e) => eprintln!("Failed to send: {}", e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_retry_expiring(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+        expiry: BundleExpiry,
+    ) -> JitoClientResult<String> {
+        let bundle = self.encoder.encode(transactions);
+        let deadline = expiry.deadline(&mut self.blockhashes, transactions);
+        let (hooks, mut requester) = self.parts();
+        hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                let Some(deadline) = deadline else {
+                    return requester
+                        .retry_loop(request, &retry_logic, None, None, None)
+                        .await;
+                };
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(JitoClientError::BundleExpired);
+                }
+
+                let retries =
+                    requester.retry_loop(request, &retry_logic, None, None, Some(deadline));
+                time::timeout(remaining, retries)
+                    .await
+                    .unwrap_or(Err(JitoClientError::BundleExpired))
+            })
+            .await
+    }
+
+    /// Sends a bundle of transactions with automatic retries for the target slots of `deadline`, never after its last slot.
+    ///
+    /// If the current slot is before the first target slot, the send waits for it. If it is already past the last target slot, the bundle is rejected locally
+    /// without being sent, and retries stop as soon as the last slot passes. Late bundles can only lose money, as the opportunity they target is gone.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// * `retry_logic` - Configuration for retry behavior including max attempts and wait times.
+    /// * `deadline` - Slots the bundle is meant to land in
+    /// * `slots` - Receiver of the current slot, such as `SlotWatcher::subscribe` with the `rpc` feature. If the sender is dropped, the last slot received is assumed.
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The current slot is past the last target slot, before or during the retries
+    /// - Too many transactions provided
+    /// - Transaction serialization fails
+    /// - Maximum retry attempts exceeded
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult, expiry::SlotDeadline};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example(slots: tokio::sync::watch::Receiver<u64>) -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let transactions: Vec<VersionedTransaction> = vec![/* your transactions */];
+    ///
+    /// let target = *slots.borrow() + 2;
+    /// let uuid = client
+    ///     .send_with_slot_deadline(&transactions, RetryLogic::new(5), SlotDeadline::range(target, target + 1), slots)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_slot_deadline(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        retry_logic: RetryLogic,
+        deadline: SlotDeadline,
+        mut slots: watch::Receiver<u64>,
+    ) -> JitoClientResult<String> {
+        if *slots.borrow_and_update() < deadline.first {
+            // A closed slot feed can't report the first slot anymore, so the send goes ahead with the last known slot
+            let _ = slots.wait_for(|slot| *slot >= deadline.first).await;
+        }
+        let current = *slots.borrow();
+        if deadline.is_past(current) {
+            return Err(JitoClientError::SlotDeadlinePassed {
+                current,
+                last: deadline.last,
+            });
+        }
+
+        let bundle = self.encoder.encode(transactions);
+        let (hooks, mut requester) = self.parts();
+        hooks
+            .run(requester.endpoint, transactions, bundle, async |request| {
+                let passed = async {
+                    match slots.wait_for(|slot| deadline.is_past(*slot)).await {
+                        Ok(slot) => *slot,
+                        // Without slot updates the deadline can't be observed, so retries run until exhausted
+                        Err(_) => future::pending().await,
+                    }
+                };
+                let retries = requester.retry_loop(request, &retry_logic, None, None, None);
+                futures::pin_mut!(retries, passed);
+                match future::select(retries, passed).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right((current, _)) => Err(JitoClientError::SlotDeadlinePassed {
+                        current,
+                        last: deadline.last,
+                    }),
+                }
+            })
+            .await
+    }
+
+    /// Sends a bundle of transactions only if a Jito leader is scheduled within `max_wait`, waiting until shortly before the leader's slot.
+    ///
+    /// Bundles only land in a Jito leader's slot, so this avoids paying tips on a bundle that would sit in the auction until it expires.
+    /// The next leader is taken from the currently connected region, and the wait is estimated from the slot distance.
+    ///
+    /// # Arguments
+    /// * `transactions` - A vec of transactions (`VersionedTransaction`) to be sent
+    /// * `max_wait` - Longest time to wait for a Jito leader's slot
+    ///
+    /// # Returns
+    /// Returns a String containing the unique bundle ID.
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - The next leader cannot be queried
+    /// - No Jito leader is scheduled within `max_wait`
+    /// - Too many transactions provided, or transaction serialization fails
+    /// - The gRPC request fails
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::{JitoClientError, JitoClientResult}};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let transactions: Vec<VersionedTransaction> = vec![/* your transactions */];
+    ///
+    /// match client.send_when_leader(&transactions, Duration::from_secs(2)).await {
+    ///     Ok(uuid) => println!("Bundle sent: {uuid}"),
+    ///     Err(e) => match e.inner() {
+    ///         JitoClientError::NoUpcomingLeader(slots) => println!("Next Jito leader is {slots} slots away"),
+    ///         _ => return Err(e),
+    ///     },
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_when_leader(
+        &mut self,
+        transactions: &[VersionedTransaction],
+        max_wait: Duration,
+    ) -> JitoClientResult<String> {
+        let leader = self.get_next_leader(&[]).await?;
+        if leader.time_until() > max_wait {
+            return Err(JitoClientError::NoUpcomingLeader(leader.slots_until()));
+        }
+        let lead = DEFAULT_SLOT_DURATION * LEADER_LEAD_SLOTS;
+        sleep(leader.time_until().saturating_sub(lead)).await;
+        self.send(transactions).await
+    }
+
+    /// Sends a single transaction through the block engine's transaction endpoint, without wrapping it in a bundle.
+    ///
+    /// The transaction gets Jito's routing to the leader, but none of the guarantees of a bundle. Include a tip transfer for it to be prioritized.
+    /// The transaction is sent over HTTP JSON-RPC to `<endpoint>/api/v1/transactions`, the searcher gRPC service has no transaction method.
+    ///
+    /// # Arguments
+    /// * `transaction` - A signed transaction (`VersionedTransaction`) to be sent
+    ///
+    /// # Returns
+    /// Returns the transaction's signature.
+    ///
+    /// # Errors
+    /// This function will return an error if transaction serialization or the request fails, or the block engine rejects the transaction.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example(transaction: VersionedTransaction) -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let signature = client.send_transaction(&transaction).await?;
+    /// println!("Sent {signature}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rpc")]
+    pub async fn send_transaction(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> JitoClientResult<solana_transaction::Signature> {
+        let rpc = self.transactions.get_or_init(|| {
+            SolanaRpc::new(format!(
+                "{}/api/v1/transactions",
+                self.endpoint.trim_end_matches('/')
+            ))
+        });
+        rpc.send_transaction(transaction).await.map_err(|e| {
+            e.with_context(ErrorContext {
+                endpoint: Some(self.endpoint),
+                bundle_size: None,
+                attempt: None,
+            })
+        })
+    }
+
+    /// Sends multiple independent bundles concurrently, keeping at most `concurrency` sends in flight at once.
+    ///
+    /// # Arguments
+    /// * `bundles` - A vec of bundles, each a vec of transactions (`VersionedTransaction`) to be sent
+    /// * `concurrency` - Maximum number of bundles in flight at once. A value of 0 is treated as 1.
+    ///
+    /// # Returns
+    /// Returns the result of each bundle send, in the same order as the input bundles. A failed bundle does not stop the others from being sent.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let client = JitoClient::new_dynamic_region(None).await?;
+    ///
+    /// let bundles: Vec<Vec<VersionedTransaction>> = vec![vec![], vec![]];
+    ///
+    /// for result in client.send_batch(bundles, 4).await {
+    ///     match result {
+    ///         Ok(uuid) => println!("Bundle ID: {}", uuid),
+    ///         Err(e) => eprintln!("Failed to send: {}", e),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_batch(
+        &self,
+        bundles: Vec<Vec<VersionedTransaction>>,
+        concurrency: usize,
+    ) -> Vec<JitoClientResult<String>> {
+        futures::stream::iter(bundles)
+            .map(|transactions| {
+                let mut client = self.client.clone();
+                async move {
+                    let bundle = Bundle::create_with(&transactions, self.encoder.options());
+                    let mut requester = self.requester(&mut client);
+                    self.hooks
+                        .run(self.endpoint, &transactions, bundle, async |request| {
+                            Ok(requester.send(request).await?.uuid)
+                        })
+                        .await
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    // Splits the client into its send hooks and a requester over its own channel, so a send can run through both
+    fn parts(&mut self) -> (&SendHooks, Requester<'_>) {
+        #[cfg(feature = "latency")]
+        self.follow_preferred_region();
+        let requester = Requester {
+            client: &mut self.client,
+            signer: &self.signer,
+            auth_pool: self.auth_pool.as_ref(),
+            stats: &self.hooks.stats,
+            endpoint: self.endpoint,
+            path: &self.path,
+            timeout: self.timeout.as_ref(),
+            in_flight: self.in_flight.as_ref(),
+        };
+        (&self.hooks, requester)
+    }
+
+    // A requester with the client's settings over `client`, a clone of the client's own
+    fn requester<'a>(&'a self, client: &'a mut SearcherClient) -> Requester<'a> {
+        Requester {
+            client,
+            signer: &self.signer,
+            auth_pool: self.auth_pool.as_ref(),
+            stats: &self.hooks.stats,
+            endpoint: self.endpoint,
+            path: &self.path,
+            timeout: self.timeout.as_ref(),
+            in_flight: self.in_flight.as_ref(),
+        }
+    }
+}
+
+// Sends bundle requests over a client's gRPC channel, with the client's signers, timeout, in-flight limit and submit path
+pub(crate) struct Requester<'a> {
+    client: &'a mut SearcherClient,
+    signer: &'a SignerInterceptor,
+    auth_pool: Option<&'a AuthenticatorPool>,
+    stats: &'a LatencyStats,
+    endpoint: &'static str,
+    path: &'a SubmitPath,
+    timeout: Option<&'a AdaptiveTimeout>,
+    in_flight: Option<&'a InFlightLimit>,
+}
+
+impl Requester<'_> {
+    // Sends the request, recording its round trip, or its failure, in the latency statistics of the endpoint.
+    // Waits for a slot first if the client limits its sends in flight.
+    // If the gRPC frontend is unavailable, the bundle is sent over the path's fallback, if any, which returns no response metadata. An adaptive timeout sets the request's deadline
+    // A rate-limited send rotates the client's authenticator pool to its next keypair
+    async fn send(
+        &mut self,
+        request: impl IntoRequest<SendBundleRequest>,
+    ) -> Result<BundleSubmission, tonic::Status> {
+        let _permit = match self.in_flight {
+            Some(in_flight) => Some(in_flight.acquire().await?),
+            None => None,
+        };
+        let (stats, endpoint) = (self.stats, self.endpoint);
+        let mut request = self.signer.request(request);
+        // A retry within a deadline already set the attempt's share of it
+        if let Some(timeout) = self.timeout
+            && !request.metadata().contains_key("grpc-timeout")
+        {
+            request.set_timeout(timeout.timeout(stats, endpoint));
+        }
+        let fallback = self.path.has_fallback().then(|| request.get_ref().clone());
+        let start = Instant::now();
+        match self.client.send_bundle(request).await {
+            Ok(response) => {
+                stats.record(endpoint, start.elapsed());
+                let metadata = ResponseMetadata::new(response.metadata().clone());
+                let response = response.into_inner();
+                let uuid = response.uuid.clone();
+                debug!("Bundle {uuid} accepted by {endpoint}{metadata}");
+                Ok(BundleSubmission {
+                    uuid,
+                    metadata,
+                    response,
+                })
+            }
+            Err(e) => {
+                if timed_out(&e) {
+                    stats.record_timeout(endpoint, start.elapsed());
+                } else {
+                    stats.record_failure(endpoint);
+                }
+                if let Some(pool) = self.auth_pool {
+                    pool.rotate_on_rate_limit(&JitoClientError::SendError(e.clone()));
+                }
+                match fallback {
+                    Some(request) if unavailable(&e) => {
+                        let uuid = self.path.fall_back(e, request).await?;
+                        Ok(BundleSubmission::from_uuid(uuid))
+                    }
+                    _ => Err(e),
+                }
+            }
+        }
+    }
+
+    // Sends the request with the options' metadata until it succeeds, waiting a random jitter between attempts, and reports each attempt if asked to.
+    // Returns error once max retries is reached, or right away if the error isn't retryable
+    // Before the retry logic's deadline, or `expires_at` if earlier, each attempt times out after its share of the remaining time
+    async fn retry_loop(
+        &mut self,
+        request: SendBundleRequest,
+        retry_logic: &RetryLogic,
+        options: Option<&RequestOptions>,
+        mut reports: Option<&mut Vec<AttemptReport>>,
+        expires_at: Option<Instant>,
+    ) -> JitoClientResult<String> {
+        let deadline = match (retry_logic.deadline, expires_at) {
+            (Some(deadline), Some(expires_at)) => Some(deadline.min(expires_at)),
+            (deadline, expires_at) => deadline.or(expires_at),
+        };
+        let mut retries = 0u8;
+        loop {
+            let mut attempt = match options {
+                Some(options) => options.request(request.clone()),
+                None => tonic::Request::new(request.clone()),
+            };
+            if let Some(deadline) = deadline {
+                let budget = retry_logic.attempt_budget(deadline, retries.saturating_add(1));
+                attempt.set_timeout(match self.timeout {
+                    Some(timeout) => budget.min(timeout.timeout(self.stats, self.endpoint)),
+                    None => budget,
+                });
+            }
+            let start = Instant::now();
+            match self.send(attempt).await {
+                Ok(submission) => {
+                    if let Some(reports) = &mut reports {
+                        reports.push(AttemptReport {
+                            attempt: retries.saturating_add(1),
+                            error: None,
+                            wait: Duration::ZERO,
+                            elapsed: start.elapsed(),
+                        });
+                    }
+                    return Ok(submission.uuid);
+                }
+                Err(e) => {
+                    debug!("Send error: {e}");
+                    let elapsed = start.elapsed();
+                    retries = retries.saturating_add(1);
+                    // A final rejection, such as an invalid bundle, fails the same way on every attempt, so it is returned right away
+                    let error = JitoClientError::SendError(e.clone());
+                    let retryable = error.is_retryable();
+                    let wait = retryable.then(|| retry_logic.next_wait(retries)).flatten();
+                    if let Some(reports) = &mut reports {
+                        reports.push(AttemptReport {
+                            attempt: retries,
+                            error: Some(e),
+                            wait: wait.unwrap_or_default(),
+                            elapsed,
+                        });
+                    }
+                    if !retryable {
+                        return Err(error.with_context(ErrorContext {
+                            attempt: Some(retries),
+                            ..Default::default()
+                        }));
+                    }
+                    let Some(wait) = wait else {
+                        return Err(JitoClientError::MaxRetriesError.with_context(ErrorContext {
+                            attempt: Some(retries),
+                            ..Default::default()
+                        }));
+                    };
+                    sleep(wait).await;
+                }
+            }
+        }
+    }
+}
+
+// Whether the gRPC frontend failed to handle the request at all, as opposed to rejecting the bundle. A request that timed out or was
+// cancelled may still have reached the block engine, so sending it again over HTTP could land it twice
+fn unavailable(status: &tonic::Status) -> bool {
+    status.code() == tonic::Code::Unavailable
+        || std::error::Error::source(status)
+            .is_some_and(|source| source.is::<tonic::transport::Error>())
+}
+
+// Whether the request ran out of time, either at the block engine or at the client's own deadline, which tonic reports as cancelled
+fn timed_out(status: &tonic::Status) -> bool {
+    status.code() == tonic::Code::DeadlineExceeded
+        || status.code() == tonic::Code::Cancelled
+            && status.message() == tonic::TimeoutExpired(()).to_string()
+}
+
+// Sends the bundle unless an identical one was sent within the dedup window, in which case that bundle's uuid is returned. Sends without dedup if there is no dedup
+async fn deduped<F>(
+    dedup: Option<&BundleDedup>,
+    bundle: Bundle,
+    send: F,
+) -> JitoClientResult<String>
+where
+    F: AsyncFnOnce(SendBundleRequest) -> JitoClientResult<String>,
+{
+    let key = match dedup.map(|dedup| dedup.claim(&bundle)).transpose()? {
+        None => None,
+        Some(Claim::New(key)) => Some(key),
+        Some(Claim::Sent(uuid)) => {
+            debug!("Suppressed duplicate of bundle {uuid}");
+            return Ok(uuid);
+        }
+    };
+    let result = send(SendBundleRequest {
+        bundle: Some(bundle),
+    })
+    .await;
+    if let (Some(dedup), Some(key)) = (dedup, key) {
+        dedup.complete(key, &result);
+    }
+    result
+}
+
+// What every send of a client goes through around its requests: the signature guard, journal and deduplication before the bundle is sent,
+// and the outcome statistics and submission sink after. Requests are timed in the latency statistics
+#[derive(Clone, Default)]
+pub(crate) struct SendHooks {
+    pub(crate) journal: Option<Arc<BundleJournal>>,
+    pub(crate) guard: Option<Arc<SignatureGuard>>,
+    pub(crate) dedup: Option<Arc<BundleDedup>>,
+    pub(crate) sink: Option<Arc<dyn SubmissionSink>>,
+    pub(crate) outcomes: BundleStats,
+    pub(crate) stats: LatencyStats,
+}
+
+impl SendHooks {
+    // Sends `bundle`, built from `transactions`, to `endpoint` with `send` through every hook. Claims the transactions' signatures in the guard
+    // and records the bundle in the journal before the send starts, then marks the send outcome in both, skipping whichever of them is missing.
+    // A bundle that could not be built fails like a rejected send. A failure gets the endpoint and bundle size attached, a success is recorded
+    // in the outcome statistics
+    pub(crate) async fn run<F>(
+        &self,
+        endpoint: &'static str,
+        transactions: &[VersionedTransaction],
+        bundle: JitoClientResult<Bundle>,
+        send: F,
+    ) -> JitoClientResult<String>
+    where
+        F: AsyncFnOnce(SendBundleRequest) -> JitoClientResult<String>,
+    {
+        let context = || ErrorContext {
+            endpoint: Some(endpoint),
+            bundle_size: Some(transactions.len()),
+            attempt: None,
+        };
+        let claimed = match &self.guard {
+            Some(guard) => Some(
+                guard
+                    .claim(transactions)
+                    .map_err(|e| e.with_context(context()))?,
+            ),
+            None => None,
+        };
+        let submitted_at = SystemTime::now();
+        let start = Instant::now();
+        let send = async { deduped(self.dedup.as_deref(), bundle?, send).await };
+        let result = match &self.journal {
+            Some(journal) => journal_send(journal, transactions, send).await,
+            None => send.await,
+        };
+        if let (Some(guard), Some(claimed)) = (&self.guard, claimed) {
+            guard.complete(&claimed, result.is_ok());
+        }
+        let result = result.map_err(|e| e.with_context(context()));
+        if let Ok(uuid) = &result {
+            self.outcomes
+                .record_sent(endpoint, uuid, transactions, start);
+        }
+        if let Some(sink) = &self.sink {
+            sink.on_submitted(&Submission {
+                endpoint,
+                transactions,
+                submitted_at,
+                elapsed: start.elapsed(),
+                result: result.as_deref(),
+            })
+            .await;
+        }
+        result
+    }
+}
+
+// Records the bundle in the journal before the send starts, then marks the send outcome
+async fn journal_send<F>(
+    journal: &BundleJournal,
+    transactions: &[VersionedTransaction],
+    send: F,
+) -> JitoClientResult<String>
+where
+    F: Future<Output = JitoClientResult<String>>,
+{
+    let id = journal.record_submission(transactions).await?;
+    let result = send.await;
+    let marked = match &result {
+        Ok(uuid) => journal.record_sent(&id, uuid),
+        Err(e) => journal.record_failed(&id, e),
+    };
+    if let Err(e) = marked {
+        debug!("Journal error: {e}");
+    }
+    result
+}
+
+/// Path bundles are sent to the block engine over, set with `JitoClient::with_submit_path`.
+#[derive(Debug, Clone, Default)]
+pub enum SubmitPath {
+    /// Sends over the client's gRPC channel
+    #[default]
+    Grpc,
+    /// Sends over the client's gRPC channel, and resends over the block engine's HTTP JSON-RPC `sendBundle` if the gRPC frontend is unavailable.
+    ///
+    /// If both fail, the gRPC error is returned, so retries and error classification are unchanged.
+    #[cfg(feature = "rpc")]
+    GrpcWithHttpFallback(SolanaRpc),
+}
+
+impl SubmitPath {
+    /// Falls back to the JSON-RPC API served by the block engine at `endpoint`, the gRPC endpoint the client connects to.
+    #[cfg(feature = "rpc")]
+    pub fn http_fallback(endpoint: &str) -> Self {
+        Self::GrpcWithHttpFallback(SolanaRpc::block_engine(endpoint))
+    }
+
+    // The submit path of a client switching block engines: a fallback to the old block engine's own JSON-RPC API switches along with it,
+    // a fallback to any other URL is kept
+    #[cfg(feature = "rpc")]
+    pub(crate) fn follow(&self, from: &str, to: &str) -> Self {
+        match self {
+            Self::GrpcWithHttpFallback(rpc) if rpc.rpc_client().url() == bundles_url(from) => {
+                Self::http_fallback(to)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    fn has_fallback(&self) -> bool {
+        !matches!(self, Self::Grpc)
+    }
+
+    async fn fall_back(
+        &self,
+        status: tonic::Status,
+        request: SendBundleRequest,
+    ) -> Result<String, tonic::Status> {
+        match (self, request.bundle) {
+            #[cfg(feature = "rpc")]
+            (Self::GrpcWithHttpFallback(rpc), Some(bundle)) => {
+                debug!("gRPC send failed ({status}), falling back to HTTP");
+                rpc.send_packets(&bundle).await.map_err(|e| {
+                    debug!("HTTP fallback send error: {e}");
+                    status
+                })
+            }
+            _ => Err(status),
+        }
+    }
+}
+
+// Caps the sends a client has in flight at once, queueing the others, optionally for a limited time
+#[derive(Debug, Clone)]
+pub(crate) struct InFlightLimit {
+    permits: Arc<Semaphore>,
+    queue_timeout: Option<Duration>,
+}
+
+impl InFlightLimit {
+    pub(crate) fn new(limit: usize, queue_timeout: Option<Duration>) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(limit)),
+            queue_timeout,
+        }
+    }
+
+    // Waits for a free slot, failing with ResourceExhausted once queued for longer than the queue timeout
+    async fn acquire(&self) -> Result<SemaphorePermit<'_>, tonic::Status> {
+        let acquire = self.permits.acquire();
+        let acquired = match self.queue_timeout {
+            None => acquire.await,
+            Some(timeout) => time::timeout(timeout, acquire).await.map_err(|_| {
+                tonic::Status::resource_exhausted(format!(
+                    "send queued for over {timeout:?} behind the in-flight limit"
+                ))
+            })?,
+        };
+        // The semaphore is never closed
+        acquired.map_err(|e| tonic::Status::internal(e.to_string()))
+    }
+}
+
+/// Outcome of a single send attempt made by a retrying send.
+#[derive(Debug, Clone)]
+pub struct AttemptReport {
+    /// Attempt number, starting at 1
+    pub attempt: u8,
+    /// Error returned by the block engine, or `None` if the attempt succeeded
+    pub error: Option<tonic::Status>,
+    /// Jitter waited after the attempt before the next one
+    pub wait: Duration,
+    /// Round trip time of the attempt
+    pub elapsed: Duration,
+}
+
+/// A bundle accepted by the block engine, along with its full response and the response's metadata.
+#[derive(Debug, Clone)]
+pub struct BundleSubmission {
+    /// Unique bundle ID
+    pub uuid: String,
+    pub metadata: ResponseMetadata,
+    /// The decoded `SendBundleResponse`, including any field added by a newer proto revision beside the uuid.
+    /// Holds only the uuid if the bundle was a suppressed duplicate or sent over an HTTP fallback
+    pub response: SendBundleResponse,
+}
+
+impl BundleSubmission {
+    // Submission of a bundle whose response wasn't received from the block engine, such as a suppressed duplicate
+    fn from_uuid(uuid: String) -> Self {
+        Self {
+            response: SendBundleResponse { uuid: uuid.clone() },
+            uuid,
+            metadata: ResponseMetadata::default(),
+        }
+    }
+}
+
+/// Headers and trailers of a block engine response, with accessors for the well-known ones.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseMetadata {
+    metadata: MetadataMap,
+}
+
+/// Rate limit counters reported by the block engine, each `None` if its header is missing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per window
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: Option<u64>,
+    /// Time until the window resets
+    pub reset: Option<Duration>,
+}
+
+impl ResponseMetadata {
+    pub fn new(metadata: MetadataMap) -> Self {
+        Self { metadata }
+    }
+
+    /// Returns the value of an ASCII entry, if present.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key)?.to_str().ok()
+    }
+
+    /// Returns every entry of the response.
+    pub fn metadata(&self) -> &MetadataMap {
+        &self.metadata
+    }
+
+    /// Returns the server-side duration reported in a `server-timing` header, summing the `dur` of every metric.
+    pub fn server_timing(&self) -> Option<Duration> {
+        let durations: Vec<f64> = self
+            .get("server-timing")?
+            .split(',')
+            .filter_map(|metric| {
+                metric
+                    .split(';')
+                    .find_map(|param| param.trim().strip_prefix("dur="))
+                    .and_then(|ms| ms.parse().ok())
+            })
+            .collect();
+        (!durations.is_empty())
+            .then(|| Duration::from_secs_f64(durations.iter().sum::<f64>().max(0.0) / 1000.0))
+    }
+
+    /// Returns the rate limit counters from `x-ratelimit-*` headers, or `ratelimit-*` headers, if any is present.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let counter = |name: &str| {
+            self.get(&format!("x-ratelimit-{name}"))
+                .or_else(|| self.get(&format!("ratelimit-{name}")))
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let rate_limit = RateLimit {
+            limit: counter("limit"),
+            remaining: counter("remaining"),
+            reset: counter("reset").map(Duration::from_secs),
+        };
+        (rate_limit != RateLimit::default()).then_some(rate_limit)
+    }
+
+    /// Returns whether the response carried no metadata.
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty()
+    }
+}
+
+// Lists the well-known entries, appended to the log line of an accepted bundle
+impl Display for ResponseMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(server_timing) = self.server_timing() {
+            write!(f, ", server timing {server_timing:?}")?;
+        }
+        if let Some(rate_limit) = self.rate_limit() {
+            let counter = |value: Option<u64>| value.map_or("?".to_string(), |v| v.to_string());
+            write!(
+                f,
+                ", rate limit {}/{} remaining",
+                counter(rate_limit.remaining),
+                counter(rate_limit.limit)
+            )?;
+            if let Some(reset) = rate_limit.reset {
+                write!(f, ", resets in {reset:?}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-send options applied to the gRPC request, such as custom metadata.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    metadata: MetadataMap,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a metadata entry sent along with the request, e.g. an experiment tag or an internal correlation id to reference with Jito support.
+    ///
+    /// # Errors
+    /// This function will return an error if the key is not a valid lowercase header name, or the value is not printable ASCII.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> JitoClientResult<Self> {
+        let invalid = || JitoClientError::InvalidMetadata(key.to_string());
+        let key = MetadataKey::from_bytes(key.as_bytes()).map_err(|_| invalid())?;
+        let value = MetadataValue::try_from(value).map_err(|_| invalid())?;
+        self.metadata.insert(key, value);
+        Ok(self)
+    }
+
+    // Wraps the message into a request carrying the metadata
+    fn request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        *request.metadata_mut() = self.metadata.clone();
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_flight_limit_times_out_queued_sends() {
+        let limit = InFlightLimit::new(1, Some(Duration::from_millis(10)));
+        let permit = limit.acquire().await.unwrap();
+        let queued = limit.acquire().await.unwrap_err();
+        assert_eq!(queued.code(), tonic::Code::ResourceExhausted);
+        drop(permit);
+        assert!(limit.acquire().await.is_ok());
+    }
+
+    #[test]
+    fn response_metadata_reads_timing_and_rate_limit() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert(
+            "server-timing",
+            "auth;dur=1.5, send;dur=2.5".parse().unwrap(),
+        );
+        metadata.insert("x-ratelimit-remaining", "7".parse().unwrap());
+        metadata.insert("x-ratelimit-reset", "1".parse().unwrap());
+        let metadata = ResponseMetadata::new(metadata);
+        assert_eq!(metadata.server_timing(), Some(Duration::from_millis(4)));
+        assert_eq!(
+            metadata.rate_limit(),
+            Some(RateLimit {
+                limit: None,
+                remaining: Some(7),
+                reset: Some(Duration::from_secs(1)),
+            })
+        );
+        assert_eq!(
+            metadata.to_string(),
+            ", server timing 4ms, rate limit 7/? remaining, resets in 1s"
+        );
+        assert!(ResponseMetadata::default().rate_limit().is_none());
+    }
+
+    #[test]
+    fn request_options_attach_metadata() {
+        let options = RequestOptions::new()
+            .with_metadata("x-correlation-id", "42")
+            .unwrap();
+        let request = options.request(());
+        assert_eq!(request.metadata().get("x-correlation-id").unwrap(), "42");
+
+        assert!(RequestOptions::new().with_metadata("Bad Key", "1").is_err());
+        assert!(
+            RequestOptions::new()
+                .with_metadata("key", "line\n")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::clock::ClockSkewEstimator;
+use crate::errors::JitoClientResult;
+use crate::grpc::bundle::BundleResult;
+use crate::grpc::searcher::SubscribeBundleResultsRequest;
+use crate::signing::SearcherClient;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time;
+use tonic::{Status, Streaming};
+
+/// How a dropped subscription is resubscribed: with exponential backoff between failed attempts, up to an optional limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Wait after the first failed attempt
+    pub initial_backoff: Duration,
+    /// Upper bound of the wait, which doubles after every failed attempt
+    pub max_backoff: Duration,
+    /// Failed attempts in a row after which the subscription ends, or `None` to retry forever
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    // Wait after the given failed attempt, starting at 1
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// What happens when a subscription's buffer is full because the consumer reads slower than results arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered event to make room, counting it in `ResultSubscription::dropped`
+    DropOldest,
+    /// Stop reading from the server until the consumer catches up. Results queue up on the server side and the stream may be dropped
+    Block,
+    /// End the subscription with a final `SubscriptionEvent::Overflow`
+    Error,
+}
+
+/// Bounded buffering between a subscription's background reader and its consumer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferPolicy {
+    /// Maximum number of buffered events. A capacity of 0 is treated as 1.
+    pub capacity: usize,
+    /// What happens when the buffer is full
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for BufferPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            overflow: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+/// An item of a resubscribing bundle result subscription.
+#[derive(Debug, Clone)]
+pub enum SubscriptionEvent {
+    /// A bundle result
+    Result(BundleResult),
+    /// The stream dropped and was resubscribed. Results produced while it was down may have been missed.
+    Gap {
+        /// Why the stream dropped
+        reason: Status,
+        /// Time between the drop and the resubscription
+        downtime: Duration,
+        /// Subscription attempts it took to resubscribe
+        attempts: u32,
+    },
+    /// The buffer overflowed under `OverflowPolicy::Error`. This is the last event of the subscription.
+    Overflow,
+}
+
+/// Bundle result subscription that resubscribes whenever the server drops the stream, created with `JitoClient::subscribe_bundle_results_with_reconnect`.
+///
+/// Yields every result along with a `Gap` event after each resubscription. The stream only ends once the reconnect policy's attempts are exhausted,
+/// or the buffer overflows under `OverflowPolicy::Error`. Events are buffered up to the buffer policy's capacity, so a slow consumer can't grow memory without bound.
+/// The background task stops when the subscription is dropped.
+pub struct ResultSubscription {
+    events: Events,
+    state: Arc<BufferState>,
+    task: JoinHandle<()>,
+}
+
+// Receiving end of the buffer, shared between the subscription and its sender
+type Events = Arc<Mutex<mpsc::Receiver<SubscriptionEvent>>>;
+
+#[derive(Default)]
+struct BufferState {
+    dropped: AtomicU64,
+    overflowed: AtomicBool,
+}
+
+impl ResultSubscription {
+    // Subscribes, recording the `date` header of every subscription response in `clock`
+    pub(crate) async fn start(
+        mut client: SearcherClient,
+        policy: ReconnectPolicy,
+        buffer: BufferPolicy,
+        clock: ClockSkewEstimator,
+    ) -> JitoClientResult<Self> {
+        let start = Instant::now();
+        let response = client
+            .subscribe_bundle_results(SubscribeBundleResultsRequest {})
+            .await?;
+        clock.record_response(response.metadata(), start);
+        let stream = response.into_inner();
+        let (sender, events, state) = EventSender::buffer(buffer);
+        let task = tokio::spawn(run(client, policy, stream, sender, clock));
+        Ok(Self {
+            events,
+            state,
+            task,
+        })
+    }
+
+    /// Returns the number of events discarded under `OverflowPolicy::DropOldest` so far.
+    pub fn dropped(&self) -> u64 {
+        self.state.dropped.load(Ordering::Relaxed)
+    }
+}
+
+// Delivers events into the bounded buffer according to the overflow policy
+struct EventSender {
+    sender: mpsc::Sender<SubscriptionEvent>,
+    // Shared with the subscription, so the oldest event can be discarded
+    events: Events,
+    overflow: OverflowPolicy,
+    state: Arc<BufferState>,
+}
+
+impl EventSender {
+    fn buffer(policy: BufferPolicy) -> (Self, Events, Arc<BufferState>) {
+        let (sender, receiver) = mpsc::channel(policy.capacity.max(1));
+        let events = Arc::new(Mutex::new(receiver));
+        let state = Arc::new(BufferState::default());
+        let sender = Self {
+            sender,
+            events: events.clone(),
+            overflow: policy.overflow,
+            state: state.clone(),
+        };
+        (sender, events, state)
+    }
+
+    // Returns false once events can't be delivered anymore, which ends the subscription
+    async fn send(&self, mut event: SubscriptionEvent) -> bool {
+        match self.overflow {
+            OverflowPolicy::Block => self.sender.send(event).await.is_ok(),
+            OverflowPolicy::DropOldest => loop {
+                match self.sender.try_send(event) {
+                    Ok(()) => return true,
+                    Err(mpsc::error::TrySendError::Closed(_)) => return false,
+                    Err(mpsc::error::TrySendError::Full(rejected)) => {
+                        event = rejected;
+                        if lock(&self.events).try_recv().is_ok() {
+                            self.state.dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                }
+            },
+            OverflowPolicy::Error => match self.sender.try_send(event) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    debug!("Bundle result buffer overflowed, ending the subscription");
+                    self.state.overflowed.store(true, Ordering::Relaxed);
+                    false
+                }
+            },
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+// Forwards results until the stream drops, then resubscribes with backoff and reports the gap. Stops once nobody listens or attempts run out
+async fn run(
+    mut client: SearcherClient,
+    policy: ReconnectPolicy,
+    mut stream: Streaming<BundleResult>,
+    sender: EventSender,
+    clock: ClockSkewEstimator,
+) {
+    loop {
+        let reason = loop {
+            match stream.message().await {
+                Ok(Some(result)) => {
+                    if !sender.send(SubscriptionEvent::Result(result)).await {
+                        return;
+                    }
+                }
+                Ok(None) => break Status::unavailable("bundle result stream ended"),
+                Err(e) => break e,
+            }
+        };
+        debug!("Bundle result stream dropped: {reason}");
+
+        let dropped = Instant::now();
+        let mut attempts = 0;
+        stream = loop {
+            attempts += 1;
+            let start = Instant::now();
+            match client
+                .subscribe_bundle_results(SubscribeBundleResultsRequest {})
+                .await
+            {
+                Ok(response) => {
+                    clock.record_response(response.metadata(), start);
+                    break response.into_inner();
+                }
+                Err(e) => {
+                    debug!("Resubscribe attempt {attempts} failed: {e}");
+                    if policy.max_attempts.is_some_and(|max| attempts >= max) || sender.is_closed()
+                    {
+                        return;
+                    }
+                    time::sleep(policy.backoff(attempts)).await;
+                }
+            }
+        };
+        let gap = SubscriptionEvent::Gap {
+            reason,
+            downtime: dropped.elapsed(),
+            attempts,
+        };
+        if !sender.send(gap).await {
+            return;
+        }
+    }
+}
+
+impl Stream for ResultSubscription {
+    type Item = SubscriptionEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match lock(&self.events).poll_recv(cx) {
+            // The overflow is reported once the events buffered before it are consumed
+            Poll::Ready(None) if self.state.overflowed.swap(false, Ordering::Relaxed) => {
+                Poll::Ready(Some(SubscriptionEvent::Overflow))
+            }
+            poll => poll,
+        }
+    }
+}
+
+impl Drop for ResultSubscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn lock<T>(events: &Mutex<T>) -> MutexGuard<'_, T> {
+    events.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gap(attempts: u32) -> SubscriptionEvent {
+        SubscriptionEvent::Gap {
+            reason: Status::unavailable("down"),
+            downtime: Duration::ZERO,
+            attempts,
+        }
+    }
+
+    fn subscription(overflow: OverflowPolicy) -> (EventSender, ResultSubscription) {
+        let (sender, events, state) = EventSender::buffer(BufferPolicy {
+            capacity: 2,
+            overflow,
+        });
+        let task = tokio::spawn(async {});
+        (
+            sender,
+            ResultSubscription {
+                events,
+                state,
+                task,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn full_buffer_drops_oldest() {
+        use futures::StreamExt;
+
+        let (sender, mut subscription) = subscription(OverflowPolicy::DropOldest);
+        for attempts in 1..=3 {
+            assert!(sender.send(gap(attempts)).await);
+        }
+        drop(sender);
+        assert_eq!(subscription.dropped(), 1);
+        let attempts: Vec<u32> = subscription
+            .by_ref()
+            .filter_map(|event| async move {
+                match event {
+                    SubscriptionEvent::Gap { attempts, .. } => Some(attempts),
+                    _ => None,
+                }
+            })
+            .collect()
+            .await;
+        assert_eq!(attempts, [2, 3]);
+    }
+
+    #[tokio::test]
+    async fn full_buffer_ends_subscription_under_error_policy() {
+        use futures::StreamExt;
+
+        let (sender, subscription) = subscription(OverflowPolicy::Error);
+        assert!(sender.send(gap(1)).await);
+        assert!(sender.send(gap(2)).await);
+        assert!(!sender.send(gap(3)).await);
+        drop(sender);
+        let events: Vec<SubscriptionEvent> = subscription.collect().await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2], SubscriptionEvent::Overflow));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            max_attempts: None,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,1276 @@
+//! In-process mock block engine, for testing and benchmarking code built on the client without network access.
+
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::bundle::{Bundle, BundleResult};
+use crate::grpc::searcher::{
+    ConnectedLeadersRegionedRequest, ConnectedLeadersRegionedResponse, ConnectedLeadersRequest,
+    ConnectedLeadersResponse, GetRegionsRequest, GetRegionsResponse, GetTipAccountsRequest,
+    GetTipAccountsResponse, NextScheduledLeaderRequest, NextScheduledLeaderResponse,
+    SendBundleRequest, SendBundleResponse, SubscribeBundleResultsRequest,
+    searcher_service_server::{SearcherService, SearcherServiceServer},
+};
+use futures::Stream;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tonic::metadata::MetadataMap;
+use tonic::transport::server::{Connected, TcpConnectInfo};
+use tonic::{Code, Request, Response, Status};
+
+/// Tip account returned by the mock's `GetTipAccounts`.
+pub const MOCK_TIP_ACCOUNT: &str = "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5";
+/// Region reported by the mock's `GetRegions` and `GetNextScheduledLeader`.
+pub const MOCK_REGION: &str = "mock";
+
+// Bundle results not yet read by a subscriber are dropped beyond this many
+const RESULTS_CAPACITY: usize = 1024;
+
+/// A failure injected into one of the mock's `SendBundle` responses, to exercise retry, failover and rate limit handling deterministically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockFault {
+    /// Accepts the bundle after the delay
+    Delay(Duration),
+    /// Fails with `ResourceExhausted` and a `retry-after` header of the delay in whole seconds, rounded up
+    RateLimited { retry_after: Duration },
+    /// Fails with the status code and message, such as a Jito rejection like `bundle contains an already processed transaction`
+    Reject(Code, String),
+    /// Drops every open connection without answering. New connections are still accepted, so clients can reconnect
+    Disconnect,
+}
+
+/// A searcher service served over plaintext HTTP/2 on a local port, accepting every bundle.
+///
+/// Each accepted bundle gets a new uuid and is recorded, its response carrying a `server-timing` header, and bundle results pushed with `push_result` are streamed to every subscriber.
+/// Failures are scripted with `inject` and `inject_after`. The server stops when the mock is dropped.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, testing::MockBlockEngine};
+/// # async fn example() -> JitoClientResult<()> {
+/// let mock = MockBlockEngine::start().await?;
+/// let mut client = JitoClient::new(mock.endpoint(), None).await?;
+///
+/// let uuid = client.send(&[]).await?;
+/// assert_eq!(mock.bundles().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockBlockEngine {
+    address: SocketAddr,
+    endpoint: &'static str,
+    state: Arc<MockState>,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+struct MockState {
+    bundles: Mutex<Vec<Bundle>>,
+    // Metadata of the last SendBundle request
+    metadata: Mutex<Option<MetadataMap>>,
+    next_id: AtomicU64,
+    results: broadcast::Sender<BundleResult>,
+    // Fault of each upcoming SendBundle request in order, None answering normally
+    faults: Mutex<VecDeque<Option<MockFault>>>,
+    connections: Arc<Connections>,
+}
+
+// Open connections, which a `Disconnect` fault drops all at once by moving to the next generation
+#[derive(Default)]
+struct Connections {
+    generation: AtomicU64,
+    next_id: AtomicU64,
+    // Waker of the task last polling each connection, to fail a pending read or write on disconnect
+    wakers: Mutex<HashMap<u64, Waker>>,
+}
+
+// A connection failing every read and write once the connections it was accepted with are dropped
+struct MockConnection {
+    stream: TcpStream,
+    id: u64,
+    generation: u64,
+    connections: Arc<Connections>,
+}
+
+impl MockBlockEngine {
+    /// Starts the mock on a free local port. Must be called within a tokio runtime.
+    ///
+    /// # Errors
+    /// This function will return an error if no local port can be bound.
+    pub async fn start() -> JitoClientResult<Self> {
+        Self::start_on(SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    /// Starts the mock on `address`, such as one clients are already trying to connect to. Must be called within a tokio runtime.
+    ///
+    /// # Errors
+    /// This function will return an error if the address can't be bound.
+    pub async fn start_on(address: SocketAddr) -> JitoClientResult<Self> {
+        let bind_error = |source| JitoClientError::TCPConnect {
+            region: None,
+            host: address.ip().to_string(),
+            source,
+        };
+        let listener = TcpListener::bind(address).await.map_err(bind_error)?;
+        let address = listener.local_addr().map_err(bind_error)?;
+        let state = Arc::new(MockState {
+            bundles: Mutex::new(Vec::new()),
+            metadata: Mutex::new(None),
+            next_id: AtomicU64::new(0),
+            results: broadcast::channel(RESULTS_CAPACITY).0,
+            faults: Mutex::new(VecDeque::new()),
+            connections: Arc::new(Connections::default()),
+        });
+
+        let (shutdown, stopped) = oneshot::channel();
+        let service = SearcherServiceServer::new(MockService {
+            state: state.clone(),
+        });
+        let connections = state.connections.clone();
+        let incoming = futures::stream::unfold(listener, move |listener| {
+            let connections = connections.clone();
+            async move {
+                let accepted = listener
+                    .accept()
+                    .await
+                    .map(|(stream, _)| connections.open(stream));
+                Some((accepted, listener))
+            }
+        });
+        let task = tokio::spawn(async move {
+            let served = tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, async {
+                    let _ = stopped.await;
+                })
+                .await;
+            if let Err(e) = served {
+                debug!("Mock block engine error: {e}");
+            }
+        });
+
+        Ok(Self {
+            address,
+            // Mocks live for the duration of a test or benchmark, so leaking the endpoint is harmless
+            endpoint: Box::leak(format!("http://{address}").into_boxed_str()),
+            state,
+            shutdown: Some(shutdown),
+            task,
+        })
+    }
+
+    /// Returns the endpoint URL to connect clients to.
+    pub fn endpoint(&self) -> &'static str {
+        self.endpoint
+    }
+
+    /// Returns the local address the mock listens on.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns every bundle received so far, in the order they arrived.
+    pub fn bundles(&self) -> Vec<Bundle> {
+        lock(&self.state.bundles).clone()
+    }
+
+    /// Returns the metadata of the last bundle received, such as its `user-agent`, or `None` if none was.
+    pub fn last_metadata(&self) -> Option<MetadataMap> {
+        lock(&self.state.metadata).clone()
+    }
+
+    /// Streams a bundle result to every current subscriber of bundle results.
+    pub fn push_result(&self, result: BundleResult) {
+        // No subscriber is not an error, the result is just not delivered
+        let _ = self.state.results.send(result);
+    }
+
+    /// Injects `fault` into the next `SendBundle` request not already given a fault. Each injected fault applies to one request, in the order injected.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::{JitoClient, RetryLogic}, errors::JitoClientResult, testing::{MockBlockEngine, MockFault}};
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mock = MockBlockEngine::start().await?;
+    /// mock.inject(MockFault::RateLimited { retry_after: Duration::from_secs(1) });
+    ///
+    /// let mut client = JitoClient::new(mock.endpoint(), None).await?;
+    /// // The first attempt is rate limited, the retry is accepted
+    /// client.send_with_retry(&[], RetryLogic::new(2)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn inject(&self, fault: MockFault) {
+        self.inject_after(0, fault);
+    }
+
+    /// Answers the next `requests` `SendBundle` requests normally, then injects `fault` into the one after, such as dropping the connection after N requests.
+    pub fn inject_after(&self, requests: usize, fault: MockFault) {
+        let mut faults = lock(&self.state.faults);
+        faults.extend(std::iter::repeat_n(None, requests));
+        faults.push_back(Some(fault));
+    }
+
+    /// Discards every injected fault not yet applied.
+    pub fn clear_faults(&self) {
+        lock(&self.state.faults).clear();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Connections {
+    fn open(self: &Arc<Self>, stream: TcpStream) -> MockConnection {
+        MockConnection {
+            stream,
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            generation: self.generation.load(Ordering::Acquire),
+            connections: self.clone(),
+        }
+    }
+
+    fn drop_all(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        for (_, waker) in lock(&self.wakers).drain() {
+            waker.wake();
+        }
+    }
+}
+
+impl MockConnection {
+    // Fails once the connection is dropped, otherwise registers the task to wake when it is
+    fn check(&self, cx: &Context<'_>) -> io::Result<()> {
+        lock(&self.connections.wakers).insert(self.id, cx.waker().clone());
+        if self.connections.generation.load(Ordering::Acquire) == self.generation {
+            Ok(())
+        } else {
+            Err(io::ErrorKind::ConnectionReset.into())
+        }
+    }
+}
+
+impl Drop for MockConnection {
+    fn drop(&mut self) {
+        lock(&self.connections.wakers).remove(&self.id);
+    }
+}
+
+impl Connected for MockConnection {
+    type ConnectInfo = TcpConnectInfo;
+
+    fn connect_info(&self) -> TcpConnectInfo {
+        self.stream.connect_info()
+    }
+}
+
+impl AsyncRead for MockConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check(cx)?;
+        Pin::new(&mut this.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MockConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.check(cx)?;
+        Pin::new(&mut this.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check(cx)?;
+        Pin::new(&mut this.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+impl Drop for MockBlockEngine {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.task.abort();
+    }
+}
+
+struct MockService {
+    state: Arc<MockState>,
+}
+
+type ResultStream = Pin<Box<dyn Stream<Item = Result<BundleResult, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl SearcherService for MockService {
+    type SubscribeBundleResultsStream = ResultStream;
+
+    async fn subscribe_bundle_results(
+        &self,
+        _: Request<SubscribeBundleResultsRequest>,
+    ) -> Result<Response<ResultStream>, Status> {
+        let results = self.state.results.subscribe();
+        let stream = futures::stream::unfold(results, |mut results| async move {
+            loop {
+                match results.recv().await {
+                    Ok(result) => return Some((Ok(result), results)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn send_bundle(
+        &self,
+        request: Request<SendBundleRequest>,
+    ) -> Result<Response<SendBundleResponse>, Status> {
+        let start = Instant::now();
+        let fault = lock(&self.state.faults).pop_front().flatten();
+        match fault {
+            None => {}
+            Some(MockFault::Delay(delay)) => tokio::time::sleep(delay).await,
+            Some(MockFault::RateLimited { retry_after }) => {
+                let mut metadata = MetadataMap::new();
+                let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                if let Ok(value) = seconds.to_string().parse() {
+                    metadata.insert("retry-after", value);
+                }
+                return Err(Status::with_metadata(
+                    Code::ResourceExhausted,
+                    "Network congested. Endpoint is globally rate limited.",
+                    metadata,
+                ));
+            }
+            Some(MockFault::Reject(code, message)) => return Err(Status::new(code, message)),
+            Some(MockFault::Disconnect) => {
+                self.state.connections.drop_all();
+                // The response is never written, the connection being dropped
+                return Err(Status::unavailable("connection dropped"));
+            }
+        }
+        *lock(&self.state.metadata) = Some(request.metadata().clone());
+        let bundle = request
+            .into_inner()
+            .bundle
+            .ok_or_else(|| Status::invalid_argument("missing bundle"))?;
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        lock(&self.state.bundles).push(bundle);
+        let mut response = Response::new(SendBundleResponse {
+            uuid: format!("00000000-0000-0000-0000-{id:012x}"),
+        });
+        let server_timing = format!("send;dur={:.3}", start.elapsed().as_secs_f64() * 1000.0);
+        if let Ok(value) = server_timing.parse() {
+            response.metadata_mut().insert("server-timing", value);
+        }
+        Ok(response)
+    }
+
+    async fn get_next_scheduled_leader(
+        &self,
+        _: Request<NextScheduledLeaderRequest>,
+    ) -> Result<Response<NextScheduledLeaderResponse>, Status> {
+        Ok(Response::new(NextScheduledLeaderResponse {
+            current_slot: 1000,
+            next_leader_slot: 1004,
+            next_leader_identity: MOCK_TIP_ACCOUNT.to_string(),
+            next_leader_region: MOCK_REGION.to_string(),
+        }))
+    }
+
+    async fn get_connected_leaders(
+        &self,
+        _: Request<ConnectedLeadersRequest>,
+    ) -> Result<Response<ConnectedLeadersResponse>, Status> {
+        Ok(Response::new(ConnectedLeadersResponse::default()))
+    }
+
+    async fn get_connected_leaders_regioned(
+        &self,
+        _: Request<ConnectedLeadersRegionedRequest>,
+    ) -> Result<Response<ConnectedLeadersRegionedResponse>, Status> {
+        Ok(Response::new(ConnectedLeadersRegionedResponse::default()))
+    }
+
+    async fn get_tip_accounts(
+        &self,
+        _: Request<GetTipAccountsRequest>,
+    ) -> Result<Response<GetTipAccountsResponse>, Status> {
+        Ok(Response::new(GetTipAccountsResponse {
+            accounts: vec![MOCK_TIP_ACCOUNT.to_string()],
+        }))
+    }
+
+    async fn get_regions(
+        &self,
+        _: Request<GetRegionsRequest>,
+    ) -> Result<Response<GetRegionsResponse>, Status> {
+        Ok(Response::new(GetRegionsResponse {
+            current_region: MOCK_REGION.to_string(),
+            available_regions: vec![MOCK_REGION.to_string()],
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::JitoClient;
+
+    #[tokio::test]
+    async fn mock_accepts_bundles() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+
+        let first = client.send(&[]).await.unwrap();
+        let second = client.send(&[]).await.unwrap();
+        assert_ne!(first, second);
+        assert_eq!(mock.bundles().len(), 2);
+        assert_eq!(client.get_tip_accounts().await.unwrap().0.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn injected_faults_apply_in_order() {
+        use crate::errors::ErrorKind;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        mock.inject_after(
+            1,
+            MockFault::RateLimited {
+                retry_after: Duration::from_millis(1500),
+            },
+        );
+        mock.inject(MockFault::Reject(
+            Code::InvalidArgument,
+            "bundle contains an already processed transaction".to_string(),
+        ));
+        mock.inject(MockFault::Delay(Duration::from_millis(50)));
+
+        assert!(client.send(&[]).await.is_ok());
+        let limited = client.send(&[]).await.unwrap_err();
+        assert!(limited.is_rate_limited());
+        let JitoClientError::SendError(status) = limited.inner() else {
+            panic!("the second send is rate limited");
+        };
+        assert_eq!(status.metadata().get("retry-after").unwrap(), "2");
+        let rejected = client.send(&[]).await.unwrap_err();
+        assert_eq!(rejected.kind(), ErrorKind::InvalidInput);
+        assert!(rejected.to_string().contains("already processed"));
+        let start = Instant::now();
+        assert!(client.send(&[]).await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(mock.bundles().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn disconnect_drops_open_connections() {
+        use crate::client::RetryLogic;
+        use crate::errors::ErrorKind;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        mock.inject_after(1, MockFault::Disconnect);
+
+        assert!(client.send(&[]).await.is_ok());
+        assert_eq!(
+            client.send(&[]).await.unwrap_err().kind(),
+            ErrorKind::Network
+        );
+
+        mock.inject(MockFault::Disconnect);
+        let retry = RetryLogic::new_with_wait_bounds(3, 1, 10).unwrap();
+        assert!(client.send_with_retry(&[], retry).await.is_ok());
+        assert_eq!(mock.bundles().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn submission_carries_response_metadata() {
+        use crate::client::RequestOptions;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+
+        let submission = client
+            .send_detailed(&[], &RequestOptions::default())
+            .await
+            .unwrap();
+        assert!(submission.metadata.server_timing().is_some());
+        assert!(submission.metadata.rate_limit().is_none());
+        assert_eq!(submission.response.uuid, submission.uuid);
+
+        let full = client.send_full(&[]).await.unwrap();
+        assert_eq!(full.response.uuid, full.uuid);
+        assert_ne!(full.uuid, submission.uuid);
+    }
+
+    #[tokio::test]
+    async fn request_signers_sign_every_rpc() {
+        use crate::errors::ErrorKind;
+        use crate::signing::{RequestSigner, SigningRequest};
+
+        // Records the signed paths and payload sizes, refusing to sign GetRegions
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<(String, Option<usize>)>>);
+
+        impl RequestSigner for Recorder {
+            fn sign(&self, request: &mut SigningRequest<'_>) -> Result<(), Status> {
+                let path = request.path().unwrap_or_default();
+                lock(&self.0).push((path, request.payload().map(<[u8]>::len)));
+                match request.method() {
+                    Some("GetRegions") => Err(Status::permission_denied("not signed")),
+                    _ => Ok(()),
+                }
+            }
+
+            fn signs_payload(&self) -> bool {
+                true
+            }
+        }
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let mut client = JitoClient::builder()
+            .endpoint(mock.endpoint())
+            .request_signer(recorder.clone())
+            .build()
+            .await
+            .unwrap();
+
+        client.send(&[]).await.unwrap();
+        client.get_tip_accounts().await.unwrap();
+        let refused = client.get_regions().await.unwrap_err();
+        assert_eq!(refused.kind(), ErrorKind::Auth);
+        let signed = lock(&recorder.0).clone();
+        assert_eq!(
+            signed,
+            [
+                ("/searcher.SearcherService/SendBundle".to_string(), Some(2)),
+                (
+                    "/searcher.SearcherService/GetTipAccounts".to_string(),
+                    Some(0)
+                ),
+                ("/searcher.SearcherService/GetRegions".to_string(), Some(0)),
+            ]
+        );
+        assert_eq!(mock.bundles().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn user_agent_identifies_crate_and_bot() {
+        use crate::builder::USER_AGENT;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        client.send(&[]).await.unwrap();
+        let metadata = mock.last_metadata().unwrap();
+        let agent = metadata.get("user-agent").unwrap().to_str().unwrap();
+        assert!(agent.starts_with(&format!("{USER_AGENT} tonic/")));
+
+        let mut client = JitoClient::builder()
+            .endpoint(mock.endpoint())
+            .user_agent("arb-bot/2.1")
+            .build()
+            .await
+            .unwrap();
+        client.send(&[]).await.unwrap();
+        let metadata = mock.last_metadata().unwrap();
+        let agent = metadata.get("user-agent").unwrap().to_str().unwrap();
+        assert!(agent.starts_with(&format!("{USER_AGENT} arb-bot/2.1 tonic/")));
+
+        let invalid = JitoClient::builder()
+            .endpoint(mock.endpoint())
+            .user_agent("bot\n")
+            .build()
+            .await;
+        assert!(matches!(invalid, Err(JitoClientError::InvalidMetadata(_))));
+    }
+
+    #[tokio::test]
+    async fn slow_attempt_leaves_room_for_a_retry() {
+        use crate::client::RetryLogic;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        mock.inject(MockFault::Delay(Duration::from_secs(2)));
+
+        let start = Instant::now();
+        let retry = RetryLogic::unlimited_until_deadline(start + Duration::from_millis(600));
+        client.send_with_retry(&[], retry).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn timed_out_sends_are_recorded() {
+        use crate::stats::AdaptiveTimeout;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_adaptive_timeout(AdaptiveTimeout {
+                min: Duration::from_millis(1),
+                max: Duration::from_millis(30),
+                ..Default::default()
+            });
+        mock.inject(MockFault::Delay(Duration::from_millis(500)));
+
+        assert!(client.send(&[]).await.is_err());
+        let stats = client.region_stats();
+        assert_eq!(stats.get(mock.endpoint()).unwrap().failures, 1);
+        let recorded = stats.percentile(mock.endpoint(), 1.0, 1).unwrap();
+        assert!(recorded >= Duration::from_millis(30), "{recorded:?}");
+    }
+
+    #[tokio::test]
+    async fn prepared_bundles_are_sent_as_built() {
+        use crate::dedup::BundleDedup;
+        use solana_transaction::versioned::VersionedTransaction;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        let prepared = client
+            .prepare(vec![VersionedTransaction::default(); 2])
+            .unwrap();
+
+        let first = client.send_prepared(&prepared).await.unwrap();
+        let second = client.send_prepared(&prepared).await.unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            mock.bundles(),
+            [prepared.bundle().clone(), prepared.bundle().clone()]
+        );
+
+        let mut client = client.with_dedup(Arc::new(BundleDedup::new(Duration::from_secs(60))));
+        let uuid = client.send_prepared(&prepared).await.unwrap();
+        assert_eq!(client.send_prepared(&prepared).await.unwrap(), uuid);
+        assert_eq!(mock.bundles().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn background_sender_sends_queued_bundles() {
+        use crate::sender::{BackgroundSender, QueueMode, SendQueuePolicy};
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        let sender = BackgroundSender::spawn(
+            client,
+            SendQueuePolicy {
+                capacity: 4,
+                mode: QueueMode::DropOldest,
+            },
+        );
+
+        let first = sender.submit(Vec::new());
+        let second = sender.submit(Vec::new());
+        assert_ne!(
+            first.result().await.unwrap(),
+            second.result().await.unwrap()
+        );
+        assert_eq!(mock.bundles().len(), 2);
+        assert_eq!(sender.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn fanout_reports_every_block_engine() {
+        use crate::fanout::FanoutClient;
+
+        let (first, second) = (
+            MockBlockEngine::start().await.unwrap(),
+            MockBlockEngine::start().await.unwrap(),
+        );
+        let clients = vec![
+            JitoClient::new(first.endpoint(), None).await.unwrap(),
+            JitoClient::new(second.endpoint(), None).await.unwrap(),
+        ];
+        let mut fanout = FanoutClient::new(clients);
+        drop(second);
+
+        let report = fanout.send_all(&[]).await;
+        assert_eq!(report.outcomes.len(), 2);
+        assert_eq!(report.outcomes[0].endpoint, first.endpoint());
+        assert!(report.outcomes[1].result.is_err());
+        assert_eq!(report.first_success, Some(0));
+        assert_eq!(report.accepted().count(), 1);
+        assert!(report.uuid().is_some());
+        assert_eq!(first.bundles().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fanout_submitter_succeeds_if_any_path_does() {
+        use crate::submit::{FanoutSubmitter, Submitter};
+
+        let (up, down) = (
+            MockBlockEngine::start().await.unwrap(),
+            MockBlockEngine::start().await.unwrap(),
+        );
+        let unavailable = JitoClient::new(down.endpoint(), None).await.unwrap();
+        drop(down);
+        let mut fanout = FanoutSubmitter::new(vec![
+            Box::new(unavailable),
+            Box::new(JitoClient::new(up.endpoint(), None).await.unwrap()),
+        ]);
+
+        assert!(fanout.submit(&[]).await.is_ok());
+        assert_eq!(up.bundles().len(), 1);
+        assert!(FanoutSubmitter::new(Vec::new()).submit(&[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn health_reports_channel_state() {
+        use crate::health::ChannelState;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        client.send(&[]).await.unwrap();
+
+        let health = client.health().await;
+        assert!(health.is_healthy());
+        assert!(health.rtt.is_some());
+        assert!(health.last_success.is_some());
+        assert_eq!(health.auth_token_valid(), None);
+
+        let stopped = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(stopped.endpoint(), None).await.unwrap();
+        drop(stopped);
+        let health = client.health().await;
+        assert_eq!(health.state, ChannelState::Unreachable);
+        assert!(health.error.is_some());
+    }
+
+    #[cfg(feature = "latency")]
+    #[tokio::test]
+    async fn warm_regions_fail_over_without_connecting() {
+        #[cfg(feature = "rpc")]
+        use crate::client::SubmitPath;
+        use crate::health::ChannelState;
+        use crate::region::{Network, NodeRegion, RegionOverrides};
+
+        let (ny, fra) = (
+            MockBlockEngine::start().await.unwrap(),
+            MockBlockEngine::start().await.unwrap(),
+        );
+        let overrides = RegionOverrides::new()
+            .endpoint(NodeRegion::NY, ny.endpoint())
+            .endpoint(NodeRegion::FRA, fra.endpoint());
+        let mut client = JitoClient::builder()
+            .network(Network::Devnet)
+            .region_overrides(overrides)
+            .warm_regions(1)
+            .keep_alive(Duration::from_millis(20))
+            .build()
+            .await
+            .unwrap();
+        let state = |client: &JitoClient, endpoint| {
+            client
+                .region_stats()
+                .get(endpoint)
+                .and_then(|stats| stats.channel)
+        };
+        for _ in 0..100 {
+            if state(&client, ny.endpoint()).is_some() && state(&client, fra.endpoint()).is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(state(&client, ny.endpoint()), Some(ChannelState::Ready));
+        assert_eq!(state(&client, fra.endpoint()), Some(ChannelState::Ready));
+
+        let (current, standby) = match client.get_endpoint() == ny.endpoint() {
+            true => (ny, fra),
+            false => (fra, ny),
+        };
+        #[cfg(feature = "rpc")]
+        {
+            client.path = SubmitPath::http_fallback(current.endpoint());
+            client
+                .transactions
+                .get_or_init(|| crate::rpc::SolanaRpc::new(current.endpoint()));
+        }
+        assert_eq!(client.fail_over().unwrap(), standby.endpoint());
+        client.send(&[]).await.unwrap();
+        assert_eq!(standby.bundles().len(), 1);
+        #[cfg(feature = "rpc")]
+        {
+            let SubmitPath::GrpcWithHttpFallback(rpc) = &client.path else {
+                panic!("the fallback is kept");
+            };
+            assert!(rpc.rpc_client().url().starts_with(standby.endpoint()));
+            assert!(client.transactions.get().is_none());
+        }
+
+        let report = client.warm_fanout().send_all(&[]).await;
+        assert_eq!(report.accepted().count(), 2);
+        assert_eq!(current.bundles().len(), 1);
+
+        let stopped = current.endpoint();
+        drop(current);
+        for _ in 0..100 {
+            if state(&client, stopped) == Some(ChannelState::Unreachable) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(matches!(
+            client.fail_over(),
+            Err(JitoClientError::NoWarmRegion)
+        ));
+    }
+
+    #[cfg(feature = "latency")]
+    #[tokio::test]
+    async fn reevaluated_region_switches_when_the_region_stops_answering() {
+        use crate::region::{Network, NodeRegion, RegionOverrides};
+
+        let (ny, fra) = (
+            MockBlockEngine::start().await.unwrap(),
+            MockBlockEngine::start().await.unwrap(),
+        );
+        let overrides = RegionOverrides::new()
+            .endpoint(NodeRegion::NY, ny.endpoint())
+            .endpoint(NodeRegion::FRA, fra.endpoint());
+        let builder = JitoClient::builder()
+            .network(Network::Devnet)
+            .region_overrides(overrides)
+            .reevaluate_region(true);
+        assert!(matches!(
+            builder.clone().build().await,
+            Err(JitoClientError::ConfigError(_))
+        ));
+        let mut client = builder
+            .warm_regions(1)
+            .keep_alive(Duration::from_millis(20))
+            .build()
+            .await
+            .unwrap();
+
+        let (current, standby) = match client.get_endpoint() == ny.endpoint() {
+            true => (ny, fra),
+            false => (fra, ny),
+        };
+        client.send(&[]).await.unwrap();
+        assert_eq!(current.bundles().len(), 1);
+
+        drop(current);
+        let preferred = |client: &JitoClient| {
+            let warm = client.warm.as_ref().unwrap();
+            warm.preferred().map(|channel| channel.endpoint)
+        };
+        for _ in 0..100 {
+            if preferred(&client) == Some(standby.endpoint()) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        client.send(&[]).await.unwrap();
+        assert_eq!(client.get_endpoint(), standby.endpoint());
+        assert_eq!(standby.bundles().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn final_rejections_are_not_retried() {
+        use crate::client::RetryLogic;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        mock.inject(MockFault::Reject(Code::InvalidArgument, "bad".to_string()));
+
+        let (result, attempts) = client
+            .send_with_retry_reported(&[], RetryLogic::new(3))
+            .await;
+        let e = result.unwrap_err();
+        assert_eq!(e.status_code(), Some(Code::InvalidArgument));
+        assert_eq!(e.context().and_then(|context| context.attempt), Some(1));
+        assert_eq!(attempts.len(), 1);
+        assert!(mock.bundles().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tracked_bundle_resolves() {
+        use crate::api::BundleOutcome;
+        use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+        use std::time::Duration;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+
+        let bundle = client.send_and_track(&[]).await.unwrap();
+        mock.push_result(BundleResult {
+            bundle_id: bundle.uuid().to_string(),
+            result: Some(ResultKind::Finalized(Finalized {})),
+        });
+        let outcome = bundle.result(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(outcome, BundleOutcome::Finalized);
+    }
+
+    #[tokio::test]
+    async fn journaled_bundle_resolves_with_its_result() {
+        use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+        use crate::journal::BundleJournal;
+        use std::sync::Arc;
+
+        let path = std::env::temp_dir().join(format!(
+            "jito-journal-client-{}-{:?}.log",
+            std::process::id(),
+            std::time::SystemTime::now()
+        ));
+        let journal = Arc::new(BundleJournal::open(&path).unwrap());
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_journal(journal.clone());
+
+        let bundle = client.send_and_track(&[]).await.unwrap();
+        assert_eq!(journal.unresolved().unwrap().len(), 1);
+        mock.push_result(BundleResult {
+            bundle_id: bundle.uuid().to_string(),
+            result: Some(ResultKind::Finalized(Finalized {})),
+        });
+        bundle.result(Duration::from_secs(5)).await.unwrap();
+        // The result is journaled after its waiters are woken
+        for _ in 0..100 {
+            if journal.unresolved().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let unresolved = journal.unresolved().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(unresolved.is_empty());
+    }
+
+    #[tokio::test]
+    async fn experiment_records_outcomes_per_arm() {
+        use crate::api::BundleOutcome;
+        use crate::experiment::{Arm, RegionExperiment};
+        use crate::grpc::bundle::{Accepted, Finalized, bundle_result::Result as ResultKind};
+
+        let (a, b) = (
+            MockBlockEngine::start().await.unwrap(),
+            MockBlockEngine::start().await.unwrap(),
+        );
+        let mut experiment = RegionExperiment::new(
+            JitoClient::new(a.endpoint(), None).await.unwrap(),
+            JitoClient::new(b.endpoint(), None).await.unwrap(),
+            Duration::from_secs(3600),
+        );
+        assert_eq!(experiment.active_arm(), Arm::A);
+
+        let (arm, landed) = experiment.send(&[]).await.unwrap();
+        assert_eq!(arm, Arm::A);
+        let (_, unresolved) = experiment.send(&[]).await.unwrap();
+        a.inject(MockFault::Reject(Code::Internal, "down".to_string()));
+        assert!(experiment.send(&[]).await.is_err());
+        // Acceptance is followed until the bundle lands
+        for result in [
+            ResultKind::Accepted(Accepted {
+                slot: 1,
+                validator_identity: MOCK_TIP_ACCOUNT.to_string(),
+            }),
+            ResultKind::Finalized(Finalized {}),
+        ] {
+            a.push_result(BundleResult {
+                bundle_id: landed.uuid().to_string(),
+                result: Some(result),
+            });
+        }
+        // The results stay the caller's to claim
+        let outcome = landed.result(Duration::from_secs(5)).await.unwrap();
+        assert!(matches!(outcome, BundleOutcome::Accepted { .. }));
+        assert!(unresolved.result(Duration::from_millis(300)).await.is_err());
+
+        let stats = experiment.stats(Arm::A);
+        assert_eq!(stats.endpoint, a.endpoint());
+        assert_eq!(
+            (
+                stats.outcomes.sent,
+                stats.send_failures,
+                stats.outcomes.landed,
+                stats.unresolved()
+            ),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(stats.land_rate(), Some(1.0));
+        assert_eq!(experiment.stats(Arm::B).outcomes.sent, 0);
+        assert!(b.bundles().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stats_aggregate_outcomes_of_tracked_bundles() {
+        use crate::grpc::bundle::{
+            Dropped, DroppedReason, Processed, bundle_result::Result as ResultKind,
+        };
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        let landed = client.send_and_track(&[]).await.unwrap();
+        // A client sharing the tracker records in the same statistics
+        let mut other = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_tracker(client.tracker().await.unwrap())
+            .unwrap();
+        let dropped = other.send_and_track(&[]).await.unwrap();
+        other.send(&[]).await.unwrap();
+        for (bundle, result) in [
+            (
+                &landed,
+                ResultKind::Processed(Processed {
+                    validator_identity: MOCK_TIP_ACCOUNT.to_string(),
+                    slot: 1,
+                    bundle_index: 0,
+                }),
+            ),
+            (
+                &dropped,
+                ResultKind::Dropped(Dropped {
+                    reason: DroppedReason::BlockhashExpired as i32,
+                }),
+            ),
+        ] {
+            mock.push_result(BundleResult {
+                bundle_id: bundle.uuid().to_string(),
+                result: Some(result),
+            });
+        }
+        dropped.result(Duration::from_secs(1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let summary = client.outcome_stats().summary();
+        let counts = summary.endpoints[mock.endpoint()];
+        assert_eq!(
+            (counts.sent, counts.landed, counts.rejected, counts.dropped),
+            (3, 1, 0, 1)
+        );
+        assert_eq!(summary.land_rate(), Some(0.5));
+        assert_eq!(summary.average_tip, Some(0));
+        assert!(summary.time_to_land_p50.is_some());
+
+        other.outcome_stats().reset();
+        assert_eq!(client.outcome_stats().summary().total.sent, 0);
+    }
+
+    #[tokio::test]
+    async fn clock_skew_is_measured_from_date_headers() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        let skew = client.measure_clock_skew(3).await.unwrap();
+        // The mock shares the local clock, so only the header's resolution separates them
+        assert!(skew.offset_millis().unsigned_abs() <= 1_000);
+        assert!(skew.uncertainty() >= Duration::from_millis(500));
+        assert!(!skew.local_ahead() && !skew.local_behind());
+        assert_eq!(client.clock_skew().estimate(), Some(skew));
+    }
+
+    #[tokio::test]
+    async fn subscriptions_sample_the_clock_skew() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let mut client = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        assert_eq!(client.clock_skew().estimate(), None);
+        client.tracker().await.unwrap();
+        assert!(client.clock_skew().estimate().is_some());
+    }
+
+    #[tokio::test]
+    async fn initial_connect_is_retried() {
+        use crate::client::RetryLogic;
+
+        // A free port nothing listens on yet
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let endpoint: &'static str = Box::leak(format!("http://{address}").into_boxed_str());
+        let timeout = Some(Duration::from_millis(200));
+        let refused = JitoClient::new(endpoint, timeout).await.unwrap_err();
+        assert!(refused.is_retryable());
+
+        let retry_logic = RetryLogic::new_with_wait_bounds(50, 20, 40).unwrap();
+        let client = tokio::spawn(JitoClient::new_with_retry(endpoint, timeout, retry_logic));
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let mock = MockBlockEngine::start_on(address).await.unwrap();
+        let mut client = client.await.unwrap().unwrap();
+        client.send(&[]).await.unwrap();
+        assert_eq!(mock.bundles().len(), 1);
+
+        // Errors that can't go away aren't retried
+        let invalid = JitoClient::new_with_retry("http://", timeout, retry_logic).await;
+        assert!(matches!(
+            invalid,
+            Err(JitoClientError::InvalidEndpoint { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn connections_are_diagnosed_phase_by_phase() {
+        use crate::builder::JitoClientBuilder;
+        use crate::health::diagnose;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let diagnosis = diagnose(mock.endpoint()).await.unwrap();
+        assert_eq!(diagnosis.endpoint, mock.endpoint());
+        assert_eq!(diagnosis.addresses, vec![mock.address().ip()]);
+        assert_eq!(diagnosis.connected_to, mock.address());
+        // The mock is served in plaintext
+        assert_eq!(diagnosis.tls_handshake, None);
+        assert!(diagnosis.total() >= diagnosis.first_rpc);
+        assert!(diagnosis.to_string().contains("first rpc"));
+
+        let unset = JitoClientBuilder::new().diagnose().await;
+        assert!(matches!(unset, Err(JitoClientError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn channel_limits_bound_requests() {
+        use crate::builder::ChannelLimits;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let limits = ChannelLimits {
+            concurrency_limit: Some(1),
+            rate_limit: Some((2, Duration::from_secs(60))),
+            buffer_size: Some(8),
+        };
+        let mut client = JitoClient::builder()
+            .endpoint(mock.endpoint())
+            .channel_limits(limits)
+            .build()
+            .await
+            .unwrap();
+        // The third send waits for the next rate limit period, which doesn't come within the test
+        let sent = client.send_batch(vec![vec![]; 2], 2).await;
+        assert!(sent.iter().all(Result::is_ok));
+        let third = tokio::time::timeout(Duration::from_millis(200), client.send(&[])).await;
+        assert!(third.is_err());
+        assert_eq!(mock.bundles().len(), 2);
+
+        let zero = ChannelLimits {
+            concurrency_limit: Some(0),
+            ..ChannelLimits::default()
+        };
+        let rejected = JitoClient::builder()
+            .endpoint(mock.endpoint())
+            .channel_limits(zero)
+            .build()
+            .await;
+        assert!(matches!(rejected, Err(JitoClientError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn trackers_are_shared_within_a_block_engine() {
+        use crate::api::BundleOutcome;
+        use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+        use std::time::Duration;
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let other = MockBlockEngine::start().await.unwrap();
+        let mut owner = JitoClient::new(mock.endpoint(), None).await.unwrap();
+        let tracker = owner.tracker().await.unwrap();
+        assert_eq!(tracker.endpoint(), Some(mock.endpoint()));
+
+        let mismatched = JitoClient::new(other.endpoint(), None)
+            .await
+            .unwrap()
+            .with_tracker(tracker.clone());
+        assert!(matches!(
+            mismatched,
+            Err(JitoClientError::TrackerMismatch { .. })
+        ));
+
+        let mut shared = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_tracker(tracker.clone())
+            .unwrap();
+        let bundle = shared.send_and_track(&[]).await.unwrap();
+        drop(shared);
+        mock.push_result(BundleResult {
+            bundle_id: bundle.uuid().to_string(),
+            result: Some(ResultKind::Finalized(Finalized {})),
+        });
+        let outcome = bundle.result(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(outcome, BundleOutcome::Finalized);
+    }
+
+    #[tokio::test]
+    async fn sink_receives_submissions_and_results() {
+        use crate::grpc::bundle::{Finalized, bundle_result::Result as ResultKind};
+        use crate::sink::{Submission, SubmissionSink};
+        use futures::FutureExt;
+        use futures::future::BoxFuture;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl SubmissionSink for Recorder {
+            fn on_submitted<'a>(&'a self, submission: &'a Submission<'a>) -> BoxFuture<'a, ()> {
+                let record = match submission.result {
+                    Ok(uuid) => format!("sent {uuid}"),
+                    Err(e) => format!("failed {:?}", e.kind()),
+                };
+                lock(&self.0).push(record);
+                futures::future::ready(()).boxed()
+            }
+
+            fn on_result<'a>(&'a self, result: &'a BundleResult) -> BoxFuture<'a, ()> {
+                lock(&self.0).push(format!("result {}", result.bundle_id));
+                futures::future::ready(()).boxed()
+            }
+        }
+
+        let mock = MockBlockEngine::start().await.unwrap();
+        let recorder = Arc::new(Recorder::default());
+        let mut client = JitoClient::new(mock.endpoint(), None)
+            .await
+            .unwrap()
+            .with_submission_sink(recorder.clone());
+
+        mock.inject(MockFault::Reject(Code::InvalidArgument, "bad".to_string()));
+        assert!(client.send(&[]).await.is_err());
+        let bundle = client.send_and_track(&[]).await.unwrap();
+        mock.push_result(BundleResult {
+            bundle_id: bundle.uuid().to_string(),
+            result: Some(ResultKind::Finalized(Finalized {})),
+        });
+        bundle.result(Duration::from_secs(5)).await.unwrap();
+        // The sink sees the result after its waiter is woken
+        for _ in 0..100 {
+            if lock(&recorder.0).len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            *lock(&recorder.0),
+            [
+                "failed InvalidInput".to_string(),
+                format!("sent {}", bundle.uuid()),
+                format!("result {}", bundle.uuid()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Subscription to Jito's real-time tip stream, a websocket pushing the percentiles of recently landed tips.
+
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::tips::TipFloor;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::io;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+use tonic::codegen::http::Uri;
+
+/// Jito's public tip stream.
+pub const TIP_STREAM_URL: &str = "wss://bundles.jito.wtf/api/v1/bundles/tip_stream";
+
+// Upper bound on the size of a message, to not buffer forever from a misbehaving server
+const MAX_MESSAGE: usize = 1 << 20;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// A subscription to a tip stream, yielding every tip floor update as it is pushed.
+///
+/// Feed the updates into `TipInputs` so a `TipStrategy` reacts to auction pressure immediately, instead of polling the tip floor REST endpoint.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{errors::JitoClientResult, tip_stream::{TIP_STREAM_URL, TipStream}, tips::{TipInputs, TipPercentile, TipStrategy}};
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut stream = TipStream::connect(TIP_STREAM_URL).await?;
+/// let strategy = TipStrategy::FloorPercentile(TipPercentile::P75);
+///
+/// while let Some(tip_floor) = stream.next().await {
+///     let inputs = TipInputs { tip_floor: Some(tip_floor?), ..Default::default() };
+///     println!("Tipping {} lamports", strategy.lamports(&inputs)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct TipStream {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    closed: bool,
+}
+
+// One update of the tip stream, with tips in SOL
+#[derive(Deserialize)]
+struct TipUpdate {
+    landed_tips_25th_percentile: f64,
+    landed_tips_50th_percentile: f64,
+    landed_tips_75th_percentile: f64,
+    landed_tips_95th_percentile: f64,
+    landed_tips_99th_percentile: f64,
+}
+
+impl From<TipUpdate> for TipFloor {
+    fn from(update: TipUpdate) -> Self {
+        let lamports = |sol: f64| (sol * LAMPORTS_PER_SOL).round() as u64;
+        Self {
+            p25: lamports(update.landed_tips_25th_percentile),
+            p50: lamports(update.landed_tips_50th_percentile),
+            p75: lamports(update.landed_tips_75th_percentile),
+            p95: lamports(update.landed_tips_95th_percentile),
+            p99: lamports(update.landed_tips_99th_percentile),
+        }
+    }
+}
+
+impl TipStream {
+    /// Connects to the tip stream at `url`, a `ws://` or `wss://` URL such as `TIP_STREAM_URL`.
+    ///
+    /// # Errors
+    /// This function will return an error if the URL is invalid, or the connection or websocket handshake fails.
+    pub async fn connect(url: &str) -> JitoClientResult<Self> {
+        let uri: Uri = url
+            .parse()
+            .map_err(|_| protocol_error(format!("invalid tip stream URL {url}")))?;
+        let secure = match uri.scheme_str() {
+            Some("wss") => true,
+            Some("ws") => false,
+            _ => return Err(protocol_error(format!("{url} is not a websocket URL"))),
+        };
+        let host = uri
+            .host()
+            .ok_or_else(|| protocol_error(format!("{url} has no host")))?
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+        let tcp = TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(|source| JitoClientError::TCPConnect {
+                region: None,
+                host: host.clone(),
+                source,
+            })?;
+        let _ = tcp.set_nodelay(true);
+        let connector = if secure {
+            Connector::Rustls(tls_config()?)
+        } else {
+            Connector::Plain
+        };
+        let config = WebSocketConfig::default()
+            .max_message_size(Some(MAX_MESSAGE))
+            .max_frame_size(Some(MAX_MESSAGE));
+        let (socket, _) = tokio_tungstenite::client_async_tls_with_config(
+            url,
+            tcp,
+            Some(config),
+            Some(connector),
+        )
+        .await
+        .map_err(stream_error)?;
+        Ok(Self {
+            socket,
+            closed: false,
+        })
+    }
+
+    /// Waits for the next tip floor update.
+    ///
+    /// # Returns
+    /// Returns `None` once the server closed the stream.
+    ///
+    /// # Errors
+    /// An item is an error if the connection fails or an update cannot be parsed. Parse errors leave the stream usable.
+    pub async fn next(&mut self) -> Option<JitoClientResult<TipFloor>> {
+        if self.closed {
+            return None;
+        }
+        // Pings are answered by the socket as it reads
+        loop {
+            match self.socket.next().await {
+                Some(Ok(Message::Text(message))) => return Some(parse_update(message.as_bytes())),
+                Some(Ok(Message::Binary(message))) => return Some(parse_update(&message)),
+                Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => {}
+                Some(Ok(Message::Close(_))) | None => {
+                    // Best effort, the server closes the connection either way
+                    let _ = self.socket.close(None).await;
+                    self.closed = true;
+                    return None;
+                }
+                Some(Err(e)) => {
+                    self.closed = true;
+                    return Some(Err(stream_error(e)));
+                }
+            }
+        }
+    }
+}
+
+fn tls_config() -> JitoClientResult<Arc<rustls::ClientConfig>> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| protocol_error(e.to_string()))?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+// The stream pushes a JSON array holding the latest update
+fn parse_update(message: &[u8]) -> JitoClientResult<TipFloor> {
+    let updates: Vec<TipUpdate> = serde_json::from_slice(message)
+        .map_err(|e| JitoClientError::TipStreamError(invalid_data(e)))?;
+    updates
+        .into_iter()
+        .next_back()
+        .map(TipFloor::from)
+        .ok_or(JitoClientError::MalformedResponse(
+            "empty tip stream update",
+        ))
+}
+
+// Websocket failures, including handshake and framing violations such as a bad accept key or a masked server frame
+fn stream_error(e: tungstenite::Error) -> JitoClientError {
+    JitoClientError::TipStreamError(match e {
+        tungstenite::Error::Io(e) => e,
+        e => invalid_data(e),
+    })
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn protocol_error(message: String) -> JitoClientError {
+    JitoClientError::TipStreamError(invalid_data(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::SinkExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn updates_are_converted_to_lamports() {
+        let message = br#"[{"time":"2024-09-01T12:58:00Z","landed_tips_25th_percentile":6.001000000000001e-6,"landed_tips_50th_percentile":0.00001,"landed_tips_75th_percentile":0.0000360,"landed_tips_95th_percentile":0.0014479,"landed_tips_99th_percentile":0.01,"ema_landed_tips_50th_percentile":0.0000194}]"#;
+        let floor = parse_update(message).unwrap();
+        assert_eq!(floor.p25, 6_001);
+        assert_eq!(floor.p50, 10_000);
+        assert_eq!(floor.p95, 1_447_900);
+        assert_eq!(floor.p99, 10_000_000);
+        assert!(parse_update(b"[]").is_err());
+    }
+
+    #[tokio::test]
+    async fn stream_reads_updates_and_answers_pings() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/tips", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let update = r#"[{"landed_tips_25th_percentile":0.000001,"landed_tips_50th_percentile":0.000002,"landed_tips_75th_percentile":0.000003,"landed_tips_95th_percentile":0.000004,"landed_tips_99th_percentile":0.000005}]"#;
+            socket.send(Message::text(update)).await.unwrap();
+            socket.send(Message::Ping("ping".into())).await.unwrap();
+            let pong = socket.next().await.unwrap().unwrap();
+            assert_eq!(pong, Message::Pong("ping".into()));
+            socket.close(None).await.unwrap();
+        });
+
+        let mut stream = TipStream::connect(&url).await.unwrap();
+        let floor = stream.next().await.unwrap().unwrap();
+        assert_eq!(floor.p50, 2_000);
+        assert_eq!(floor.p99, 5_000);
+        assert!(stream.next().await.is_none());
+        server.await.unwrap();
+    }
+
+    // Serves one handshake answered with the accept key derived by `accept`, then writes `frame`
+    async fn raw_server(accept: fn(&[u8]) -> String, frame: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/tips", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(socket.read_u8().await.unwrap());
+            }
+            let request = String::from_utf8(request).unwrap();
+            let key = request
+                .lines()
+                .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+                .unwrap();
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept(key.as_bytes())
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.write_all(frame).await.unwrap();
+            let _ = socket.read_u8().await;
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn protocol_violations_are_rejected() {
+        let wrong_key = raw_server(|_| "d3Jvbmcga2V5".to_string(), b"").await;
+        assert!(TipStream::connect(&wrong_key).await.is_err());
+
+        // A text frame of "[]", masked with a zero mask, and one with the first reserved bit set
+        let accept = tungstenite::handshake::derive_accept_key;
+        for frame in [
+            &[0x81, 0x82, 0, 0, 0, 0, b'[', b']'][..],
+            &[0x81 | 0x40, 0x02, b'[', b']'][..],
+        ] {
+            let url = raw_server(accept, frame).await;
+            let mut stream = TipStream::connect(&url).await.unwrap();
+            assert!(matches!(
+                stream.next().await,
+                Some(Err(JitoClientError::TipStreamError(_)))
+            ));
+            assert!(stream.next().await.is_none());
+        }
+    }
+}
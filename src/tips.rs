@@ -0,0 +1,490 @@
+use crate::api::TipAccounts;
+use crate::errors::{ErrorKind, JitoClientError, JitoClientResult};
+use crate::grpc::searcher::GetTipAccountsRequest;
+use crate::random;
+#[cfg(feature = "rpc")]
+use crate::rpc::SolanaRpc;
+use crate::signing::SearcherClient;
+use crate::stats::BundleStats;
+#[cfg(feature = "rpc")]
+use solana_keypair::{Keypair, Signer};
+use solana_pubkey::Pubkey;
+use solana_transaction::versioned::VersionedTransaction;
+#[cfg(feature = "rpc")]
+use solana_transaction::{Hash, Message, VersionedMessage};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Default time tip accounts are cached before they are fetched again.
+pub const DEFAULT_TIP_ACCOUNTS_TTL: Duration = Duration::from_secs(300);
+
+// Mainnet tip accounts at the time of release, the fallback when the block engine cannot be asked
+const KNOWN_TIP_ACCOUNTS: [Pubkey; 8] = [
+    Pubkey::from_str_const("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    Pubkey::from_str_const("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    Pubkey::from_str_const("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    Pubkey::from_str_const("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    Pubkey::from_str_const("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    Pubkey::from_str_const("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    Pubkey::from_str_const("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    Pubkey::from_str_const("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// Returns the well-known mainnet tip accounts compiled into the crate.
+///
+/// `TipAccountProvider` falls back to these when `GetTipAccounts` is unavailable, so tipping works offline or during block engine outages.
+/// Prefer the accounts returned by the block engine otherwise, in case they changed since this release.
+pub fn known_tip_accounts() -> TipAccounts {
+    TipAccounts(KNOWN_TIP_ACCOUNTS.to_vec())
+}
+
+// System program, whose transfers pay tips
+const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::from_str_const("11111111111111111111111111111111");
+// Instruction discriminant of a system transfer
+const SYSTEM_TRANSFER: u32 = 2;
+
+/// Returns the lamports the transactions transfer to any of `tip_accounts` with system transfers.
+///
+/// Only accounts referenced directly by the transactions are seen, not those loaded from address lookup tables, which tip accounts shouldn't be.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::tips::{known_tip_accounts, tip_lamports};
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// let transactions: Vec<VersionedTransaction> = vec![];
+/// assert_eq!(tip_lamports(&transactions, &known_tip_accounts().0), 0);
+/// ```
+pub fn tip_lamports(transactions: &[VersionedTransaction], tip_accounts: &[Pubkey]) -> u64 {
+    let mut lamports = 0u64;
+    for txn in transactions {
+        let keys = txn.message.static_account_keys();
+        for instruction in txn.message.instructions() {
+            if keys.get(instruction.program_id_index as usize) != Some(&SYSTEM_PROGRAM_ID) {
+                continue;
+            }
+            let Some((discriminant, amount)) = instruction.data.split_at_checked(4) else {
+                continue;
+            };
+            let recipient = instruction
+                .accounts
+                .get(1)
+                .and_then(|index| keys.get(*index as usize));
+            if discriminant == SYSTEM_TRANSFER.to_le_bytes()
+                && let Ok(amount) = <[u8; 8]>::try_from(amount)
+                && recipient.is_some_and(|recipient| tip_accounts.contains(recipient))
+            {
+                lamports = lamports.saturating_add(u64::from_le_bytes(amount));
+            }
+        }
+    }
+    lamports
+}
+
+/// Caches the block engine's tip accounts, so tipping a bundle doesn't cost a `GetTipAccounts` request every time.
+///
+/// The accounts are fetched on first use and again once they are older than the TTL. Concurrent callers wait for a single fetch.
+/// If the block engine is unreachable, `known_tip_accounts` are used instead until the TTL passes.
+/// Created with `JitoClient::tip_account_provider`, sharing the client's connection.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult};
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let client = JitoClient::new_dynamic_region(None).await?;
+/// let tips = client.tip_account_provider(Duration::from_secs(60));
+///
+/// // Spread tips over the accounts to avoid write lock contention between bundles
+/// let tip_account = tips.random().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TipAccountProvider {
+    client: SearcherClient,
+    ttl: Duration,
+    cached: Mutex<Option<(Instant, TipAccounts)>>,
+    next: AtomicUsize,
+    // Statistics of the client the provider was created by, told the fetched accounts to count tips with
+    stats: Option<BundleStats>,
+}
+
+impl TipAccountProvider {
+    pub(crate) fn new(client: SearcherClient, ttl: Duration) -> Self {
+        Self {
+            client,
+            ttl,
+            cached: Mutex::new(None),
+            next: AtomicUsize::new(0),
+            stats: None,
+        }
+    }
+
+    pub(crate) fn with_stats(mut self, stats: BundleStats) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    /// Returns the tip accounts, fetching them from the block engine if the cached ones expired.
+    ///
+    /// Falls back to `known_tip_accounts` if the block engine cannot be reached or doesn't answer in time.
+    ///
+    /// # Errors
+    /// This function will return an error if the accounts need to be fetched and the block engine rejects the request or returns an invalid pubkey.
+    pub async fn accounts(&self) -> JitoClientResult<TipAccounts> {
+        let mut cached = self.cached.lock().await;
+        if let Some((fetched, accounts)) = cached.as_ref()
+            && fetched.elapsed() < self.ttl
+        {
+            return Ok(accounts.clone());
+        }
+        let accounts = match self.fetch().await {
+            Err(e) if matches!(e.kind(), ErrorKind::Network | ErrorKind::Timeout) => {
+                debug!("Tip accounts unavailable, using known accounts: {e}");
+                known_tip_accounts()
+            }
+            result => result?,
+        };
+        if let Some(stats) = &self.stats {
+            stats.set_tip_accounts(&accounts);
+        }
+        *cached = Some((Instant::now(), accounts.clone()));
+        Ok(accounts)
+    }
+
+    async fn fetch(&self) -> JitoClientResult<TipAccounts> {
+        let response = self
+            .client
+            .clone()
+            .get_tip_accounts(GetTipAccountsRequest {})
+            .await?;
+        TipAccounts::try_from(response.into_inner())
+    }
+
+    /// Returns a tip account picked at random.
+    ///
+    /// # Errors
+    /// This function will return an error if the accounts cannot be fetched, or the block engine returned none.
+    pub async fn random(&self) -> JitoClientResult<Pubkey> {
+        let accounts = self.accounts().await?;
+        Self::pick(&accounts, random::index(accounts.0.len()))
+    }
+
+    /// Returns the tip accounts in turn, starting over after the last one.
+    ///
+    /// # Errors
+    /// This function will return an error if the accounts cannot be fetched, or the block engine returned none.
+    pub async fn round_robin(&self) -> JitoClientResult<Pubkey> {
+        let accounts = self.accounts().await?;
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        Self::pick(&accounts, next % accounts.0.len().max(1))
+    }
+
+    /// Discards the cached accounts, so the next call fetches them again.
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+
+    fn pick(accounts: &TipAccounts, index: usize) -> JitoClientResult<Pubkey> {
+        accounts
+            .0
+            .get(index)
+            .copied()
+            .ok_or(JitoClientError::NoTipAccounts)
+    }
+}
+
+/// Recent landed tip percentiles in lamports, such as those published by Jito's tip floor API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TipFloor {
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p95: u64,
+    pub p99: u64,
+}
+
+/// Percentile of recently landed tips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipPercentile {
+    P25,
+    P50,
+    P75,
+    P95,
+    P99,
+}
+
+impl TipFloor {
+    /// Returns the tip at `percentile`.
+    pub fn at(&self, percentile: TipPercentile) -> u64 {
+        match percentile {
+            TipPercentile::P25 => self.p25,
+            TipPercentile::P50 => self.p50,
+            TipPercentile::P75 => self.p75,
+            TipPercentile::P95 => self.p95,
+            TipPercentile::P99 => self.p99,
+        }
+    }
+}
+
+/// Market data a `TipStrategy` sizes tips from. Only the inputs the strategy uses need to be set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TipInputs {
+    /// Recent landed tip percentiles
+    pub tip_floor: Option<TipFloor>,
+    /// Profit the bundle is expected to make in lamports
+    pub expected_profit: Option<u64>,
+}
+
+/// How much a bundle tips, declared once and applied to every bundle.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::tips::{TipInputs, TipStrategy};
+/// let strategy = TipStrategy::ProfitShare { basis_points: 5_000, min: 1_000, max: 1_000_000 };
+/// let inputs = TipInputs { expected_profit: Some(50_000), ..Default::default() };
+/// assert_eq!(strategy.lamports(&inputs).unwrap(), 25_000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipStrategy {
+    /// Tips a fixed amount of lamports
+    Fixed(u64),
+    /// Tips a percentile of recently landed tips, outbidding that share of recent bundles
+    FloorPercentile(TipPercentile),
+    /// Tips a share of the expected profit in basis points, clamped to `min..=max` lamports
+    ProfitShare {
+        basis_points: u16,
+        min: u64,
+        max: u64,
+    },
+}
+
+impl TipStrategy {
+    /// Returns the tip in lamports.
+    ///
+    /// # Errors
+    /// This function will return `MissingTipInput` if the input the strategy sizes the tip from is not set.
+    pub fn lamports(&self, inputs: &TipInputs) -> JitoClientResult<u64> {
+        match *self {
+            Self::Fixed(lamports) => Ok(lamports),
+            Self::FloorPercentile(percentile) => inputs
+                .tip_floor
+                .map(|floor| floor.at(percentile))
+                .ok_or(JitoClientError::MissingTipInput("a tip floor")),
+            Self::ProfitShare {
+                basis_points,
+                min,
+                max,
+            } => {
+                let profit = inputs
+                    .expected_profit
+                    .ok_or(JitoClientError::MissingTipInput("an expected profit"))?;
+                let share = profit as u128 * basis_points as u128 / 10_000;
+                Ok((share as u64).clamp(min, max.max(min)))
+            }
+        }
+    }
+}
+
+/// Builds signed tip transactions, fetching a recent blockhash and picking a tip account for each.
+///
+/// The tip is a standalone transaction transferring lamports to a tip account, meant to be the bundle's last transaction,
+/// so it only pays if every other transaction lands.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, rpc::SolanaRpc, tips::TipTransaction};
+/// # use solana_keypair::Keypair;
+/// # use solana_transaction::versioned::VersionedTransaction;
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let mut client = JitoClient::new_dynamic_region(None).await?;
+/// let tips = TipTransaction::new(
+///     SolanaRpc::new("https://api.mainnet-beta.solana.com"),
+///     client.tip_account_provider(Duration::from_secs(60)),
+/// );
+/// let payer = Keypair::new();
+///
+/// let mut transactions: Vec<VersionedTransaction> = vec![];
+/// transactions.push(tips.build(&payer, 10_000).await?);
+/// client.send(&transactions).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "rpc")]
+pub struct TipTransaction {
+    rpc: SolanaRpc,
+    tips: TipAccountProvider,
+}
+
+#[cfg(feature = "rpc")]
+impl TipTransaction {
+    pub fn new(rpc: SolanaRpc, tips: TipAccountProvider) -> Self {
+        Self { rpc, tips }
+    }
+
+    /// Returns the tip account provider the tip accounts are picked from.
+    pub fn tips(&self) -> &TipAccountProvider {
+        &self.tips
+    }
+
+    /// Builds and signs a transaction transferring `lamports` from `payer` to a tip account picked at random.
+    ///
+    /// The blockhash and tip accounts are fetched concurrently, the tip accounts only once they are no longer cached.
+    ///
+    /// # Errors
+    /// This function will return an error if the blockhash or tip accounts cannot be fetched, or the transaction cannot be signed.
+    pub async fn build(
+        &self,
+        payer: &Keypair,
+        lamports: u64,
+    ) -> JitoClientResult<VersionedTransaction> {
+        let (blockhash, tip_account) =
+            futures::try_join!(self.rpc.latest_blockhash(), self.tips.random())?;
+        Self::sign(payer, &tip_account, lamports, blockhash)
+    }
+
+    /// Builds and signs a tip transaction like `build`, sizing the tip with `strategy`.
+    ///
+    /// # Errors
+    /// This function will return an error if the strategy's inputs are missing, or `build` fails.
+    pub async fn build_with(
+        &self,
+        payer: &Keypair,
+        strategy: &TipStrategy,
+        inputs: &TipInputs,
+    ) -> JitoClientResult<VersionedTransaction> {
+        self.build(payer, strategy.lamports(inputs)?).await
+    }
+
+    /// Signs a transaction transferring `lamports` from `payer` to `tip_account`, with a blockhash fetched by the caller.
+    ///
+    /// # Errors
+    /// This function will return an error if the transaction cannot be signed.
+    pub fn sign(
+        payer: &Keypair,
+        tip_account: &Pubkey,
+        lamports: u64,
+        blockhash: Hash,
+    ) -> JitoClientResult<VersionedTransaction> {
+        let transfer =
+            solana_system_interface::instruction::transfer(&payer.pubkey(), tip_account, lamports);
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[transfer],
+            Some(&payer.pubkey()),
+            &blockhash,
+        ));
+        Ok(VersionedTransaction::try_new(message, &[payer])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::SignerInterceptor;
+    use tonic::transport::Channel;
+
+    // A provider whose connection is never used, with the given accounts cached
+    async fn cached(accounts: Vec<Pubkey>, ttl: Duration) -> TipAccountProvider {
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let provider = TipAccountProvider::new(SignerInterceptor::default().client(channel), ttl);
+        *provider.cached.lock().await = Some((Instant::now(), TipAccounts(accounts)));
+        provider
+    }
+
+    #[tokio::test]
+    async fn cached_accounts_are_rotated() {
+        let accounts = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let provider = cached(accounts.clone(), Duration::from_secs(60)).await;
+
+        assert_eq!(provider.round_robin().await.unwrap(), accounts[0]);
+        assert_eq!(provider.round_robin().await.unwrap(), accounts[1]);
+        assert_eq!(provider.round_robin().await.unwrap(), accounts[0]);
+        assert!(accounts.contains(&provider.random().await.unwrap()));
+
+        let empty = cached(Vec::new(), Duration::from_secs(60)).await;
+        assert!(matches!(
+            empty.random().await,
+            Err(JitoClientError::NoTipAccounts)
+        ));
+    }
+
+    #[tokio::test]
+    async fn expired_accounts_are_refetched() {
+        // The refetch cannot connect, so the known accounts are used
+        let provider = cached(vec![Pubkey::new_unique()], Duration::ZERO).await;
+        assert_eq!(provider.accounts().await.unwrap(), known_tip_accounts());
+    }
+
+    #[test]
+    fn strategies_size_tips() {
+        let floor = TipFloor {
+            p25: 1_000,
+            p50: 2_000,
+            p75: 5_000,
+            p95: 20_000,
+            p99: 100_000,
+        };
+        let inputs = TipInputs {
+            tip_floor: Some(floor),
+            expected_profit: Some(1_000_000),
+        };
+        let share = |basis_points, min, max| TipStrategy::ProfitShare {
+            basis_points,
+            min,
+            max,
+        };
+
+        assert_eq!(TipStrategy::Fixed(10).lamports(&inputs).unwrap(), 10);
+        let p75 = TipStrategy::FloorPercentile(TipPercentile::P75);
+        assert_eq!(p75.lamports(&inputs).unwrap(), 5_000);
+        assert_eq!(
+            share(1_000, 0, u64::MAX).lamports(&inputs).unwrap(),
+            100_000
+        );
+        assert_eq!(share(1_000, 0, 50_000).lamports(&inputs).unwrap(), 50_000);
+        assert_eq!(share(1, 2_000, 50_000).lamports(&inputs).unwrap(), 2_000);
+        assert!(matches!(
+            p75.lamports(&TipInputs::default()),
+            Err(JitoClientError::MissingTipInput(_))
+        ));
+    }
+
+    #[test]
+    fn tips_are_summed_over_transfers_to_tip_accounts() {
+        use solana_keypair::{Keypair, Signer};
+        use solana_system_interface::instruction::transfer;
+        use solana_transaction::{Message, VersionedMessage};
+
+        let payer = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let instructions = [
+            transfer(&payer.pubkey(), &tip_account, 1_000),
+            transfer(&payer.pubkey(), &Pubkey::new_unique(), 50_000),
+            transfer(&payer.pubkey(), &tip_account, 500),
+        ];
+        let message = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer.pubkey())));
+        let txn = VersionedTransaction::try_new(message, &[&payer]).unwrap();
+
+        assert_eq!(
+            tip_lamports(&[txn.clone(), txn.clone()], &[tip_account]),
+            3_000
+        );
+        assert_eq!(tip_lamports(&[txn], &known_tip_accounts().0), 0);
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn tip_transaction_transfers_to_tip_account() {
+        let payer = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let txn = TipTransaction::sign(&payer, &tip_account, 10_000, Hash::default()).unwrap();
+
+        assert_eq!(txn.signatures.len(), 1);
+        assert_eq!(
+            txn.message.static_account_keys()[..2],
+            [payer.pubkey(), tip_account]
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use crate::api::BundleOutcome;
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::grpc::bundle::BundleResult;
+use crate::journal::BundleJournal;
+use crate::sink::SubmissionSink;
+use crate::stats::BundleStats;
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time;
+
+// Results for bundles nobody is waiting on yet are kept this long, since a result can arrive before the send returns its uuid
+const UNCLAIMED_TTL: Duration = Duration::from_secs(60);
+
+/// Shares one bundle result subscription across many concurrent waiters, routing each result to whoever awaits its bundle id.
+///
+/// Wrap in an `Arc` to share between tasks. The background reader stops when the tracker is closed or dropped.
+///
+/// Results are only streamed for bundles sent to the block engine the subscription is open on, so the tracker must subscribe to the same
+/// block engine the bundles are sent to. `JitoClient::tracker` subscribes over the client's own channel, and records its endpoint.
+pub struct BundleTracker {
+    state: Arc<Mutex<TrackerState>>,
+    task: JoinHandle<()>,
+    endpoint: Option<&'static str>,
+    stats: Option<BundleStats>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    waiters: HashMap<String, Vec<oneshot::Sender<BundleResult>>>,
+    unclaimed: HashMap<String, (Instant, BundleResult)>,
+    closed: bool,
+}
+
+impl BundleTracker {
+    /// Starts tracking results from a bundle result stream, such as the one returned by `JitoClient::subscribe_bundle_results`.
+    ///
+    /// Stream errors are logged and skipped. Once the stream ends, all pending and future waits fail with `TrackerClosed`.
+    pub fn new<S, E>(results: S) -> Self
+    where
+        S: Stream<Item = Result<BundleResult, E>> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        Self::start(results, None, None, None)
+    }
+
+    /// Starts tracking results like `new`, handing a copy of every result to `sink` once its waiters are woken.
+    pub fn with_sink<S, E>(results: S, sink: Arc<dyn SubmissionSink>) -> Self
+    where
+        S: Stream<Item = Result<BundleResult, E>> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        Self::start(results, Some(sink), None, None)
+    }
+
+    // Starts the reader, recording every result in `stats` and `journal` once its waiters are woken
+    pub(crate) fn start<S, E>(
+        results: S,
+        sink: Option<Arc<dyn SubmissionSink>>,
+        stats: Option<BundleStats>,
+        journal: Option<Arc<BundleJournal>>,
+    ) -> Self
+    where
+        S: Stream<Item = Result<BundleResult, E>> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        let state = Arc::new(Mutex::new(TrackerState::default()));
+        let reader_state = state.clone();
+        let reader_stats = stats.clone();
+        let task = tokio::spawn(async move {
+            futures::pin_mut!(results);
+            loop {
+                let result = match results.next().await {
+                    Some(Ok(result)) => result,
+                    None => break,
+                    Some(Err(e)) => {
+                        debug!("Bundle result stream error: {e}");
+                        continue;
+                    }
+                };
+                lock(&reader_state).dispatch(&result);
+                if let Some(stats) = &reader_stats {
+                    stats.record_result(&result);
+                }
+                if let Some(journal) = &journal
+                    && let Err(e) = journal.record_result(&result)
+                {
+                    debug!("Journal result record error: {e}");
+                }
+                if let Some(sink) = &sink {
+                    sink.on_result(&result).await;
+                }
+            }
+            let mut state = lock(&reader_state);
+            state.closed = true;
+            state.waiters.clear();
+        });
+        Self {
+            state,
+            task,
+            endpoint: None,
+            stats,
+        }
+    }
+
+    // Records the block engine the results are streamed from
+    pub(crate) fn on_endpoint(mut self, endpoint: &'static str) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    // The statistics results are recorded in, if the tracker was created by `JitoClient::tracker`
+    pub(crate) fn stats(&self) -> Option<&BundleStats> {
+        self.stats.as_ref()
+    }
+
+    /// Returns the endpoint of the block engine the results are streamed from, if the tracker was created by `JitoClient::tracker`.
+    pub fn endpoint(&self) -> Option<&'static str> {
+        self.endpoint
+    }
+
+    /// Waits for the first result of the given bundle.
+    ///
+    /// # Arguments
+    /// * `bundle_id` - The uuid returned when the bundle was sent
+    /// * `timeout` - Maximum time to wait for the result
+    ///
+    /// # Errors
+    /// This function will return an error if:
+    /// - No result arrives within the timeout
+    /// - The result stream ended
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use jito_grpc_client::{client::JitoClient, errors::JitoClientResult, tracker::BundleTracker};
+    /// # use solana_transaction::versioned::VersionedTransaction;
+    /// # use std::time::Duration;
+    /// # async fn example() -> JitoClientResult<()> {
+    /// let mut client = JitoClient::new_dynamic_region(None).await?;
+    /// let tracker = BundleTracker::new(client.subscribe_bundle_results().await?);
+    ///
+    /// let transactions: Vec<VersionedTransaction> = vec![];
+    /// let uuid = client.send(&transactions).await?;
+    /// let result = tracker.await_result(&uuid, Duration::from_secs(5)).await?;
+    /// println!("Bundle result: {:?}", result.result);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn await_result(
+        &self,
+        bundle_id: &str,
+        timeout: Duration,
+    ) -> JitoClientResult<BundleResult> {
+        let receiver = {
+            let mut state = lock(&self.state);
+            if let Some((_, result)) = state.unclaimed.remove(bundle_id) {
+                return Ok(result);
+            }
+            if state.closed {
+                return Err(JitoClientError::TrackerClosed);
+            }
+            let (sender, receiver) = oneshot::channel();
+            state
+                .waiters
+                .entry(bundle_id.to_string())
+                .or_default()
+                .push(sender);
+            receiver
+        };
+
+        match time::timeout(timeout, receiver).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(JitoClientError::TrackerClosed),
+            Err(_) => {
+                let mut state = lock(&self.state);
+                if let Some(waiters) = state.waiters.get_mut(bundle_id) {
+                    waiters.retain(|w| !w.is_closed());
+                    if waiters.is_empty() {
+                        state.waiters.remove(bundle_id);
+                    }
+                }
+                Err(JitoClientError::ResultTimeout)
+            }
+        }
+    }
+
+    /// Stops the background reader. Pending and future waits fail with `TrackerClosed`.
+    pub fn close(&self) {
+        self.task.abort();
+        let mut state = lock(&self.state);
+        state.closed = true;
+        state.waiters.clear();
+    }
+}
+
+/// A sent bundle whose result can be awaited, returned by `JitoClient::send_and_track`.
+///
+/// Cheap to clone, and independent of the client, so many bundles can be awaited concurrently from different tasks.
+#[derive(Clone)]
+pub struct TrackedBundle {
+    uuid: String,
+    tracker: Arc<BundleTracker>,
+}
+
+impl TrackedBundle {
+    pub(crate) fn new(uuid: String, tracker: Arc<BundleTracker>) -> Self {
+        Self { uuid, tracker }
+    }
+
+    /// Returns the bundle uuid.
+    pub fn uuid(&self) -> &str {
+        &self.uuid
+    }
+
+    /// Waits up to `timeout` for the first result of the bundle, decoded into its outcome.
+    ///
+    /// # Errors
+    /// This function will return an error if no result arrives within the timeout, the result stream ended, or the result is malformed.
+    pub async fn result(&self, timeout: Duration) -> JitoClientResult<BundleOutcome> {
+        self.tracker
+            .await_result(&self.uuid, timeout)
+            .await?
+            .outcome()
+    }
+}
+
+impl TrackerState {
+    // Hands the result to everyone waiting on its bundle, or keeps it for a later wait if nobody is
+    fn dispatch(&mut self, result: &BundleResult) {
+        if let Some(waiters) = self.waiters.remove(&result.bundle_id) {
+            for waiter in waiters {
+                let _ = waiter.send(result.clone());
+            }
+            return;
+        }
+
+        let now = Instant::now();
+        self.unclaimed
+            .retain(|_, (received, _)| now.duration_since(*received) < UNCLAIMED_TTL);
+        self.unclaimed
+            .entry(result.bundle_id.clone())
+            .or_insert_with(|| (now, result.clone()));
+    }
+}
+
+impl Drop for BundleTracker {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn lock(state: &Mutex<TrackerState>) -> MutexGuard<'_, TrackerState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    fn result(bundle_id: &str) -> Result<BundleResult, String> {
+        Ok(BundleResult {
+            bundle_id: bundle_id.to_string(),
+            result: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn results_are_routed_by_id() {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let tracker = BundleTracker::new(receiver);
+
+        let waiting = tracker.await_result("b", Duration::from_secs(1));
+        futures::pin_mut!(waiting);
+        assert!(futures::poll!(&mut waiting).is_pending());
+        sender.unbounded_send(result("a")).unwrap();
+        sender.unbounded_send(result("b")).unwrap();
+        assert_eq!(waiting.await.unwrap().bundle_id, "b");
+
+        // Results that arrived before anyone waited are kept
+        let early = tracker.await_result("a", Duration::from_secs(1)).await;
+        assert_eq!(early.unwrap().bundle_id, "a");
+    }
+
+    #[tokio::test]
+    async fn missing_result_times_out() {
+        let tracker = BundleTracker::new(stream::pending::<Result<BundleResult, String>>());
+        let outcome = tracker.await_result("a", Duration::from_millis(10)).await;
+        assert!(matches!(outcome, Err(JitoClientError::ResultTimeout)));
+    }
+
+    #[tokio::test]
+    async fn close_fails_pending_waits() {
+        let tracker = BundleTracker::new(stream::pending::<Result<BundleResult, String>>());
+        let waiting = tracker.await_result("a", Duration::from_secs(1));
+        futures::pin_mut!(waiting);
+        assert!(futures::poll!(&mut waiting).is_pending());
+
+        tracker.close();
+        assert!(matches!(waiting.await, Err(JitoClientError::TrackerClosed)));
+    }
+
+    #[tokio::test]
+    async fn ended_stream_closes_tracker() {
+        let tracker = BundleTracker::new(stream::empty::<Result<BundleResult, String>>());
+        let outcome = tracker.await_result("a", Duration::from_secs(1)).await;
+        assert!(matches!(outcome, Err(JitoClientError::TrackerClosed)));
+    }
+}
@@ -0,0 +1,456 @@
+use crate::connector::{IpPreference, Proxy, ProxyConnector, ResolvingConnector};
+use crate::errors::{JitoClientError, JitoClientResult};
+use crate::resolver::Resolver;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tonic::codegen::http::Uri;
+use tonic::transport::{Certificate, Channel, Endpoint, channel::ClientTlsConfig};
+
+// Timeout of connections and requests, unless set otherwise
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// `user-agent` sent by every client, naming the crate and its version. Extended with `JitoClientBuilder::user_agent`.
+pub const USER_AGENT: &str = concat!("jito-grpc-client/", env!("CARGO_PKG_VERSION"));
+
+/// The root certificates used to verify the block engine's TLS certificate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TlsRoots {
+    /// The operating system's certificate store
+    #[default]
+    Native,
+    /// The Mozilla root certificates bundled into the binary, for scratch or distroless containers without a CA store
+    WebPki,
+    /// Only the CA certificates added with `JitoClientBuilder::ca_certificate`
+    Custom,
+}
+
+/// Bounds on the resources a client's channel consumes, applied with `JitoClientBuilder::channel_limits` or `ConnectOptions::channel_limits`.
+///
+/// Useful when many clients run in one process. Each limit applies per channel, and is unset by default, leaving tonic's defaults.
+///
+/// Requests wait for the limits inside the channel, where the client can't tell the wait apart from the round trip, so the round trips recorded
+/// in `LatencyStats`, and an `AdaptiveTimeout` derived from them, include time spent queued. A request's deadline only starts once it leaves the queue.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{transport::ChannelLimits, client::JitoClient, errors::JitoClientResult};
+/// # use std::time::Duration;
+/// # async fn example() -> JitoClientResult<()> {
+/// let limits = ChannelLimits {
+///     concurrency_limit: Some(16),
+///     rate_limit: Some((100, Duration::from_secs(1))),
+///     buffer_size: Some(64),
+/// };
+/// let client = JitoClient::builder().channel_limits(limits).build().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelLimits {
+    /// Requests in flight at once on the channel, further requests waiting for one to finish. See `Endpoint::concurrency_limit`
+    pub concurrency_limit: Option<usize>,
+    /// Requests allowed per period, further requests waiting for the next period. See `Endpoint::rate_limit`
+    pub rate_limit: Option<(u64, Duration)>,
+    /// Requests buffered before the channel's worker, callers waiting once it is full. Defaults to 1024 in tonic. See `Endpoint::buffer_size`
+    pub buffer_size: Option<usize>,
+}
+
+impl ChannelLimits {
+    // Rejects limits of zero, which would block every request or fail to build the channel
+    pub(crate) fn validate(&self) -> JitoClientResult<()> {
+        let invalid = |limit: &str| {
+            Err(JitoClientError::ConfigError(format!(
+                "channel {limit} must be above 0"
+            )))
+        };
+        if self.concurrency_limit == Some(0) {
+            return invalid("concurrency limit");
+        }
+        if let Some((limit, period)) = self.rate_limit
+            && (limit == 0 || period.is_zero())
+        {
+            return invalid("rate limit and its period");
+        }
+        if self.buffer_size == Some(0) {
+            return invalid("buffer size");
+        }
+        Ok(())
+    }
+}
+
+/// Validates a block engine endpoint URL and completes it with the defaults it leaves out: the `https` scheme, and the scheme's port, 443 for https and 80 for http.
+///
+/// # Returns
+/// Returns the endpoint as `scheme://host:port`, such as `https://ny.mainnet.block-engine.jito.wtf:443` for `ny.mainnet.block-engine.jito.wtf`.
+///
+/// # Errors
+/// This function will return an `InvalidEndpoint` error if the endpoint is empty, isn't a valid URL, has no host, has a scheme other than http or https,
+/// or has a path or query, which gRPC endpoints don't take.
+///
+/// # Examples
+/// ```
+/// # use jito_grpc_client::transport::normalize_endpoint;
+/// assert_eq!(normalize_endpoint("ny.mainnet.block-engine.jito.wtf").unwrap(), "https://ny.mainnet.block-engine.jito.wtf:443");
+/// assert_eq!(normalize_endpoint("http://127.0.0.1:1234").unwrap(), "http://127.0.0.1:1234");
+/// assert!(normalize_endpoint("https://ny.mainnet.block-engine.jito.wtf/api/v1/bundles").is_err());
+/// ```
+pub fn normalize_endpoint(endpoint: &str) -> JitoClientResult<String> {
+    let invalid = |reason| JitoClientError::InvalidEndpoint {
+        endpoint: endpoint.to_string(),
+        reason,
+    };
+    let trimmed = endpoint.trim();
+    if trimmed.is_empty() {
+        return Err(invalid("empty"));
+    }
+    let uri = if trimmed.contains("://") {
+        trimmed.parse::<Uri>()
+    } else {
+        format!("https://{trimmed}").parse::<Uri>()
+    }
+    .map_err(|_| invalid("not a valid URL"))?;
+
+    let default_port = match uri.scheme_str() {
+        Some("https") => 443,
+        Some("http") => 80,
+        _ => return Err(invalid("unsupported scheme, expected http or https")),
+    };
+    let host = match uri.host() {
+        Some(host) if !host.is_empty() => host,
+        _ => return Err(invalid("missing host")),
+    };
+    if !matches!(uri.path(), "" | "/") || uri.query().is_some() {
+        return Err(invalid("gRPC endpoints take no path or query"));
+    }
+    let port = uri.port_u16().unwrap_or(default_port);
+    Ok(format!(
+        "{}://{host}:{port}",
+        uri.scheme_str().unwrap_or("https")
+    ))
+}
+
+// Normalizes an endpoint, interning the completed URL if it differs, since clients keep their endpoint for their whole lifetime
+pub(crate) fn normalized(endpoint: &'static str) -> JitoClientResult<&'static str> {
+    let normalized = normalize_endpoint(endpoint)?;
+    Ok(if normalized == endpoint {
+        endpoint
+    } else {
+        intern(normalized)
+    })
+}
+
+// Returns a `'static` copy of the endpoint, leaking each distinct endpoint once however many clients are built for it
+pub(crate) fn intern(endpoint: String) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    match interned.get(endpoint.as_str()) {
+        Some(endpoint) => endpoint,
+        None => {
+            let endpoint: &'static str = Box::leak(endpoint.into_boxed_str());
+            interned.insert(endpoint);
+            endpoint
+        }
+    }
+}
+
+/// Settings of a connection to a Jito service: timeouts, TLS, proxy, name resolution, keep-alive and channel limits.
+///
+/// Shared by every client of the crate, and public so clients of other Jito services, such as the auth or shredstream services,
+/// connect the same way as the block engine client. `JitoClientBuilder` sets the same options for the block engine.
+///
+/// # Examples
+/// ```no_run
+/// # use jito_grpc_client::{auth::Authenticator, errors::JitoClientResult, grpc::auth::Role, transport::{ConnectOptions, normalize_endpoint}};
+/// # use solana_keypair::Keypair;
+/// # use std::{sync::Arc, time::Duration};
+/// # use tonic::transport::Endpoint;
+/// # async fn example(keypair: Arc<Keypair>) -> JitoClientResult<()> {
+/// let endpoint = normalize_endpoint("ny.mainnet.block-engine.jito.wtf")?;
+/// let channel = ConnectOptions::new()
+///     .timeout(Duration::from_secs(5))
+///     .keep_alive(Duration::from_secs(10))
+///     .connect(Endpoint::from_shared(endpoint)?)
+///     .await?;
+/// let authenticator = Authenticator::connect(channel, keypair, Role::Searcher).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectOptions {
+    pub(crate) connect_timeout: Duration,
+    pub(crate) request_timeout: Duration,
+    pub(crate) tls_roots: TlsRoots,
+    pub(crate) ca_certificates: Vec<Certificate>,
+    pub(crate) domain_name: Option<String>,
+    pub(crate) proxy: Option<Proxy>,
+    pub(crate) resolver: Option<Resolver>,
+    pub(crate) ip_preference: IpPreference,
+    pub(crate) keep_alive: Option<Duration>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) limits: ChannelLimits,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_TIMEOUT,
+            request_timeout: DEFAULT_TIMEOUT,
+            tls_roots: TlsRoots::default(),
+            ca_certificates: Vec::new(),
+            domain_name: None,
+            proxy: None,
+            resolver: None,
+            ip_preference: IpPreference::default(),
+            keep_alive: None,
+            user_agent: None,
+            limits: ChannelLimits::default(),
+        }
+    }
+}
+
+impl ConnectOptions {
+    /// Creates options with the default settings: 2 second timeouts, the operating system's TLS roots, a direct connection and no channel limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets both the connect timeout and the request timeout.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.connect_timeout(timeout).request_timeout(timeout)
+    }
+
+    /// Sets the time allowed to establish the connection, including the TLS handshake. Defaults to 2 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the deadline of each request. Defaults to 2 seconds.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Sets where the TLS root certificates come from. Defaults to the operating system's certificate store.
+    pub fn tls_roots(mut self, tls_roots: TlsRoots) -> Self {
+        self.tls_roots = tls_roots;
+        self
+    }
+
+    /// Trusts an additional CA certificate, given in PEM format, on top of the selected TLS roots.
+    pub fn ca_certificate(mut self, pem: impl AsRef<[u8]>) -> Self {
+        self.ca_certificates.push(Certificate::from_pem(pem));
+        self
+    }
+
+    /// Overrides the domain name used for SNI and to verify the server certificate, which otherwise is the endpoint's host.
+    pub fn tls_domain_name(mut self, domain_name: impl Into<String>) -> Self {
+        self.domain_name = Some(domain_name.into());
+        self
+    }
+
+    /// Tunnels the connection through an HTTP CONNECT or SOCKS5 proxy.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets how host names are resolved. Ignored when a proxy is set.
+    pub fn resolver(mut self, resolver: Resolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Sets which IP address family to prefer when a host resolves to both. Ignored when a proxy is set.
+    pub fn ip_preference(mut self, preference: IpPreference) -> Self {
+        self.ip_preference = preference;
+        self
+    }
+
+    /// Appends `agent` to the `user-agent` header identifying the crate and its version, see `JitoClientBuilder::user_agent`.
+    pub fn user_agent(mut self, agent: impl Into<String>) -> Self {
+        self.user_agent = Some(agent.into());
+        self
+    }
+
+    /// Keeps an idle connection alive with HTTP/2 PINGs and TCP keep-alive probes every `interval`, see `JitoClientBuilder::keep_alive`.
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Bounds the resources the channel consumes, see `ChannelLimits`.
+    pub fn channel_limits(mut self, limits: ChannelLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Timeout given by the `new` style constructors, defaulting to 2 seconds
+    pub(crate) fn with_timeout(timeout: Option<Duration>) -> Self {
+        let timeout = timeout.unwrap_or(DEFAULT_TIMEOUT);
+        Self {
+            connect_timeout: timeout,
+            request_timeout: timeout,
+            ..Self::default()
+        }
+    }
+
+    fn tls_config(&self) -> ClientTlsConfig {
+        let mut config =
+            ClientTlsConfig::new().ca_certificates(self.ca_certificates.iter().cloned());
+        config = match self.tls_roots {
+            TlsRoots::Native => config.with_native_roots(),
+            TlsRoots::WebPki => config.with_webpki_roots(),
+            TlsRoots::Custom => config,
+        };
+        match &self.domain_name {
+            Some(domain_name) => config.domain_name(domain_name.clone()),
+            None => config,
+        }
+    }
+
+    // Applies the user agent, TLS, timeout, keep-alive and channel limit settings to the endpoint
+    pub(crate) fn configure(&self, endpoint: Endpoint) -> JitoClientResult<Endpoint> {
+        self.limits.validate()?;
+        let user_agent = match &self.user_agent {
+            Some(agent) => format!("{USER_AGENT} {agent}"),
+            None => USER_AGENT.to_string(),
+        };
+        let endpoint = endpoint
+            .user_agent(user_agent)
+            .map_err(|_| JitoClientError::InvalidMetadata("user-agent".to_string()))?
+            .tls_config(self.tls_config())?
+            .tcp_nodelay(true)
+            .timeout(self.request_timeout)
+            .connect_timeout(self.connect_timeout)
+            .buffer_size(self.limits.buffer_size);
+        let endpoint = match self.limits.concurrency_limit {
+            Some(limit) => endpoint.concurrency_limit(limit),
+            None => endpoint,
+        };
+        let endpoint = match self.limits.rate_limit {
+            Some((limit, period)) => endpoint.rate_limit(limit, period),
+            None => endpoint,
+        };
+        Ok(match self.keep_alive {
+            Some(interval) => endpoint
+                .tcp_keepalive(Some(interval))
+                .http2_keep_alive_interval(interval)
+                .keep_alive_timeout(self.request_timeout)
+                .keep_alive_while_idle(true),
+            None => endpoint,
+        })
+    }
+
+    /// Applies the settings to `endpoint`, then connects to it directly, through the proxy, or with the configured resolver.
+    ///
+    /// # Errors
+    /// This function will return a `ConfigError` if a channel limit is 0, an `InvalidMetadata` error if the user agent is not a valid header value,
+    /// or a transport error if the connection can't be established.
+    pub async fn connect(&self, endpoint: Endpoint) -> JitoClientResult<Channel> {
+        let endpoint = self.configure(endpoint)?;
+        let channel = match &self.proxy {
+            Some(proxy) => {
+                endpoint
+                    .connect_with_connector(ProxyConnector::new(proxy.clone()))
+                    .await?
+            }
+            None if self.resolver.is_some() || self.ip_preference != IpPreference::Any => {
+                let resolver = self.resolver.clone().unwrap_or_default();
+                endpoint
+                    .connect_with_connector(ResolvingConnector::new(resolver, self.ip_preference))
+                    .await?
+            }
+            None => endpoint.connect().await?,
+        };
+        Ok(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::searcher::{
+        GetTipAccountsRequest, searcher_service_client::SearcherServiceClient,
+    };
+    use crate::region::NodeRegion;
+    use crate::testing::MockBlockEngine;
+
+    #[test]
+    fn endpoints_are_normalized_or_rejected() {
+        let normalize = |endpoint| normalize_endpoint(endpoint).map_err(|e| e.to_string());
+        assert_eq!(
+            normalize(" ny.mainnet.block-engine.jito.wtf ").unwrap(),
+            "https://ny.mainnet.block-engine.jito.wtf:443"
+        );
+        assert_eq!(
+            normalize("http://localhost").unwrap(),
+            "http://localhost:80"
+        );
+        assert_eq!(
+            normalize("https://[::1]:8443/").unwrap(),
+            "https://[::1]:8443"
+        );
+        assert_eq!(
+            normalized(NodeRegion::NY.endpoint()).unwrap(),
+            NodeRegion::NY.endpoint()
+        );
+        let bare = normalized("ny.mainnet.block-engine.jito.wtf").unwrap();
+        assert_eq!(bare, NodeRegion::NY.endpoint());
+        assert!(std::ptr::eq(
+            bare,
+            normalized("ny.mainnet.block-engine.jito.wtf").unwrap()
+        ));
+
+        assert!(normalize("").unwrap_err().contains("empty"));
+        assert!(
+            normalize("grpc://ny.mainnet.block-engine.jito.wtf")
+                .unwrap_err()
+                .contains("unsupported scheme")
+        );
+        assert!(
+            normalize("https://ny.mainnet.block-engine.jito.wtf/api/v1/bundles")
+                .unwrap_err()
+                .contains("no path")
+        );
+        assert!(
+            normalize("https://ny mainnet")
+                .unwrap_err()
+                .contains("not a valid URL")
+        );
+        assert!(matches!(
+            normalize_endpoint("https://:443"),
+            Err(JitoClientError::InvalidEndpoint { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn options_connect_clients_of_any_service() {
+        let mock = MockBlockEngine::start().await.unwrap();
+        let channel = ConnectOptions::new()
+            .timeout(Duration::from_secs(1))
+            .user_agent("shredstream-bot")
+            .connect(Endpoint::from_static(mock.endpoint()))
+            .await
+            .unwrap();
+        let response = SearcherServiceClient::new(channel)
+            .get_tip_accounts(GetTipAccountsRequest {})
+            .await
+            .unwrap();
+        assert!(!response.into_inner().accounts.is_empty());
+
+        let limits = ChannelLimits {
+            buffer_size: Some(0),
+            ..ChannelLimits::default()
+        };
+        let zero_buffer = ConnectOptions::new()
+            .channel_limits(limits)
+            .connect(Endpoint::from_static(mock.endpoint()))
+            .await;
+        assert!(matches!(zero_buffer, Err(JitoClientError::ConfigError(_))));
+    }
+}
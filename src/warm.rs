@@ -0,0 +1,165 @@
+use crate::health::{self, ChannelState};
+use crate::signing::{SearcherClient, SignerInterceptor};
+use crate::stats::LatencyStats;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time;
+
+// A connected channel to one block engine, kept open by the keeper
+#[derive(Clone)]
+pub(crate) struct WarmChannel {
+    pub(crate) endpoint: &'static str,
+    pub(crate) client: SearcherClient,
+}
+
+// Share of the preferred region's check round trip another region must beat it by to be preferred, so a client doesn't flap between close regions
+const SWITCH_MARGIN: f64 = 0.2;
+
+// Channels to the fastest regions, the client's own included, kept connected and authenticated by a background keeper.
+// Every `interval` the keeper sends each channel a signed `GetTipAccounts` and records the channel's state and the check's round trip in the
+// latency statistics. When re-evaluating, it then prefers the channel answering its checks fastest, which the client switches to.
+// The keeper stops when the channels are dropped
+pub(crate) struct WarmChannels {
+    channels: Vec<WarmChannel>,
+    // Index of the preferred channel, the client's own until the keeper finds a faster one
+    preferred: Arc<AtomicUsize>,
+    reevaluate: bool,
+    keeper: JoinHandle<()>,
+}
+
+impl WarmChannels {
+    // Spawns the keeper. Must be called within a tokio runtime
+    pub(crate) fn spawn(
+        channels: Vec<WarmChannel>,
+        signer: SignerInterceptor,
+        stats: LatencyStats,
+        interval: Duration,
+        reevaluate: bool,
+    ) -> Self {
+        let kept = channels.clone();
+        let preferred = Arc::new(AtomicUsize::new(0));
+        let preference = preferred.clone();
+        let keeper = tokio::spawn(async move {
+            let mut ticks = time::interval(interval);
+            ticks.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                let checks = kept.iter().map(|channel| {
+                    let mut client = channel.client.clone();
+                    let (signer, stats) = (&signer, &stats);
+                    async move {
+                        let (state, rtt, error) = health::check(&mut client, signer).await;
+                        if let Some(error) = error {
+                            debug!("Warm channel to {} is {state:?}: {error}", channel.endpoint);
+                        }
+                        stats.record_channel(channel.endpoint, state, rtt);
+                    }
+                });
+                futures::future::join_all(checks).await;
+                if reevaluate {
+                    prefer_fastest(&kept, &stats, &preference);
+                }
+            }
+        });
+        Self {
+            channels,
+            preferred,
+            reevaluate,
+            keeper,
+        }
+    }
+
+    // The channels, fastest region first
+    pub(crate) fn channels(&self) -> &[WarmChannel] {
+        &self.channels
+    }
+
+    // The channel the keeper prefers, if the region is re-evaluated
+    pub(crate) fn preferred(&self) -> Option<&WarmChannel> {
+        self.reevaluate
+            .then(|| self.channels.get(self.preferred.load(Ordering::Relaxed)))
+            .flatten()
+    }
+
+    // Makes the channel to `endpoint` the preferred one, such as after failing over to it, until the keeper finds a faster one
+    pub(crate) fn prefer(&self, endpoint: &str) {
+        if let Some(index) = self.channels.iter().position(|c| c.endpoint == endpoint) {
+            self.preferred.store(index, Ordering::Relaxed);
+        }
+    }
+}
+
+// Prefers the ready channel answering its checks fastest, if it beats the preferred channel by the switch margin or the preferred channel isn't ready.
+// Every channel is timed with the same signed GetTipAccounts, so the regions are compared like for like
+fn prefer_fastest(channels: &[WarmChannel], stats: &LatencyStats, preferred: &AtomicUsize) {
+    let round_trip = |channel: &WarmChannel| {
+        stats
+            .get(channel.endpoint)
+            .filter(|stats| stats.channel == Some(ChannelState::Ready))
+            .and_then(|stats| stats.check_rtt)
+    };
+    let fastest = channels
+        .iter()
+        .enumerate()
+        .filter_map(|(index, channel)| Some((index, round_trip(channel)?)))
+        .min_by_key(|(_, rtt)| *rtt);
+    let Some((fastest, rtt)) = fastest else {
+        return;
+    };
+    let current = preferred.load(Ordering::Relaxed);
+    let faster = match channels.get(current).and_then(round_trip) {
+        Some(current_rtt) => rtt < current_rtt.mul_f64(1.0 - SWITCH_MARGIN),
+        None => true,
+    };
+    if faster && fastest != current {
+        debug!(
+            "Preferring {} with a {rtt:?} check round trip",
+            channels[fastest].endpoint
+        );
+        preferred.store(fastest, Ordering::Relaxed);
+    }
+}
+
+impl Drop for WarmChannels {
+    fn drop(&mut self) {
+        self.keeper.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::transport::Channel;
+
+    #[tokio::test]
+    async fn faster_regions_are_preferred_past_the_margin() {
+        let channel = |endpoint| WarmChannel {
+            endpoint,
+            client: SignerInterceptor::default()
+                .client(Channel::from_static("http://127.0.0.1:1").connect_lazy()),
+        };
+        let channels = [channel("http://ny"), channel("http://fra")];
+        let (stats, preferred) = (LatencyStats::new(), AtomicUsize::new(0));
+        let check = |endpoint, state, millis| {
+            stats.record_channel(endpoint, state, Some(Duration::from_millis(millis)))
+        };
+
+        check("http://ny", ChannelState::Ready, 10);
+        check("http://fra", ChannelState::Ready, 9);
+        prefer_fastest(&channels, &stats, &preferred);
+        assert_eq!(preferred.load(Ordering::Relaxed), 0);
+
+        // Two checks at 5ms bring the average below 80% of 10ms
+        for _ in 0..2 {
+            check("http://fra", ChannelState::Ready, 5);
+        }
+        prefer_fastest(&channels, &stats, &preferred);
+        assert_eq!(preferred.load(Ordering::Relaxed), 1);
+
+        stats.record_channel("http://fra", ChannelState::Unreachable, None);
+        prefer_fastest(&channels, &stats, &preferred);
+        assert_eq!(preferred.load(Ordering::Relaxed), 0);
+    }
+}
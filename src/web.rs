@@ -0,0 +1,75 @@
+//! Searcher client over any gRPC transport, for environments where the tonic channel used by `JitoClient` isn't available.
+//!
+//! In the browser, built for wasm32 without the default `native` feature, `SearcherClient::web` sends over `tonic_web_wasm_client::Client`
+//! to a gRPC-web proxy in front of the block engine. Elsewhere, wrap any gRPC transport with `SearcherClient::new`.
+
+use crate::api::{NextLeader, TipAccounts};
+use crate::errors::JitoClientResult;
+use crate::grpc::{
+    bundle::Bundle,
+    searcher::{
+        GetTipAccountsRequest, NextScheduledLeaderRequest, SendBundleRequest,
+        searcher_service_client::SearcherServiceClient,
+    },
+};
+use solana_transaction::versioned::VersionedTransaction;
+use tonic::client::GrpcService;
+use tonic::codegen::{Body, Bytes, StdError};
+
+/// Submits bundles and queries leaders over a caller-provided gRPC transport.
+pub struct SearcherClient<T> {
+    client: SearcherServiceClient<T>,
+}
+
+impl<T> SearcherClient<T>
+where
+    T: GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    /// Creates a client that sends its requests over `transport`.
+    pub fn new(transport: T) -> Self {
+        Self {
+            client: SearcherServiceClient::new(transport),
+        }
+    }
+
+    /// Sends a bundle of transactions. See `JitoClient::send`.
+    pub async fn send(
+        &mut self,
+        transactions: &[VersionedTransaction],
+    ) -> JitoClientResult<String> {
+        let request = SendBundleRequest {
+            bundle: Some(Bundle::create(transactions)?),
+        };
+        let response = self.client.send_bundle(request).await?;
+        Ok(response.into_inner().uuid)
+    }
+
+    /// Returns the next scheduled leader connected to the block engine. See `JitoClient::get_next_leader`.
+    pub async fn get_next_leader(&mut self, regions: &[&str]) -> JitoClientResult<NextLeader> {
+        let request = NextScheduledLeaderRequest {
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+        };
+        let response = self.client.get_next_scheduled_leader(request).await?;
+        response.into_inner().try_into()
+    }
+
+    /// Returns the tip accounts. See `JitoClient::get_tip_accounts`.
+    pub async fn get_tip_accounts(&mut self) -> JitoClientResult<TipAccounts> {
+        let response = self
+            .client
+            .get_tip_accounts(GetTipAccountsRequest {})
+            .await?;
+        response.into_inner().try_into()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SearcherClient<tonic_web_wasm_client::Client> {
+    /// Creates a client sending gRPC-web requests from the browser to `base_url`, a gRPC-web proxy in front of the block engine.
+    pub fn web(base_url: impl Into<String>) -> Self {
+        Self::new(tonic_web_wasm_client::Client::new(base_url.into()))
+    }
+}